@@ -0,0 +1,59 @@
+use std::fmt::Display;
+
+/// Arithmetic operators used in expressions.
+/// - `Add`: Addition operator (`+`)
+/// - `Sub`: Subtraction operator (`-`)
+/// - `Mul`: Multiplication operator (`*`)
+/// - `Div`: Division operator (`/`)
+/// - `Rem`: Remainder operator (`%`)
+/// - `Concat`: String concatenation operator (`||`)
+///
+#[derive(Debug, Clone, PartialEq)]
+pub enum ArithmeticOperator {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+    Concat,
+}
+
+impl ArithmeticOperator {
+    /// Creates an `ArithmeticOperator` from its symbol.
+    ///
+    /// Returns `None` if the token is not an arithmetic operator.
+    ///
+    pub fn new_from_token(token: &str) -> Option<Self> {
+        match token {
+            "+" => Some(ArithmeticOperator::Add),
+            "-" => Some(ArithmeticOperator::Sub),
+            "*" => Some(ArithmeticOperator::Mul),
+            "/" => Some(ArithmeticOperator::Div),
+            "%" => Some(ArithmeticOperator::Rem),
+            "||" => Some(ArithmeticOperator::Concat),
+            _ => None,
+        }
+    }
+
+    /// Returns the precedence of the operator. Operators with higher precedence are applied first.
+    pub fn precedence(&self) -> u8 {
+        match self {
+            ArithmeticOperator::Mul | ArithmeticOperator::Div | ArithmeticOperator::Rem => 2,
+            ArithmeticOperator::Add | ArithmeticOperator::Sub | ArithmeticOperator::Concat => 1,
+        }
+    }
+}
+
+impl Display for ArithmeticOperator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let symbol = match self {
+            ArithmeticOperator::Add => "+",
+            ArithmeticOperator::Sub => "-",
+            ArithmeticOperator::Mul => "*",
+            ArithmeticOperator::Div => "/",
+            ArithmeticOperator::Rem => "%",
+            ArithmeticOperator::Concat => "||",
+        };
+        write!(f, "{}", symbol)
+    }
+}
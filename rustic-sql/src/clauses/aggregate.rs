@@ -0,0 +1,221 @@
+use crate::{errors::SqlError, lexer::format_identifier, register::Register, value::Value};
+use std::fmt::Display;
+
+/// Enum for the aggregate functions that can be used in a `SELECT` clause.
+///
+/// - `Count`: Number of registers, or of non-null values of a column.
+/// - `Sum`: Sum of the values of a column.
+/// - `Avg`: Average of the values of a column.
+/// - `Min`: Smallest value of a column.
+/// - `Max`: Largest value of a column.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub enum AggregateFunction {
+    Count,
+    Sum,
+    Avg,
+    Min,
+    Max,
+}
+
+impl AggregateFunction {
+    /// Creates an `AggregateFunction` from the name of the function.
+    pub fn new_from_token(token: &str) -> Option<Self> {
+        match token {
+            "COUNT" => Some(AggregateFunction::Count),
+            "SUM" => Some(AggregateFunction::Sum),
+            "AVG" => Some(AggregateFunction::Avg),
+            "MIN" => Some(AggregateFunction::Min),
+            "MAX" => Some(AggregateFunction::Max),
+            _ => None,
+        }
+    }
+}
+
+/// Struct that represents a call to an aggregate function, like `SUM(cantidad)`.
+///
+/// # Fields
+///
+/// * `function` - The aggregate function.
+/// * `column` - The column the function is applied to, or `None` for `COUNT(*)`.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct Aggregate {
+    pub function: AggregateFunction,
+    pub column: Option<String>,
+}
+
+impl Aggregate {
+    /// Applies the aggregate function to a group of registers and returns the result.
+    ///
+    /// `Null` values are ignored. If every value is `Null`, the result is `Null`, except for `COUNT` which is zero.
+    ///
+    /// Returns `SqlError::InvalidType` if `SUM` or `AVG` find a value that is not a number.
+    ///
+    /// # Arguments
+    ///
+    /// * `registers` - The registers of the group.
+    ///
+    pub fn apply(&self, registers: &[Register]) -> Result<Value, SqlError> {
+        let column = match &self.column {
+            Some(column) => column,
+            None => return Ok(Value::Integer(registers.len() as i64)),
+        };
+
+        let mut values = Vec::new();
+        for register in registers {
+            let value = register.0.get(column).ok_or(SqlError::InvalidColumn)?;
+            if !value.is_null() {
+                values.push(value);
+            }
+        }
+
+        match self.function {
+            AggregateFunction::Count => Ok(Value::Integer(values.len() as i64)),
+            AggregateFunction::Sum => sum(&values),
+            AggregateFunction::Avg if values.is_empty() => Ok(Value::Null),
+            AggregateFunction::Avg => match sum(&values)? {
+                Value::Integer(total) => Value::Float(total as f64),
+                total => total,
+            }
+            .div(&Value::Integer(values.len() as i64)),
+            AggregateFunction::Min => Ok(values
+                .into_iter()
+                .min_by(|a, b| a.sort_cmp(b))
+                .cloned()
+                .unwrap_or(Value::Null)),
+            AggregateFunction::Max => Ok(values
+                .into_iter()
+                .max_by(|a, b| a.sort_cmp(b))
+                .cloned()
+                .unwrap_or(Value::Null)),
+        }
+    }
+}
+
+fn sum(values: &[&Value]) -> Result<Value, SqlError> {
+    let mut total = Value::Null;
+    for value in values {
+        let value = value.typed();
+        if !matches!(value, Value::Integer(_) | Value::Float(_)) {
+            return Err(SqlError::InvalidType);
+        }
+        total = match total {
+            Value::Null => value,
+            total => total.add(&value)?,
+        };
+    }
+    Ok(total)
+}
+
+impl Display for AggregateFunction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AggregateFunction::Count => write!(f, "COUNT"),
+            AggregateFunction::Sum => write!(f, "SUM"),
+            AggregateFunction::Avg => write!(f, "AVG"),
+            AggregateFunction::Min => write!(f, "MIN"),
+            AggregateFunction::Max => write!(f, "MAX"),
+        }
+    }
+}
+
+/// Writes the aggregate as in a query, like `SUM(cantidad)`, which is also the name of its value in the aggregated registers.
+impl Display for Aggregate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.column {
+            Some(column) => write!(f, "{}({})", self.function, format_identifier(column)),
+            None => write!(f, "{}(*)", self.function),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Aggregate, AggregateFunction};
+    use crate::{errors::SqlError, register::Register, value::Value};
+    use std::collections::HashMap;
+
+    fn registers(values: Vec<Value>) -> Vec<Register> {
+        values
+            .into_iter()
+            .map(|v| Register(HashMap::from([(String::from("cantidad"), v)])))
+            .collect()
+    }
+
+    fn aggregate(function: AggregateFunction, column: Option<&str>) -> Aggregate {
+        Aggregate {
+            function,
+            column: column.map(|c| c.to_string()),
+        }
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(
+            aggregate(AggregateFunction::Count, None).to_string(),
+            "COUNT(*)"
+        );
+        assert_eq!(
+            aggregate(AggregateFunction::Max, Some("o.cantidad")).to_string(),
+            "MAX(o.cantidad)"
+        );
+        assert_eq!(
+            aggregate(AggregateFunction::Sum, Some("precio final")).to_string(),
+            "SUM(\"precio final\")"
+        );
+    }
+
+    #[test]
+    fn apply_aggregates() {
+        let registers = registers(vec![Value::Integer(1), Value::Null, Value::Integer(4)]);
+        let apply = |function: AggregateFunction, column: Option<&str>| {
+            aggregate(function, column).apply(&registers)
+        };
+
+        assert_eq!(apply(AggregateFunction::Count, None), Ok(Value::Integer(3)));
+        assert_eq!(
+            apply(AggregateFunction::Count, Some("cantidad")),
+            Ok(Value::Integer(2))
+        );
+        assert_eq!(
+            apply(AggregateFunction::Sum, Some("cantidad")),
+            Ok(Value::Integer(5))
+        );
+        assert_eq!(
+            apply(AggregateFunction::Avg, Some("cantidad")),
+            Ok(Value::Float(2.5))
+        );
+        assert_eq!(
+            apply(AggregateFunction::Min, Some("cantidad")),
+            Ok(Value::Integer(1))
+        );
+        assert_eq!(
+            apply(AggregateFunction::Max, Some("cantidad")),
+            Ok(Value::Integer(4))
+        );
+    }
+
+    #[test]
+    fn apply_to_empty_and_text() {
+        let empty = registers(vec![]);
+        assert_eq!(
+            aggregate(AggregateFunction::Sum, Some("cantidad")).apply(&empty),
+            Ok(Value::Null)
+        );
+        assert_eq!(
+            aggregate(AggregateFunction::Avg, Some("cantidad")).apply(&empty),
+            Ok(Value::Null)
+        );
+
+        let texts = registers(vec![Value::Text(String::from("a"))]);
+        assert_eq!(
+            aggregate(AggregateFunction::Sum, Some("cantidad")).apply(&texts),
+            Err(SqlError::InvalidType)
+        );
+        assert_eq!(
+            aggregate(AggregateFunction::Max, Some("cantidad")).apply(&texts),
+            Ok(Value::Text(String::from("a")))
+        );
+    }
+}
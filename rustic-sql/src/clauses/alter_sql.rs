@@ -0,0 +1,340 @@
+use crate::{
+    csv::Csv,
+    errors::SqlError,
+    lexer::format_identifier,
+    register::Register,
+    schema::{ColumnDef, Schema},
+    table::Table,
+    utils::{find_file_in_folder, table_to_csv},
+    value::Value,
+    wal::Operation,
+};
+use std::{fmt::Display, fs::File, io::BufReader, path::Path};
+
+/// Enum for the changes that an `ALTER TABLE` clause can make to a table.
+///
+/// - `AddColumn`: Adds a column at the end of the table, optionally with a declaration of its type.
+/// - `DropColumn`: Removes a column and its values.
+/// - `RenameColumn`: Changes the name of a column.
+/// - `RenameTo`: Changes the name of the table.
+///
+#[derive(Debug, PartialEq)]
+pub enum AlterAction {
+    AddColumn {
+        name: String,
+        definition: Option<ColumnDef>,
+    },
+    DropColumn(String),
+    RenameColumn {
+        from: String,
+        to: String,
+    },
+    RenameTo(String),
+}
+
+/// Struct that represents the `ALTER TABLE` SQL clause.
+/// The `ALTER TABLE` clause is used to change the columns or the name of a table.
+///
+/// # Fields
+///
+/// * `table_name` - The name of the table to change.
+/// * `action` - The change to make to the table.
+///
+#[derive(Debug, PartialEq)]
+pub struct Alter {
+    pub table_name: String,
+    pub action: AlterAction,
+}
+
+impl Alter {
+    /// Applies the change to the table in the given folder.
+    ///
+    /// Column changes rewrite the header and every register of the table, and update its schema if it has one.
+    /// Renaming the table renames its file and its schema file.
+    /// The changes to both files are made by one write-ahead log operation, so a crash can't leave them out of sync.
+    ///
+    /// # Arguments
+    ///
+    /// * `folder_path` - A string slice that contains the path to the folder where the table is located.
+    /// * `csv` - The codec of the table file.
+    ///
+    pub fn apply_to_folder(&self, folder_path: &str, csv: &Csv) -> Result<(), SqlError> {
+        let file_name = self.table_name.to_string() + ".csv";
+        if !find_file_in_folder(folder_path, &file_name) {
+            return Err(SqlError::TableNotFound(self.table_name.to_string()));
+        }
+
+        if let AlterAction::RenameTo(new_name) = &self.action {
+            return self.rename_table(folder_path, new_name);
+        }
+
+        let file =
+            File::open(folder_path.to_string() + "/" + &file_name).map_err(SqlError::from)?;
+        let mut table = self.read_table(BufReader::new(file), csv)?;
+        let mut schema = Schema::load(folder_path, &self.table_name)?;
+
+        self.apply_to_table(&mut table, schema.as_mut())?;
+
+        let lines = table_to_csv(&table, &table.columns, csv)?;
+        let mut operation = Operation::start(folder_path)?;
+        operation.replace(&self.table_name, &lines)?;
+        if let Some(schema) = schema {
+            schema.save(&mut operation, &self.table_name)?;
+        }
+        operation.commit()
+    }
+
+    /// Applies a column change to a table and its schema, if it has one.
+    ///
+    /// # Arguments
+    ///
+    /// * `table` - The table to change.
+    /// * `schema` - The schema of the table, if it has one.
+    ///
+    pub fn apply_to_table(
+        &self,
+        table: &mut Table,
+        schema: Option<&mut Schema>,
+    ) -> Result<(), SqlError> {
+        match &self.action {
+            AlterAction::AddColumn { name, definition } => {
+                if table.columns.contains(name) {
+                    return Err(SqlError::InvalidColumn);
+                }
+                let value = match definition {
+                    Some(definition) => {
+                        let default = definition.default.clone().unwrap_or(Value::Null);
+                        if table.registers.is_empty() {
+                            default
+                        } else {
+                            definition.validate(default)?
+                        }
+                    }
+                    None => Value::Null,
+                };
+                if let Some(schema) = schema {
+                    schema
+                        .columns
+                        .push(definition.clone().ok_or(SqlError::InvalidSyntax)?);
+                }
+
+                table.columns.push(name.to_string());
+                for register in &mut table.registers {
+                    register.0.insert(name.to_string(), value.clone());
+                }
+            }
+            AlterAction::DropColumn(name) => {
+                if !table.columns.contains(name) {
+                    return Err(SqlError::ColumnNotFound(name.to_string()));
+                }
+                if table.columns.len() == 1 {
+                    return Err(SqlError::InvalidColumn);
+                }
+                if let Some(schema) = schema {
+                    schema.columns.retain(|c| &c.name != name);
+                }
+
+                table.columns.retain(|c| c != name);
+                for register in &mut table.registers {
+                    register.0.remove(name);
+                }
+            }
+            AlterAction::RenameColumn { from, to } => {
+                if !table.columns.contains(from) {
+                    return Err(SqlError::ColumnNotFound(from.to_string()));
+                }
+                if table.columns.contains(to) {
+                    return Err(SqlError::InvalidColumn);
+                }
+                if let Some(schema) = schema {
+                    for column in schema.columns.iter_mut().filter(|c| &c.name == from) {
+                        column.name = to.to_string();
+                    }
+                }
+
+                for column in table.columns.iter_mut().filter(|c| *c == from) {
+                    *column = to.to_string();
+                }
+                for register in &mut table.registers {
+                    if let Some(value) = register.0.remove(from) {
+                        register.0.insert(to.to_string(), value);
+                    }
+                }
+            }
+            AlterAction::RenameTo(_) => return Err(SqlError::InvalidSyntax),
+        }
+        Ok(())
+    }
+
+    fn rename_table(&self, folder_path: &str, new_name: &str) -> Result<(), SqlError> {
+        if find_file_in_folder(folder_path, &(new_name.to_string() + ".csv")) {
+            return Err(SqlError::InvalidTable);
+        }
+
+        let mut operation = Operation::start(folder_path)?;
+        operation.rename_file(
+            &(self.table_name.to_string() + ".csv"),
+            &(new_name.to_string() + ".csv"),
+        );
+        if Path::new(&Schema::path(folder_path, &self.table_name)).is_file() {
+            operation.rename_file(
+                &Schema::file_name(&self.table_name),
+                &Schema::file_name(new_name),
+            );
+        }
+        operation.commit()
+    }
+
+    fn read_table(&self, reader: BufReader<File>, csv: &Csv) -> Result<Table, SqlError> {
+        let mut table = Table::new();
+
+        let mut records = csv.records(reader);
+        table.columns = records.header()?;
+
+        for record in records {
+            let fields = record?;
+            table
+                .registers
+                .push(Register::from_fields(&table.columns, &fields));
+        }
+        Ok(table)
+    }
+}
+
+impl Display for Alter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "ALTER TABLE {} ", format_identifier(&self.table_name))?;
+        match &self.action {
+            AlterAction::AddColumn {
+                definition: Some(definition),
+                ..
+            } => write!(f, "ADD COLUMN {}", definition),
+            AlterAction::AddColumn { name, .. } => {
+                write!(f, "ADD COLUMN {}", format_identifier(name))
+            }
+            AlterAction::DropColumn(name) => write!(f, "DROP COLUMN {}", format_identifier(name)),
+            AlterAction::RenameColumn { from, to } => write!(
+                f,
+                "RENAME COLUMN {} TO {}",
+                format_identifier(from),
+                format_identifier(to)
+            ),
+            AlterAction::RenameTo(name) => write!(f, "RENAME TO {}", format_identifier(name)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Alter;
+    use crate::{
+        csv::Csv,
+        errors::SqlError,
+        parser::{parse, Statement},
+        utils::temp_folder,
+    };
+    use std::fs;
+
+    fn parse_alter(query: &str) -> Alter {
+        match parse(query) {
+            Ok(Statement::Alter(alter)) => alter,
+            other => panic!("{:?}", other),
+        }
+    }
+
+    #[test]
+    fn add_drop_and_rename_columns() {
+        let folder = temp_folder("alter_columns", &["testing"]);
+        let path = folder.clone() + "/testing.csv";
+
+        let alter = parse_alter("ALTER TABLE testing ADD COLUMN activo BOOLEAN DEFAULT TRUE");
+        alter.apply_to_folder(&folder, &Csv::default()).unwrap();
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "nombre,apellido,edad,activo\nJuan,Pérez,30,TRUE\nAna,López,18,TRUE\nCarlos,Gómez,40,TRUE\n"
+        );
+
+        let alter = parse_alter("ALTER TABLE testing DROP COLUMN apellido");
+        alter.apply_to_folder(&folder, &Csv::default()).unwrap();
+        let alter = parse_alter("ALTER TABLE testing RENAME COLUMN edad TO anios");
+        alter.apply_to_folder(&folder, &Csv::default()).unwrap();
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "nombre,anios,activo\nJuan,30,TRUE\nAna,18,TRUE\nCarlos,40,TRUE\n"
+        );
+
+        let alter = parse_alter("ALTER TABLE testing DROP COLUMN edad");
+        assert_eq!(
+            alter.apply_to_folder(&folder, &Csv::default()),
+            Err(SqlError::ColumnNotFound(String::from("edad")))
+        );
+
+        let alter = parse_alter("ALTER TABLE testing ADD nombre");
+        assert_eq!(
+            alter.apply_to_folder(&folder, &Csv::default()),
+            Err(SqlError::InvalidColumn)
+        );
+
+        let alter = parse_alter("ALTER TABLE testing ADD id INT NOT NULL");
+        assert_eq!(
+            alter.apply_to_folder(&folder, &Csv::default()),
+            Err(SqlError::InvalidValue)
+        );
+
+        fs::remove_dir_all(&folder).unwrap();
+    }
+
+    #[test]
+    fn rename_table() {
+        let folder = temp_folder("alter_rename", &["testing"]);
+        fs::write(
+            folder.clone() + "/testing.schema",
+            "nombre TEXT\napellido TEXT\nedad INTEGER\n",
+        )
+        .unwrap();
+
+        let alter = parse_alter("ALTER TABLE testing RENAME TO personas");
+        alter.apply_to_folder(&folder, &Csv::default()).unwrap();
+
+        assert!(fs::metadata(folder.clone() + "/personas.csv").is_ok());
+        assert!(fs::metadata(folder.clone() + "/personas.schema").is_ok());
+        assert!(fs::metadata(folder.clone() + "/testing.csv").is_err());
+        assert_eq!(
+            alter.apply_to_folder(&folder, &Csv::default()),
+            Err(SqlError::TableNotFound(String::from("testing")))
+        );
+
+        fs::remove_dir_all(&folder).unwrap();
+    }
+
+    #[test]
+    fn schema_is_updated() {
+        let folder = temp_folder("alter_schema", &["testing"]);
+        let schema_path = folder.clone() + "/testing.schema";
+        fs::write(&schema_path, "nombre TEXT\napellido TEXT\nedad INTEGER\n").unwrap();
+
+        let alter = parse_alter("ALTER TABLE testing ADD activo");
+        assert_eq!(
+            alter.apply_to_folder(&folder, &Csv::default()),
+            Err(SqlError::InvalidSyntax)
+        );
+
+        let alter = parse_alter("ALTER TABLE testing ADD COLUMN altura FLOAT");
+        alter.apply_to_folder(&folder, &Csv::default()).unwrap();
+        let alter = parse_alter("ALTER TABLE testing RENAME COLUMN edad TO anios");
+        alter.apply_to_folder(&folder, &Csv::default()).unwrap();
+        let alter = parse_alter("ALTER TABLE testing DROP COLUMN apellido");
+        alter.apply_to_folder(&folder, &Csv::default()).unwrap();
+
+        assert_eq!(
+            fs::read_to_string(&schema_path).unwrap(),
+            "nombre TEXT\nanios INTEGER\naltura FLOAT\n"
+        );
+        assert_eq!(
+            fs::read_to_string(folder.clone() + "/testing.csv").unwrap(),
+            "nombre,anios,altura\nJuan,30,\nAna,18,\nCarlos,40,\n"
+        );
+
+        fs::remove_dir_all(&folder).unwrap();
+    }
+}
@@ -1,11 +1,23 @@
+use super::{
+    aggregate::Aggregate,
+    expression::{parse_constant, Expression},
+};
 use crate::{
-    errors::SqlError, logical_operator::LogicalOperator, operator::Operator, utils::is_number,
+    errors::SqlError,
+    lexer::{expect_punctuation, unexpected, Token, TokenKind},
+    logical_operator::LogicalOperator,
+    operator::Operator,
+    value::Value,
 };
-use std::collections::HashMap;
+use std::{cmp::Ordering, collections::HashMap, fmt::Display};
 
 /// Enum for the conditions used in the `WHERE` clause.
 ///
 /// - `Simple`: Simple condition with a field, operator and value.
+/// - `Comparison`: Condition that compares two expressions, like `precio > costo` or `cantidad * 2 = 4`.
+/// - `In`: Condition that checks if a field is (or is not) one of a list of values, as in `IN (1, 2)`.
+/// - `Between`: Condition that checks if a field is (or is not) between two values, both included.
+/// - `IsNull`: Condition that checks if a field is (or is not) `Null`.
 /// - `Complex`: Complex condition with a left condition, logical operator and right condition.
 ///
 #[derive(Debug, PartialEq)]
@@ -13,7 +25,27 @@ pub enum Condition {
     Simple {
         field: String,
         operator: Operator,
-        value: String,
+        value: Value,
+    },
+    Comparison {
+        left: Expression,
+        operator: Operator,
+        right: Expression,
+    },
+    In {
+        field: String,
+        values: Vec<Value>,
+        negated: bool,
+    },
+    Between {
+        field: String,
+        low: Value,
+        high: Value,
+        negated: bool,
+    },
+    IsNull {
+        field: String,
+        negated: bool,
     },
     Complex {
         left: Option<Box<Condition>>, // Opcional para el caso de 'Not'
@@ -23,63 +55,155 @@ pub enum Condition {
 }
 
 impl Condition {
-    /// Creates a new `Condition` with a simple condition from tokens.
+    /// Creates a new `Condition` with a simple condition from the tokens read by the lexer.
     ///
     /// # Arguments
     ///
-    /// * `tokens` - A slice of `&str` with the tokens of the condition.
+    /// * `tokens` - The tokens read by `lexer::tokenize`.
     /// * `pos` - A mutable reference to `usize` with the position of the tokens.
     ///
     /// The tokens must be in the following order: `field`, `operator`, `value`.
+    /// The field and the value can also be expressions, like `cantidad * 2` or another column.
+    ///
+    /// The condition can also be `field [NOT] IN (values)`, `field [NOT] BETWEEN low AND high`, `field [NOT] LIKE pattern` or `field IS [NOT] NULL`.
+    ///
+    /// Returns `SqlError::UnexpectedToken`, with its offset, for the first token that doesn't follow this syntax.
     ///
     /// # Examples
     ///
     /// ```
-    /// let tokens = vec!["age", ">", "18"];
-    /// let pos = 0;
+    /// # use rustic_sql::Value;
+    /// # use rustic_sql::clauses::condition::Condition;
+    /// # use rustic_sql::lexer;
+    /// # use rustic_sql::operator::Operator;
+    /// let tokens = lexer::tokenize("age > 18").unwrap();
+    /// let mut pos = 0;
     /// let condition = Condition::new_simple_from_tokens(&tokens, &mut pos).unwrap();
     /// assert_eq!(condition,
     ///     Condition::Simple {
     ///         field: String::from("age"),
     ///         operator: Operator::Greater,
-    ///         value: String::from("18")
+    ///         value: Value::Integer(18)
     ///     })
     ///
     /// ```
     ///
-    pub fn new_simple_from_tokens(tokens: &[&str], pos: &mut usize) -> Result<Self, SqlError> {
-        if let Some(field) = tokens.get(*pos) {
-            *pos += 1;
+    pub fn new_simple_from_tokens(tokens: &[Token], pos: &mut usize) -> Result<Self, SqlError> {
+        let start = *pos;
+        let left = Expression::new_from_tokens(tokens, pos)?;
+        let is_keyword =
+            |pos: usize, keyword: &str| tokens.get(pos).is_some_and(|t| t.is_keyword(keyword));
+
+        let negated = is_keyword(*pos, "NOT");
+        let at = if negated { *pos + 1 } else { *pos };
+        let keyword = ["IS", "IN", "BETWEEN", "LIKE"]
+            .into_iter()
+            .find(|keyword| is_keyword(at, keyword));
+        if let Some(keyword) = keyword {
+            // `NOT` goes after `IS`, as in `IS NOT NULL`.
+            if negated && keyword == "IS" {
+                return Err(unexpected(tokens, at));
+            }
+            let field = match left {
+                Expression::Column(field) => field,
+                _ => return Err(unexpected(tokens, start)),
+            };
+            *pos = at + 1;
+            return Condition::new_with_keyword(field, keyword, negated, tokens, pos);
+        }
 
-            if let Some(operator) = tokens.get(*pos) {
-                *pos += 1;
+        let operator = tokens
+            .get(*pos)
+            .filter(|t| t.kind == TokenKind::Operator)
+            .and_then(|t| Operator::new_from_token(&t.text))
+            .ok_or_else(|| unexpected(tokens, *pos))?;
+        *pos += 1;
+        let right = Expression::new_from_tokens(tokens, pos)?;
 
-                if let Some(value) = tokens.get(*pos) {
+        match (left, right) {
+            (Expression::Column(field), Expression::Literal(value)) => Ok(Condition::Simple {
+                field,
+                operator,
+                value,
+            }),
+            (left, right) => Ok(Condition::Comparison {
+                left,
+                operator,
+                right,
+            }),
+        }
+    }
+
+    fn new_with_keyword(
+        field: String,
+        keyword: &str,
+        negated: bool,
+        tokens: &[Token],
+        pos: &mut usize,
+    ) -> Result<Self, SqlError> {
+        let is_keyword =
+            |pos: usize, keyword: &str| tokens.get(pos).is_some_and(|t| t.is_keyword(keyword));
+
+        match keyword {
+            "IS" => {
+                let negated = is_keyword(*pos, "NOT");
+                if negated {
                     *pos += 1;
-                    Ok(Condition::new_simple(field, operator, value)?)
-                } else {
-                    Err(SqlError::InvalidSyntax)
                 }
-            } else {
-                Err(SqlError::InvalidSyntax)
+                if !is_keyword(*pos, "NULL") {
+                    return Err(unexpected(tokens, *pos));
+                }
+                *pos += 1;
+                Ok(Condition::IsNull { field, negated })
             }
-        } else {
-            Err(SqlError::InvalidSyntax)
+            "IN" => {
+                expect_punctuation(tokens, pos, '(')?;
+                let mut values = vec![parse_constant(tokens, pos)?];
+                while tokens.get(*pos).is_some_and(|t| t.is_punctuation(',')) {
+                    *pos += 1;
+                    values.push(parse_constant(tokens, pos)?);
+                }
+                expect_punctuation(tokens, pos, ')')?;
+                Ok(Condition::In {
+                    field,
+                    values,
+                    negated,
+                })
+            }
+            "BETWEEN" => {
+                let low = parse_constant(tokens, pos)?;
+                if !is_keyword(*pos, "AND") {
+                    return Err(unexpected(tokens, *pos));
+                }
+                *pos += 1;
+                let high = parse_constant(tokens, pos)?;
+                Ok(Condition::Between {
+                    field,
+                    low,
+                    high,
+                    negated,
+                })
+            }
+            _ => Ok(Condition::Simple {
+                field,
+                operator: if negated {
+                    Operator::NotLike
+                } else {
+                    Operator::Like
+                },
+                value: parse_constant(tokens, pos)?,
+            }),
         }
     }
 
-    fn new_simple(field: &str, operator: &str, value: &str) -> Result<Self, SqlError> {
-        let op = match operator {
-            "=" => Operator::Equal,
-            ">" => Operator::Greater,
-            "<" => Operator::Lesser,
-            _ => return Err(SqlError::InvalidSyntax),
-        };
+    /// Creates a new `Condition` with a simple condition from a field, the symbol of an operator and a literal value.
+    pub fn new_simple(field: &str, operator: &str, value: &str) -> Result<Self, SqlError> {
+        let op = Operator::new_from_token(operator).ok_or(SqlError::InvalidSyntax)?;
 
         Ok(Condition::Simple {
             field: field.to_string(),
             operator: op,
-            value: value.to_string(),
+            value: Value::from_literal(value),
         })
     }
 
@@ -94,15 +218,19 @@ impl Condition {
     /// # Examples
     ///
     /// ```
+    /// # use rustic_sql::Value;
+    /// # use rustic_sql::clauses::condition::Condition;
+    /// # use rustic_sql::logical_operator::LogicalOperator;
+    /// # use rustic_sql::operator::Operator;
     /// let left = Condition::Simple {
     ///     field: String::from("age"),
     ///     operator: Operator::Greater,
-    ///     value: String::from("18"),
+    ///     value: Value::Integer(18),
     /// };
     /// let right = Condition::Simple {
     ///     field: String::from("city"),
     ///     operator: Operator::Equal,
-    ///     value: String::from("Gaiman"),
+    ///     value: Value::Text(String::from("Gaiman")),
     /// };
     /// let complex = Condition::new_complex(Some(left), LogicalOperator::And, right);
     ///
@@ -111,13 +239,13 @@ impl Condition {
     ///         left: Some(Box::new(Condition::Simple {
     ///                     field: String::from("age"),
     ///                     operator: Operator::Greater,
-    ///                     value: String::from("18"),
+    ///                     value: Value::Integer(18),
     ///          })),
     ///         operator: LogicalOperator::And,
     ///         right: Box::new(Condition::Simple {
     ///                     field: String::from("city"),
     ///                     operator: Operator::Equal,
-    ///                     value: String::from("Gaiman"),
+    ///                     value: Value::Text(String::from("Gaiman")),
     ///          })
     /// })
     /// ```
@@ -135,70 +263,286 @@ impl Condition {
     }
 
     /// Executes the condition on the given register.
-    /// Returns a bool with the result of the condition.
+    /// Returns true only if the condition holds, so a condition that is unknown because of a `Null` value doesn't match the register.
+    ///
+    /// # Arguments
+    ///
+    /// * `register` - A reference to a `HashMap<String, Value>` with the register to evaluate.
+    ///
+    /// The values are compared taking their types into account, so `30 > 9` holds.
+    /// Comparing values of incompatible types is an error.
+    ///
+    pub fn execute(&self, register: &HashMap<String, Value>) -> Result<bool, SqlError> {
+        Ok(self.evaluate(register)? == Some(true))
+    }
+
+    /// Evaluates the condition on the given register with the three-valued logic of SQL.
+    ///
+    /// Returns `None` when the result is unknown, like in a comparison with a `Null` value.
+    /// `AND`, `OR` and `NOT` propagate the unknown results: `NOT` of an unknown is unknown, `FALSE AND` anything is false and `TRUE OR` anything is true.
     ///
     /// # Arguments
     ///
-    /// * `register` - A reference to a `HashMap<String, String>` with the register to evaluate.
+    /// * `register` - A reference to a `HashMap<String, Value>` with the register to evaluate.
     ///
-    pub fn execute(&self, register: &HashMap<String, String>) -> Result<bool, SqlError> {
-        let op_result: Result<bool, SqlError> = match &self {
+    pub fn evaluate(&self, register: &HashMap<String, Value>) -> Result<Option<bool>, SqlError> {
+        let field_value = |field: &String| {
+            register
+                .get(field)
+                .ok_or_else(|| SqlError::ColumnNotFound(field.to_string()))
+        };
+
+        match &self {
             Condition::Simple {
                 field,
                 operator,
                 value,
+            } => compare(field_value(field)?, operator, value),
+            Condition::Comparison {
+                left,
+                operator,
+                right,
             } => {
-                let y = value;
-                if let Some(x) = register.get(field) {
-                    if is_number(y) && !is_number(x) || !is_number(y) && is_number(x) {
-                        return Err(SqlError::InvalidSyntax);
-                    }
-                    match operator {
-                        Operator::Lesser => Ok(x < y),
-                        Operator::Greater => Ok(x > y),
-                        Operator::Equal => Ok(x == y),
+                let x = left.evaluate(register)?;
+                let y = right.evaluate(register)?;
+                compare(&x, operator, &y)
+            }
+            Condition::In {
+                field,
+                values,
+                negated,
+            } => {
+                let x = field_value(field)?;
+                if x.is_null() {
+                    return Ok(None);
+                }
+                if values.iter().any(|y| x.compare(y) == Some(Ordering::Equal)) {
+                    return Ok(Some(!negated));
+                }
+                // Without a match, `x IN (1, NULL)` is unknown, because `x` could be the `Null`.
+                if values.iter().any(|y| y.is_null()) {
+                    return Ok(None);
+                }
+                Ok(Some(*negated))
+            }
+            Condition::Between {
+                field,
+                low,
+                high,
+                negated,
+            } => {
+                let x = field_value(field)?;
+                if x.is_null() || low.is_null() || high.is_null() {
+                    return Ok(None);
+                }
+                // Values of types that can't be compared make the result unknown, like `Null` does.
+                match (x.compare(low), x.compare(high)) {
+                    (Some(from_low), Some(to_high)) => {
+                        let between = from_low != Ordering::Less && to_high != Ordering::Greater;
+                        Ok(Some(between != *negated))
                     }
-                } else {
-                    Err(SqlError::Error)
+                    _ => Ok(None),
                 }
             }
+            Condition::IsNull { field, negated } => {
+                Ok(Some(field_value(field)?.is_null() != *negated))
+            }
             Condition::Complex {
                 left,
                 operator,
                 right,
-            } => match operator {
-                LogicalOperator::Not => {
-                    let result = right.execute(register)?;
-                    Ok(!result)
+            } => match (operator, left) {
+                (LogicalOperator::Not, _) => Ok(right.evaluate(register)?.map(|result| !result)),
+                (LogicalOperator::Or, Some(left)) => {
+                    match (left.evaluate(register)?, right.evaluate(register)?) {
+                        (Some(true), _) | (_, Some(true)) => Ok(Some(true)),
+                        (Some(false), Some(false)) => Ok(Some(false)),
+                        _ => Ok(None),
+                    }
                 }
-                LogicalOperator::Or => {
-                    if let Some(left) = left {
-                        let left_result = left.execute(register)?;
-                        let right_result = right.execute(register)?;
-                        Ok(left_result || right_result)
-                    } else {
-                        Err(SqlError::Error)
+                (LogicalOperator::And, Some(left)) => {
+                    match (left.evaluate(register)?, right.evaluate(register)?) {
+                        (Some(false), _) | (_, Some(false)) => Ok(Some(false)),
+                        (Some(true), Some(true)) => Ok(Some(true)),
+                        _ => Ok(None),
                     }
                 }
-                LogicalOperator::And => {
-                    if let Some(left) = left {
-                        let left_result = left.execute(register)?;
-                        let right_result = right.execute(register)?;
-                        Ok(left_result && right_result)
+                _ => Err(SqlError::Error),
+            },
+        }
+    }
+
+    /// Returns the names of the fields compared in the condition.
+    pub fn fields(&self) -> Vec<&str> {
+        match self {
+            Condition::Comparison { left, right, .. } => {
+                let mut fields = left.columns();
+                fields.extend(right.columns());
+                fields
+            }
+            Condition::Simple { field, .. }
+            | Condition::In { field, .. }
+            | Condition::Between { field, .. }
+            | Condition::IsNull { field, .. } => vec![field.as_str()],
+            Condition::Complex { left, right, .. } => {
+                let mut fields = left.as_ref().map(|l| l.fields()).unwrap_or_default();
+                fields.extend(right.fields());
+                fields
+            }
+        }
+    }
+
+    /// Returns the aggregate functions used in the condition, like the ones of a `HAVING` clause.
+    pub fn aggregates(&self) -> Vec<&Aggregate> {
+        match self {
+            Condition::Comparison { left, right, .. } => {
+                let mut aggregates = left.aggregates();
+                aggregates.extend(right.aggregates());
+                aggregates
+            }
+            Condition::Simple { .. }
+            | Condition::In { .. }
+            | Condition::Between { .. }
+            | Condition::IsNull { .. } => Vec::new(),
+            Condition::Complex { left, right, .. } => {
+                let mut aggregates = left.as_ref().map(|l| l.aggregates()).unwrap_or_default();
+                aggregates.extend(right.aggregates());
+                aggregates
+            }
+        }
+    }
+
+    fn precedence(&self) -> u8 {
+        match self {
+            Condition::Complex { operator, .. } => operator.precedence(),
+            _ => u8::MAX,
+        }
+    }
+}
+
+impl Display for Condition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let literal = |value: &Value| Expression::Literal(value.clone());
+        let not = |negated: &bool| if *negated { "NOT " } else { "" };
+
+        match self {
+            Condition::Simple {
+                field,
+                operator,
+                value,
+            } => write!(f, "{} {} {}", field, operator, literal(value)),
+            Condition::Comparison {
+                left,
+                operator,
+                right,
+            } => write!(f, "{} {} {}", left, operator, right),
+            Condition::In {
+                field,
+                values,
+                negated,
+            } => {
+                let values: Vec<String> = values.iter().map(|v| literal(v).to_string()).collect();
+                write!(f, "{} {}IN ({})", field, not(negated), values.join(", "))
+            }
+            Condition::Between {
+                field,
+                low,
+                high,
+                negated,
+            } => write!(
+                f,
+                "{} {}BETWEEN {} AND {}",
+                field,
+                not(negated),
+                literal(low),
+                literal(high)
+            ),
+            Condition::IsNull { field, negated } => {
+                write!(f, "{} IS {}NULL", field, not(negated))
+            }
+            Condition::Complex {
+                left,
+                operator,
+                right,
+            } => {
+                if let Some(left) = left {
+                    if left.precedence() < operator.precedence() {
+                        write!(f, "({}) ", left)?;
                     } else {
-                        Err(SqlError::Error)
+                        write!(f, "{} ", left)?;
                     }
                 }
-            },
-        };
-        op_result
+                write!(f, "{} ", operator)?;
+                // `NOT NOT a` needs no parentheses, but `a AND (b AND c)` does to keep its grouping.
+                let binds_looser = match left {
+                    Some(_) => right.precedence() <= operator.precedence(),
+                    None => right.precedence() < operator.precedence(),
+                };
+                if binds_looser {
+                    write!(f, "({})", right)
+                } else {
+                    write!(f, "{}", right)
+                }
+            }
+        }
     }
 }
 
+/// Compares two values. Returns `None` if one of them is `Null` or if their types can't be compared, because the result is unknown.
+fn compare(x: &Value, operator: &Operator, y: &Value) -> Result<Option<bool>, SqlError> {
+    if x.is_null() || y.is_null() {
+        return Ok(None);
+    }
+    let result = match operator {
+        Operator::Like => Some(like(&x.to_string(), &y.to_string())),
+        Operator::NotLike => Some(!like(&x.to_string(), &y.to_string())),
+        _ => x.compare(y).map(|ordering| match operator {
+            Operator::Lesser => ordering == Ordering::Less,
+            Operator::LesserEqual => ordering != Ordering::Greater,
+            Operator::Greater => ordering == Ordering::Greater,
+            Operator::GreaterEqual => ordering != Ordering::Less,
+            Operator::Equal => ordering == Ordering::Equal,
+            _ => ordering != Ordering::Equal,
+        }),
+    };
+    Ok(result)
+}
+
+/// Returns true if the text matches the `LIKE` pattern, where `%` matches any text and `_` any single character.
+fn like(text: &str, pattern: &str) -> bool {
+    let text: Vec<char> = text.chars().collect();
+
+    // matches[j] is true if the pattern read so far matches the first `j` characters of the text.
+    let mut matches = vec![false; text.len() + 1];
+    matches[0] = true;
+
+    for p in pattern.chars() {
+        let mut next = vec![false; text.len() + 1];
+        if p == '%' {
+            next[0] = matches[0];
+        }
+        for j in 1..=text.len() {
+            next[j] = match p {
+                '%' => next[j - 1] || matches[j],
+                '_' => matches[j - 1],
+                c => matches[j - 1] && text[j - 1] == c,
+            };
+        }
+        matches = next;
+    }
+
+    matches[text.len()]
+}
+
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison)]
 mod tests {
     use super::Condition;
-    use crate::clauses::condition::{LogicalOperator, Operator};
+    use crate::{
+        clauses::condition::{LogicalOperator, Operator, Value},
+        errors::SqlError,
+        lexer::tokenize,
+    };
     use std::collections::HashMap;
 
     #[test]
@@ -209,14 +553,14 @@ mod tests {
             Condition::Simple {
                 field: String::from("age"),
                 operator: Operator::Greater,
-                value: String::from("18")
+                value: Value::Integer(18)
             }
         )
     }
 
     #[test]
     fn create_simple_from_tokens() {
-        let tokens = vec!["age", ">", "18"];
+        let tokens = tokenize("age > 18").unwrap();
         let mut pos = 0;
         let condition = Condition::new_simple_from_tokens(&tokens, &mut pos).unwrap();
 
@@ -225,7 +569,7 @@ mod tests {
             Condition::Simple {
                 field: String::from("age"),
                 operator: Operator::Greater,
-                value: String::from("18")
+                value: Value::Integer(18)
             }
         )
     }
@@ -235,13 +579,13 @@ mod tests {
         let left = Condition::Simple {
             field: String::from("age"),
             operator: Operator::Greater,
-            value: String::from("18"),
+            value: Value::Integer(18),
         };
 
         let right = Condition::Simple {
             field: String::from("city"),
             operator: Operator::Equal,
-            value: String::from("Gaiman"),
+            value: Value::Text(String::from("Gaiman")),
         };
 
         let complex = Condition::new_complex(Some(left), LogicalOperator::And, right);
@@ -252,13 +596,13 @@ mod tests {
                 left: Some(Box::new(Condition::Simple {
                     field: String::from("age"),
                     operator: Operator::Greater,
-                    value: String::from("18"),
+                    value: Value::Integer(18),
                 })),
                 operator: LogicalOperator::And,
                 right: Box::new(Condition::Simple {
                     field: String::from("city"),
                     operator: Operator::Equal,
-                    value: String::from("Gaiman"),
+                    value: Value::Text(String::from("Gaiman")),
                 })
             }
         )
@@ -269,7 +613,7 @@ mod tests {
         let right = Condition::Simple {
             field: String::from("name"),
             operator: Operator::Equal,
-            value: String::from("Alen"),
+            value: Value::Text(String::from("Alen")),
         };
 
         let complex = Condition::new_complex(None, LogicalOperator::Not, right);
@@ -282,7 +626,7 @@ mod tests {
                 right: Box::new(Condition::Simple {
                     field: String::from("name"),
                     operator: Operator::Equal,
-                    value: String::from("Alen"),
+                    value: Value::Text(String::from("Alen")),
                 })
             }
         )
@@ -291,20 +635,23 @@ mod tests {
     #[test]
     fn execute_simple() {
         let mut register = HashMap::new();
-        register.insert(String::from("name"), String::from("Alen"));
-        register.insert(String::from("lastname"), String::from("Davies"));
-        register.insert(String::from("age"), String::from("24"));
+        register.insert(String::from("name"), Value::Text(String::from("Alen")));
+        register.insert(
+            String::from("lastname"),
+            Value::Text(String::from("Davies")),
+        );
+        register.insert(String::from("age"), Value::Integer(24));
 
         let condition_true = Condition::Simple {
             field: String::from("age"),
             operator: Operator::Greater,
-            value: String::from("18"),
+            value: Value::Integer(18),
         };
 
         let condition_false = Condition::Simple {
             field: String::from("age"),
             operator: Operator::Greater,
-            value: String::from("40"),
+            value: Value::Integer(40),
         };
 
         let result_true = condition_true.execute(&register).unwrap();
@@ -315,22 +662,177 @@ mod tests {
         assert_eq!(result_false, false);
     }
 
+    #[test]
+    fn execute_numeric_comparison() {
+        let mut register = HashMap::new();
+        register.insert(String::from("age"), Value::Integer(30));
+        register.insert(String::from("height"), Value::Float(1.75));
+
+        let condition = Condition::new_simple("age", ">", "9").unwrap();
+        assert!(condition.execute(&register).unwrap());
+
+        let condition = Condition::new_simple("height", "<", "-1.5").unwrap();
+        assert!(!condition.execute(&register).unwrap());
+
+        let condition = Condition::new_simple("age", "=", "30.0").unwrap();
+        assert!(condition.execute(&register).unwrap());
+    }
+
+    #[test]
+    fn execute_incompatible_types() {
+        let mut register = HashMap::new();
+        register.insert(String::from("age"), Value::Integer(30));
+        register.insert(String::from("email"), Value::Null);
+
+        let condition = Condition::new_simple("age", ">", "Alen").unwrap();
+        assert_eq!(condition.evaluate(&register), Ok(None));
+        assert!(!condition.execute(&register).unwrap());
+
+        let condition = Condition::Between {
+            field: String::from("age"),
+            low: Value::Text(String::from("a")),
+            high: Value::Text(String::from("z")),
+            negated: true,
+        };
+        assert_eq!(condition.evaluate(&register), Ok(None));
+
+        let condition = Condition::new_simple("email", "=", "Alen").unwrap();
+        assert!(!condition.execute(&register).unwrap());
+    }
+
+    #[test]
+    fn execute_comparison_operators() {
+        let mut register = HashMap::new();
+        register.insert(String::from("age"), Value::Integer(30));
+
+        let holds = |operator: &str, value: &str| {
+            Condition::new_simple("age", operator, value)
+                .unwrap()
+                .execute(&register)
+                .unwrap()
+        };
+        assert!(holds(">=", "30"));
+        assert!(holds("<=", "30"));
+        assert!(!holds("<=", "29"));
+        assert!(holds("!=", "29"));
+        assert!(!holds("<>", "30"));
+        assert_eq!(
+            Condition::new_simple("age", "=>", "30"),
+            Err(SqlError::InvalidSyntax)
+        );
+    }
+
+    #[test]
+    fn execute_in_between_like_is_null() {
+        let mut register = HashMap::new();
+        register.insert(String::from("age"), Value::Integer(30));
+        register.insert(String::from("name"), Value::Text(String::from("Gómez")));
+        register.insert(String::from("email"), Value::Null);
+
+        let holds = |query: &str| {
+            let tokens = tokenize(query).unwrap();
+            let mut pos = 0;
+            let condition = Condition::new_simple_from_tokens(&tokens, &mut pos).unwrap();
+            assert_eq!(pos, tokens.len());
+            condition.execute(&register).unwrap()
+        };
+        assert!(holds("age IN (18, 30)"));
+        assert!(holds("name NOT IN ('López', 'Pérez')"));
+        assert!(!holds("age NOT IN (18, NULL)"));
+        assert!(holds("age BETWEEN 18 AND 30"));
+        assert!(!holds("age NOT BETWEEN 18 AND 30"));
+        assert!(holds("name LIKE 'G_m%'"));
+        assert!(!holds("name LIKE 'G%s'"));
+        assert!(holds("name NOT LIKE '%z_'"));
+        assert!(holds("age LIKE '3%'"));
+        assert!(holds("email IS NULL"));
+        assert!(!holds("email IS NOT NULL"));
+        assert!(!holds("email LIKE '%'"));
+    }
+
+    #[test]
+    fn parse_errors_with_offsets() {
+        let error = |query: &str| {
+            let tokens = tokenize(query).unwrap();
+            let mut pos = 0;
+            Condition::new_simple_from_tokens(&tokens, &mut pos).unwrap_err()
+        };
+        let unexpected = |token: &str, offset: usize| SqlError::UnexpectedToken {
+            token: token.to_string(),
+            offset: Some(offset),
+        };
+
+        assert_eq!(error("age IN 18"), unexpected("18", 7));
+        assert_eq!(error("age IN (18, )"), unexpected(")", 12));
+        assert_eq!(error("age BETWEEN 18 OR 30"), unexpected("OR", 15));
+        assert_eq!(error("age NOT IS NULL"), unexpected("IS", 8));
+        assert_eq!(error("age IS 18"), unexpected("18", 7));
+        assert_eq!(error("age + 1 IN (2)"), unexpected("age", 0));
+    }
+
+    #[test]
+    fn evaluate_three_valued_logic() {
+        let mut register = HashMap::new();
+        register.insert(String::from("age"), Value::Integer(30));
+        register.insert(String::from("email"), Value::Null);
+
+        let simple = |field: &str, value: Value| Condition::Simple {
+            field: String::from(field),
+            operator: Operator::Equal,
+            value,
+        };
+        let unknown = || simple("email", Value::Text(String::from("a@b.com")));
+        let truth = || simple("age", Value::Integer(30));
+        let falsity = || simple("age", Value::Integer(18));
+        let complex = |left: Option<Condition>, operator, right| {
+            Condition::new_complex(left, operator, right)
+        };
+
+        assert_eq!(unknown().evaluate(&register), Ok(None));
+        assert_eq!(simple("age", Value::Null).evaluate(&register), Ok(None));
+
+        let not_unknown = complex(None, LogicalOperator::Not, unknown());
+        assert_eq!(not_unknown.evaluate(&register), Ok(None));
+        assert_eq!(not_unknown.execute(&register), Ok(false));
+
+        let cases = [
+            (LogicalOperator::And, truth(), Ok(None)),
+            (LogicalOperator::And, falsity(), Ok(Some(false))),
+            (LogicalOperator::Or, truth(), Ok(Some(true))),
+            (LogicalOperator::Or, falsity(), Ok(None)),
+        ];
+        for (operator, known, expected) in cases {
+            let condition = complex(Some(unknown()), operator, known);
+            assert_eq!(condition.evaluate(&register), expected);
+        }
+
+        let mut pos = 0;
+        let tokens = tokenize("age IN (18, NULL)").unwrap();
+        let in_list = Condition::new_simple_from_tokens(&tokens, &mut pos).unwrap();
+        assert_eq!(in_list.evaluate(&register), Ok(None));
+        let not_in = complex(None, LogicalOperator::Not, in_list);
+        assert_eq!(not_in.execute(&register), Ok(false));
+    }
+
     #[test]
     fn execute_and() {
         let mut register = HashMap::new();
-        register.insert(String::from("name"), String::from("Alen"));
-        register.insert(String::from("lastname"), String::from("Davies"));
-        register.insert(String::from("age"), String::from("24"));
+        register.insert(String::from("name"), Value::Text(String::from("Alen")));
+        register.insert(
+            String::from("lastname"),
+            Value::Text(String::from("Davies")),
+        );
+        register.insert(String::from("age"), Value::Integer(24));
 
         let left = Condition::Simple {
             field: String::from("age"),
             operator: Operator::Greater,
-            value: String::from("18"),
+            value: Value::Integer(18),
         };
         let right = Condition::Simple {
             field: String::from("name"),
             operator: Operator::Equal,
-            value: String::from("Alen"),
+            value: Value::Text(String::from("Alen")),
         };
 
         let condition = Condition::Complex {
@@ -347,19 +849,22 @@ mod tests {
     #[test]
     fn execute_or() {
         let mut register = HashMap::new();
-        register.insert(String::from("name"), String::from("Alen"));
-        register.insert(String::from("lastname"), String::from("Davies"));
-        register.insert(String::from("age"), String::from("24"));
+        register.insert(String::from("name"), Value::Text(String::from("Alen")));
+        register.insert(
+            String::from("lastname"),
+            Value::Text(String::from("Davies")),
+        );
+        register.insert(String::from("age"), Value::Integer(24));
 
         let left = Condition::Simple {
             field: String::from("age"),
             operator: Operator::Greater,
-            value: String::from("40"),
+            value: Value::Integer(40),
         };
         let right = Condition::Simple {
             field: String::from("name"),
             operator: Operator::Equal,
-            value: String::from("Emily"),
+            value: Value::Text(String::from("Emily")),
         };
 
         let condition = Condition::Complex {
@@ -376,14 +881,17 @@ mod tests {
     #[test]
     fn execute_not() {
         let mut register = HashMap::new();
-        register.insert(String::from("name"), String::from("Alen"));
-        register.insert(String::from("lastname"), String::from("Davies"));
-        register.insert(String::from("age"), String::from("24"));
+        register.insert(String::from("name"), Value::Text(String::from("Alen")));
+        register.insert(
+            String::from("lastname"),
+            Value::Text(String::from("Davies")),
+        );
+        register.insert(String::from("age"), Value::Integer(24));
 
         let right = Condition::Simple {
             field: String::from("name"),
             operator: Operator::Equal,
-            value: String::from("Emily"),
+            value: Value::Text(String::from("Emily")),
         };
 
         let condition = Condition::Complex {
@@ -400,20 +908,23 @@ mod tests {
     #[test]
     fn execute_and_or() {
         let mut register = HashMap::new();
-        register.insert(String::from("name"), String::from("Alen"));
-        register.insert(String::from("lastname"), String::from("Davies"));
-        register.insert(String::from("age"), String::from("24"));
-        register.insert(String::from("city"), String::from("Gaiman"));
+        register.insert(String::from("name"), Value::Text(String::from("Alen")));
+        register.insert(
+            String::from("lastname"),
+            Value::Text(String::from("Davies")),
+        );
+        register.insert(String::from("age"), Value::Integer(24));
+        register.insert(String::from("city"), Value::Text(String::from("Gaiman")));
 
         let left = Condition::Simple {
             field: String::from("age"),
             operator: Operator::Greater,
-            value: String::from("40"),
+            value: Value::Integer(40),
         };
         let right1 = Condition::Simple {
             field: String::from("name"),
             operator: Operator::Equal,
-            value: String::from("Alen"),
+            value: Value::Text(String::from("Alen")),
         };
 
         let or = Condition::Complex {
@@ -425,7 +936,7 @@ mod tests {
         let right2 = Condition::Simple {
             field: String::from("city"),
             operator: Operator::Equal,
-            value: String::from("Trelew"),
+            value: Value::Text(String::from("Trelew")),
         };
 
         let and = Condition::Complex {
@@ -442,15 +953,18 @@ mod tests {
     #[test]
     fn execute_not_and() {
         let mut register = HashMap::new();
-        register.insert(String::from("name"), String::from("Alen"));
-        register.insert(String::from("lastname"), String::from("Davies"));
-        register.insert(String::from("age"), String::from("24"));
-        register.insert(String::from("city"), String::from("Gaiman"));
+        register.insert(String::from("name"), Value::Text(String::from("Alen")));
+        register.insert(
+            String::from("lastname"),
+            Value::Text(String::from("Davies")),
+        );
+        register.insert(String::from("age"), Value::Integer(24));
+        register.insert(String::from("city"), Value::Text(String::from("Gaiman")));
 
         let right1 = Condition::Simple {
             field: String::from("age"),
             operator: Operator::Greater,
-            value: String::from("40"),
+            value: Value::Integer(40),
         };
 
         let not = Condition::Complex {
@@ -462,7 +976,7 @@ mod tests {
         let right2 = Condition::Simple {
             field: String::from("city"),
             operator: Operator::Equal,
-            value: String::from("Gaiman"),
+            value: Value::Text(String::from("Gaiman")),
         };
 
         let and = Condition::Complex {
@@ -479,10 +993,13 @@ mod tests {
     #[test]
     fn execute_not_and_or_with_paren() {
         let mut register = HashMap::new();
-        register.insert(String::from("name"), String::from("Alen"));
-        register.insert(String::from("lastname"), String::from("Davies"));
-        register.insert(String::from("age"), String::from("24"));
-        register.insert(String::from("city"), String::from("Gaiman"));
+        register.insert(String::from("name"), Value::Text(String::from("Alen")));
+        register.insert(
+            String::from("lastname"),
+            Value::Text(String::from("Davies")),
+        );
+        register.insert(String::from("age"), Value::Integer(24));
+        register.insert(String::from("city"), Value::Text(String::from("Gaiman")));
 
         // NOT (city = Gaiman AND (age > 18 OR lastname = Davies))
 
@@ -493,20 +1010,20 @@ mod tests {
                 left: Some(Box::new(Condition::Simple {
                     field: String::from("city"),
                     operator: Operator::Equal,
-                    value: String::from("Gaiman"),
+                    value: Value::Text(String::from("Gaiman")),
                 })),
                 operator: LogicalOperator::And,
                 right: Box::new(Condition::Complex {
                     left: Some(Box::new(Condition::Simple {
                         field: String::from("age"),
                         operator: Operator::Greater,
-                        value: String::from("18"),
+                        value: Value::Integer(18),
                     })),
                     operator: LogicalOperator::Or,
                     right: Box::new(Condition::Simple {
                         field: String::from("lastname"),
                         operator: Operator::Equal,
-                        value: String::from("Davies"),
+                        value: Value::Text(String::from("Davies")),
                     }),
                 }),
             }),
@@ -521,10 +1038,13 @@ mod tests {
 
     fn execute_and_or_with_paren2() {
         let mut register = HashMap::new();
-        register.insert(String::from("name"), String::from("Alen"));
-        register.insert(String::from("lastname"), String::from("Davies"));
-        register.insert(String::from("age"), String::from("24"));
-        register.insert(String::from("city"), String::from("Gaiman"));
+        register.insert(String::from("name"), Value::Text(String::from("Alen")));
+        register.insert(
+            String::from("lastname"),
+            Value::Text(String::from("Davies")),
+        );
+        register.insert(String::from("age"), Value::Integer(24));
+        register.insert(String::from("city"), Value::Text(String::from("Gaiman")));
 
         // city = Gaiman AND (age > 30 OR lastname = Davies)
 
@@ -532,20 +1052,20 @@ mod tests {
             left: Some(Box::new(Condition::Simple {
                 field: String::from("city"),
                 operator: Operator::Equal,
-                value: String::from("Gaiman"),
+                value: Value::Text(String::from("Gaiman")),
             })),
             operator: LogicalOperator::And,
             right: Box::new(Condition::Complex {
                 left: Some(Box::new(Condition::Simple {
                     field: String::from("age"),
                     operator: Operator::Greater,
-                    value: String::from("30"),
+                    value: Value::Integer(30),
                 })),
                 operator: LogicalOperator::Or,
                 right: Box::new(Condition::Simple {
                     field: String::from("lastname"),
                     operator: Operator::Equal,
-                    value: String::from("Davies"),
+                    value: Value::Text(String::from("Davies")),
                 }),
             }),
         };
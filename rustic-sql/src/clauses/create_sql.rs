@@ -0,0 +1,95 @@
+use crate::{
+    csv::Csv, errors::SqlError, lexer::format_identifier, schema::Schema,
+    utils::find_file_in_folder, wal::Operation,
+};
+use std::fmt::Display;
+
+/// Struct that represents the `CREATE TABLE` SQL clause.
+/// The `CREATE TABLE` clause is used to create a new table with declared column types.
+///
+/// # Fields
+///
+/// * `table_name` - The name of the table to create.
+/// * `schema` - The declarations of the columns of the table.
+///
+#[derive(Debug, PartialEq)]
+pub struct Create {
+    pub table_name: String,
+    pub schema: Schema,
+}
+
+impl Create {
+    /// Creates the table in the given folder: a csv file with the column names as header and the schema file with the column declarations.
+    ///
+    /// Both files are written by one write-ahead log operation, so a crash can't leave one without the other.
+    ///
+    /// Returns an error if the table already exists.
+    ///
+    /// # Arguments
+    ///
+    /// * `folder_path` - A string slice that contains the path to the folder where the table will be created.
+    /// * `csv` - The codec of the table file.
+    ///
+    pub fn apply_to_folder(&self, folder_path: &str, csv: &Csv) -> Result<(), SqlError> {
+        let file_name = self.table_name.to_string() + ".csv";
+        if find_file_in_folder(folder_path, &file_name) {
+            return Err(SqlError::InvalidTable);
+        }
+
+        let columns: Vec<String> = self.schema.columns.iter().map(|c| c.name.clone()).collect();
+
+        let mut operation = Operation::start(folder_path)?;
+        operation.replace(&self.table_name, &[csv.format_record(&columns)])?;
+        self.schema.save(&mut operation, &self.table_name)?;
+        operation.commit()
+    }
+}
+
+impl Display for Create {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let columns: Vec<String> = self.schema.columns.iter().map(|c| c.to_string()).collect();
+        write!(
+            f,
+            "CREATE TABLE {} ({})",
+            format_identifier(&self.table_name),
+            columns.join(", ")
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        csv::Csv,
+        errors::SqlError,
+        parser::{parse, Statement},
+        utils::temp_folder,
+    };
+    use std::fs;
+
+    #[test]
+    fn create_table_files() {
+        let folder = temp_folder("create_table", &[]);
+
+        let create = match parse("CREATE TABLE personas (id INT NOT NULL, nombre TEXT)") {
+            Ok(Statement::Create(create)) => create,
+            other => panic!("{:?}", other),
+        };
+
+        assert_eq!(create.apply_to_folder(&folder, &Csv::default()), Ok(()));
+        assert_eq!(
+            fs::read_to_string(folder.clone() + "/personas.csv").unwrap(),
+            "id,nombre\n"
+        );
+        assert_eq!(
+            fs::read_to_string(folder.clone() + "/personas.schema").unwrap(),
+            "id INTEGER NOT NULL\nnombre TEXT\n"
+        );
+        assert_eq!(
+            create.apply_to_folder(&folder, &Csv::default()),
+            Err(SqlError::InvalidTable)
+        );
+
+        fs::remove_dir_all(&folder).unwrap();
+    }
+}
@@ -1,12 +1,11 @@
 use super::where_sql::Where;
-use crate::utils::{is_delete, is_from, is_where};
-use crate::{errors::SqlError, register::Register, table::Table, utils::find_file_in_folder};
-use std::io::Write;
-use std::{
-    collections::HashMap,
-    fs::{self, File},
-    io::{BufRead, BufReader},
+use crate::lexer::format_identifier;
+use crate::{
+    csv::Csv, errors::SqlError, register::Register, table::Table, utils::find_file_in_folder,
+    value::Value, wal::Operation,
 };
+use std::fmt::Display;
+use std::{collections::HashMap, fs::File, io::BufReader};
 
 /// Struct that represents the `DELETE` SQL clause.
 /// The `DELETE` clause is used to delete records from a table.
@@ -23,120 +22,65 @@ pub struct Delete {
 }
 
 impl Delete {
-    /// Creates and returns a new `Delete` instance from tokens.
-    ///
-    /// # Arguments
-    ///
-    /// - `tokens`: a `Vec<String>` that holds the tokens that form the `DELETE` clause.
-    ///
-    /// The tokens must be in the following order: `DELETE`, `FROM`, `table_name`, `WHERE`, `condition`.
+    /// Applies the `DELETE` clause to the given table.
     ///
-    /// If the `WHERE` clause is not present, the `where_clause` field will be `None`.
+    /// Returns a new table with the records that do not meet the condition.
+    /// The ones that meet the condition will be deleted.
     ///
-    /// # Examples
+    /// If the `WHERE` clause is not present, all records will be deleted.
     ///
-    /// ```
-    /// let tokens = vec![
-    ///     String::from("DELETE"),
-    ///     String::from("FROM"),
-    ///     String::from("table"),
-    /// ];
-    /// let delete = Delete::new_from_tokens(tokens).unwrap();
+    /// # Arguments
     ///
-    /// assert_eq!(
-    ///    delete,
-    ///     Delete {
-    ///         table_name: String::from("table"),
-    ///         where_clause: None
-    ///     }
-    /// );
-    /// ```
+    /// - `table`: a `BufReader<File>` that holds the table to which the `DELETE` clause will be applied.
+    /// - `csv`: the codec of the table file.
     ///
-    pub fn new_from_tokens(tokens: Vec<String>) -> Result<Self, SqlError> {
-        if tokens.len() < 3 {
-            return Err(SqlError::InvalidSyntax);
-        }
-        let mut where_tokens: Vec<&str> = Vec::new();
-
-        let mut i = 0;
-        let mut table_name = String::new();
-
-        while i < tokens.len() {
-            if i == 0 && !is_delete(&tokens[i]) || i == 1 && !is_from(&tokens[i]) {
-                return Err(SqlError::InvalidSyntax);
-            }
-            if i == 1 && is_from(&tokens[i]) && i + 1 < tokens.len() {
-                table_name = tokens[i + 1].to_string();
-            }
-
-            if i == 3 && is_where(&tokens[i]) {
-                while i < tokens.len() {
-                    where_tokens.push(tokens[i].as_str());
-                    i += 1;
-                }
-            }
-            i += 1;
-        }
-
-        if table_name.is_empty() {
-            return Err(SqlError::InvalidSyntax);
-        }
-
-        let mut where_clause = None;
-
-        if !where_tokens.is_empty() {
-            where_clause = Some(Where::new_from_tokens(where_tokens)?);
-        }
-
-        Ok(Self {
-            table_name,
-            where_clause,
-        })
+    pub fn apply_to_table(&self, table: BufReader<File>, csv: &Csv) -> Result<Table, SqlError> {
+        let (result, _) = self.apply_to_table_counting(table, csv)?;
+        Ok(result)
     }
 
-    /// Applies the `DELETE` clause to the given table.
-    ///
-    /// Returns a new table with the records that do not meet the condition.
-    /// The ones that meet the condition will be deleted.
-    ///
-    /// If the `WHERE` clause is not present, all records will be deleted.
+    /// Applies the `DELETE` clause to the given table, like `apply_to_table`, and also returns the number of deleted records.
     ///
     /// # Arguments
     ///
     /// - `table`: a `BufReader<File>` that holds the table to which the `DELETE` clause will be applied.
+    /// - `csv`: the codec of the table file.
     ///
-    pub fn apply_to_table(&self, table: BufReader<File>) -> Result<Table, SqlError> {
+    pub fn apply_to_table_counting(
+        &self,
+        table: BufReader<File>,
+        csv: &Csv,
+    ) -> Result<(Table, usize), SqlError> {
         let mut result = Table::new();
+        let mut deleted = 0;
 
-        for (idx, line) in table.lines().enumerate() {
-            let line = line.map_err(|_| SqlError::Error)?;
+        let mut records = csv.records(table);
+        result.columns = records.header()?;
 
-            if idx == 0 {
-                result.columns = line.split(',').map(|s| s.to_string()).collect();
-                if self.where_clause.is_none() {
-                    return Ok(result);
-                }
+        for record in records {
+            let fields = record?;
+
+            if self.where_clause.is_none() {
+                deleted += 1;
                 continue;
             }
-            let register = self.execute(line, &result.columns)?;
+            let register = self.execute(&fields, &result.columns)?;
 
             if !register.0.is_empty() {
                 result.registers.push(register);
+            } else {
+                deleted += 1;
             }
         }
-        Ok(result)
+        Ok((result, deleted))
     }
 
-    fn execute(&self, line: String, columns: &Vec<String>) -> Result<Register, SqlError> {
-        let atributes: Vec<String> = line.split(',').map(|s| s.to_string()).collect();
-
-        let mut register = Register(HashMap::new());
-
-        for (idx, col) in columns.iter().enumerate() {
-            register
-                .0
-                .insert(col.to_string(), atributes[idx].to_string());
-        }
+    fn execute(
+        &self,
+        fields: &[Option<String>],
+        columns: &Vec<String>,
+    ) -> Result<Register, SqlError> {
+        let register = Register::from_fields(columns, fields);
 
         let mut result = Register(HashMap::new());
 
@@ -147,7 +91,7 @@ impl Delete {
                 for col in columns {
                     result.0.insert(
                         col.to_string(),
-                        register.0.get(col).unwrap_or(&String::new()).to_string(),
+                        register.0.get(col).cloned().unwrap_or(Value::Null),
                     );
                 }
             }
@@ -156,6 +100,7 @@ impl Delete {
     }
 
     /// Updates the table file with the new data after the `DELETE` clause is applied.
+    /// The new data is recorded in the write-ahead log before it replaces the file, so a crash can't leave it half written.
     ///
     /// # Arguments
     ///
@@ -163,18 +108,9 @@ impl Delete {
     /// - `folder_path`: a `&str` that holds the path to the folder where the table file is located.
     ///
     pub fn write_table(&self, csv: Vec<String>, folder_path: &str) -> Result<(), SqlError> {
-        let temp_file_path = folder_path.to_string() + "/" + "temp.csv";
-        let mut temp_file = File::create(&temp_file_path).map_err(|_| SqlError::Error)?;
-
-        for line in csv {
-            writeln!(temp_file, "{}", line).map_err(|_| SqlError::Error)?;
-        }
-
-        let path = folder_path.to_string() + "/" + &self.table_name + ".csv";
-
-        fs::rename(&temp_file_path, path).map_err(|_| SqlError::Error)?;
-
-        Ok(())
+        let mut operation = Operation::start(folder_path)?;
+        operation.replace(&self.table_name, &csv)?;
+        operation.commit()
     }
 
     /// Opens the table file to which the `DELETE` clause will be applied.
@@ -186,11 +122,11 @@ impl Delete {
     pub fn open_table(&self, folder_path: &str) -> Result<BufReader<File>, SqlError> {
         let table_name = self.table_name.to_string() + ".csv";
         if !find_file_in_folder(folder_path, &table_name) {
-            return Err(SqlError::InvalidTable);
+            return Err(SqlError::TableNotFound(self.table_name.to_string()));
         }
 
         let table_path = folder_path.to_string() + "/" + &table_name;
-        let file = File::open(&table_path).map_err(|_| SqlError::InvalidTable)?;
+        let file = File::open(&table_path).map_err(SqlError::from)?;
 
         let reader = BufReader::new(file);
 
@@ -198,6 +134,16 @@ impl Delete {
     }
 }
 
+impl Display for Delete {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "DELETE FROM {}", format_identifier(&self.table_name))?;
+        if let Some(where_clause) = &self.where_clause {
+            write!(f, " WHERE {}", where_clause.condition)?;
+        }
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::HashMap;
@@ -205,82 +151,13 @@ mod tests {
     use super::Delete;
     use crate::{
         clauses::{condition::Condition, where_sql::Where},
-        errors::SqlError,
+        csv::Csv,
         operator::Operator,
         register::Register,
         table::Table,
+        value::Value,
     };
 
-    #[test]
-    fn new_1_token() {
-        let tokens = vec![String::from("DELETE")];
-        let delete = Delete::new_from_tokens(tokens);
-        assert_eq!(delete, Err(SqlError::InvalidSyntax));
-    }
-
-    #[test]
-    fn new_2_token() {
-        let tokens = vec![String::from("DELETE"), String::from("FROM")];
-        let delete = Delete::new_from_tokens(tokens);
-        assert_eq!(delete, Err(SqlError::InvalidSyntax));
-    }
-
-    #[test]
-    fn new_without_where() {
-        let tokens = vec![
-            String::from("DELETE"),
-            String::from("FROM"),
-            String::from("table"),
-        ];
-        let delete = Delete::new_from_tokens(tokens).unwrap();
-        assert_eq!(
-            delete,
-            Delete {
-                table_name: String::from("table"),
-                where_clause: None
-            }
-        );
-    }
-
-    #[test]
-    fn new_4_tokens() {
-        let tokens = vec![
-            String::from("DELETE"),
-            String::from("FROM"),
-            String::from("table"),
-            String::from("WHERE"),
-        ];
-        let delete = Delete::new_from_tokens(tokens);
-        assert_eq!(delete, Err(SqlError::InvalidSyntax));
-    }
-
-    #[test]
-    fn new_with_where() {
-        let tokens = vec![
-            String::from("DELETE"),
-            String::from("FROM"),
-            String::from("table"),
-            String::from("WHERE"),
-            String::from("cantidad"),
-            String::from(">"),
-            String::from("1"),
-        ];
-        let delete = Delete::new_from_tokens(tokens).unwrap();
-        assert_eq!(
-            delete,
-            Delete {
-                table_name: String::from("table"),
-                where_clause: Some(Where {
-                    condition: Condition::Simple {
-                        field: String::from("cantidad"),
-                        operator: Operator::Greater,
-                        value: String::from("1")
-                    }
-                }),
-            }
-        );
-    }
-
     #[test]
     fn delete_without_where_should_delete_all() {
         let delete = Delete {
@@ -290,7 +167,7 @@ mod tests {
         let folder_path = String::from("tablas");
         let reader = delete.open_table(&folder_path).unwrap();
 
-        let table = delete.apply_to_table(reader).unwrap();
+        let table = delete.apply_to_table(reader, &Csv::default()).unwrap();
         let expected = Table {
             columns: vec![
                 String::from("nombre"),
@@ -312,14 +189,14 @@ mod tests {
                 condition: Condition::Simple {
                     field: String::from("edad"),
                     operator: Operator::Greater,
-                    value: String::from("18"),
+                    value: Value::Integer(18),
                 },
             }),
         };
         let folder_path = String::from("tablas");
         let reader = delete.open_table(&folder_path).unwrap();
 
-        let table = delete.apply_to_table(reader).unwrap();
+        let table = delete.apply_to_table(reader, &Csv::default()).unwrap();
         let expected = Table {
             columns: vec![
                 String::from("nombre"),
@@ -327,9 +204,9 @@ mod tests {
                 String::from("edad"),
             ],
             registers: vec![Register(HashMap::from([
-                (String::from("nombre"), String::from("Ana")),
-                (String::from("apellido"), String::from("López")),
-                (String::from("edad"), String::from("18")),
+                (String::from("nombre"), Value::Text(String::from("Ana"))),
+                (String::from("apellido"), Value::Text(String::from("López"))),
+                (String::from("edad"), Value::Integer(18)),
             ]))],
         };
 
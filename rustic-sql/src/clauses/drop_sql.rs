@@ -0,0 +1,97 @@
+use crate::{
+    errors::SqlError, lexer::format_identifier, schema::Schema, utils::find_file_in_folder,
+    wal::Operation,
+};
+use std::{fmt::Display, path::Path};
+
+/// Struct that represents the `DROP TABLE` SQL clause.
+/// The `DROP TABLE` clause is used to delete a table, with its file and schema.
+///
+/// # Fields
+///
+/// * `table_name` - The name of the table to delete.
+/// * `if_exists` - Whether a missing table is ignored instead of being an error.
+///
+#[derive(Debug, PartialEq)]
+pub struct Drop {
+    pub table_name: String,
+    pub if_exists: bool,
+}
+
+impl Drop {
+    /// Deletes the table file and its schema file from the given folder.
+    ///
+    /// Both files are removed by one write-ahead log operation, so a crash can't leave one without the other.
+    ///
+    /// Returns `SqlError::InvalidTable` if the table doesn't exist, unless `if_exists` is true.
+    ///
+    /// # Arguments
+    ///
+    /// * `folder_path` - A string slice that contains the path to the folder where the table is located.
+    ///
+    pub fn apply_to_folder(&self, folder_path: &str) -> Result<(), SqlError> {
+        let file_name = self.table_name.to_string() + ".csv";
+        if !find_file_in_folder(folder_path, &file_name) {
+            if self.if_exists {
+                return Ok(());
+            }
+            return Err(SqlError::TableNotFound(self.table_name.to_string()));
+        }
+
+        let mut operation = Operation::start(folder_path)?;
+        operation.remove_file(&file_name);
+        if Path::new(&Schema::path(folder_path, &self.table_name)).is_file() {
+            operation.remove_file(&Schema::file_name(&self.table_name));
+        }
+        operation.commit()
+    }
+}
+
+impl Display for Drop {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "DROP TABLE ")?;
+        if self.if_exists {
+            write!(f, "IF EXISTS ")?;
+        }
+        write!(f, "{}", format_identifier(&self.table_name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Drop;
+    use crate::{errors::SqlError, utils::temp_folder};
+    use std::fs;
+
+    #[test]
+    fn drop_table_files() {
+        let folder = temp_folder("drop", &[]);
+        fs::write(folder.clone() + "/personas.csv", "id\n").unwrap();
+        fs::write(folder.clone() + "/personas.schema", "id INTEGER\n").unwrap();
+
+        let drop = Drop {
+            table_name: String::from("personas"),
+            if_exists: false,
+        };
+
+        assert_eq!(drop.apply_to_folder(&folder), Ok(()));
+        let files: Vec<String> = fs::read_dir(&folder)
+            .unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().to_string())
+            .filter(|name| !name.starts_with('.'))
+            .collect();
+        assert!(files.is_empty());
+        assert_eq!(
+            drop.apply_to_folder(&folder),
+            Err(SqlError::TableNotFound(String::from("personas")))
+        );
+
+        let drop = Drop {
+            table_name: String::from("personas"),
+            if_exists: true,
+        };
+        assert_eq!(drop.apply_to_folder(&folder), Ok(()));
+
+        fs::remove_dir_all(&folder).unwrap();
+    }
+}
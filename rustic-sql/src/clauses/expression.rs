@@ -0,0 +1,545 @@
+use super::aggregate::{Aggregate, AggregateFunction};
+use crate::{
+    arithmetic_operator::ArithmeticOperator,
+    errors::SqlError,
+    functions::{cast, get_function, is_function},
+    lexer::{expect_punctuation, unexpected, Token, TokenKind},
+    schema::ColumnType,
+    value::Value,
+};
+use std::{collections::HashMap, fmt::Display};
+
+/// Enum for the expressions that can be used as columns of a `SELECT`, in conditions and in `SET`.
+///
+/// - `Column`: The value of a column of the register, like `cantidad` or `o.cantidad`.
+/// - `Literal`: A constant value, like `2` or `'texto'`.
+/// - `Binary`: An arithmetic operation between two expressions, like `cantidad * 2`.
+/// - `Function`: A call to a registered scalar function, like `UPPER(nombre)`.
+/// - `Cast`: A conversion of an expression to a type, like `CAST(cantidad AS TEXT)`.
+/// - `Aggregate`: The result of an aggregate function for a group of registers, like `SUM(cantidad)`.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expression {
+    Column(String),
+    Literal(Value),
+    Binary {
+        left: Box<Expression>,
+        operator: ArithmeticOperator,
+        right: Box<Expression>,
+    },
+    Function {
+        name: String,
+        arguments: Vec<Expression>,
+    },
+    Cast {
+        expression: Box<Expression>,
+        column_type: ColumnType,
+    },
+    Aggregate(Aggregate),
+}
+
+impl Expression {
+    /// Parses an expression from the tokens read by the lexer, starting at the position `pos`.
+    ///
+    /// Stops at the first token that can't continue the expression, leaving `pos` there.
+    /// `*`, `/` and `%` are applied before `+`, `-` and `||`, and parentheses group a sub-expression.
+    ///
+    /// Returns `SqlError::UnexpectedToken`, with its offset, for the first token that can't start an operand or close a parenthesis.
+    ///
+    /// # Arguments
+    ///
+    /// * `tokens` - The tokens read by `lexer::tokenize`.
+    /// * `pos` - The position of the first token of the expression.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use rustic_sql::Value;
+    /// # use rustic_sql::arithmetic_operator::ArithmeticOperator;
+    /// # use rustic_sql::clauses::expression::Expression;
+    /// # use rustic_sql::lexer;
+    /// let tokens = lexer::tokenize("cantidad + 1 WHERE").unwrap();
+    /// let mut pos = 0;
+    /// let expression = Expression::new_from_tokens(&tokens, &mut pos).unwrap();
+    ///
+    /// assert_eq!(expression, Expression::Binary {
+    ///     left: Box::new(Expression::Column("cantidad".to_string())),
+    ///     operator: ArithmeticOperator::Add,
+    ///     right: Box::new(Expression::Literal(Value::Integer(1))),
+    /// });
+    /// assert_eq!(pos, 3);
+    /// ```
+    ///
+    pub fn new_from_tokens(tokens: &[Token], pos: &mut usize) -> Result<Self, SqlError> {
+        parse_binary(tokens, pos, 1)
+    }
+
+    /// Evaluates the expression for a register and returns its value.
+    ///
+    /// Returns `SqlError::InvalidColumn` if a column is not in the register, or the error of a function that fails.
+    ///
+    /// # Arguments
+    ///
+    /// * `register` - The values of the register, by column name.
+    ///
+    pub fn evaluate(&self, register: &HashMap<String, Value>) -> Result<Value, SqlError> {
+        match self {
+            Expression::Column(name) => register
+                .get(name)
+                .cloned()
+                .ok_or_else(|| SqlError::ColumnNotFound(name.to_string())),
+            Expression::Literal(value) => Ok(value.clone()),
+            Expression::Binary {
+                left,
+                operator,
+                right,
+            } => {
+                let left = left.evaluate(register)?;
+                let right = right.evaluate(register)?;
+                match operator {
+                    ArithmeticOperator::Add => left.add(&right),
+                    ArithmeticOperator::Sub => left.sub(&right),
+                    ArithmeticOperator::Mul => left.mul(&right),
+                    ArithmeticOperator::Div => left.div(&right),
+                    ArithmeticOperator::Rem => left.rem(&right),
+                    ArithmeticOperator::Concat if left.is_null() || right.is_null() => {
+                        Ok(Value::Null)
+                    }
+                    ArithmeticOperator::Concat => Ok(Value::Text(format!("{}{}", left, right))),
+                }
+            }
+            Expression::Function { name, arguments } => {
+                let function = get_function(name).ok_or(SqlError::InvalidSyntax)?;
+                let values = arguments
+                    .iter()
+                    .map(|argument| argument.evaluate(register))
+                    .collect::<Result<Vec<Value>, SqlError>>()?;
+                function(&values)
+            }
+            Expression::Cast {
+                expression,
+                column_type,
+            } => cast(expression.evaluate(register)?, column_type),
+            // The aggregated registers have the value of each aggregate under its name.
+            Expression::Aggregate(aggregate) => {
+                let name = aggregate.to_string();
+                register
+                    .get(&name)
+                    .cloned()
+                    .ok_or(SqlError::ColumnNotFound(name))
+            }
+        }
+    }
+
+    /// Returns the names of the columns used in the expression, without the ones inside aggregate functions.
+    pub fn columns(&self) -> Vec<&str> {
+        match self {
+            Expression::Column(name) => vec![name.as_str()],
+            Expression::Literal(_) => Vec::new(),
+            Expression::Binary { left, right, .. } => {
+                let mut columns = left.columns();
+                columns.extend(right.columns());
+                columns
+            }
+            Expression::Function { arguments, .. } => {
+                arguments.iter().flat_map(|a| a.columns()).collect()
+            }
+            Expression::Cast { expression, .. } => expression.columns(),
+            Expression::Aggregate(_) => Vec::new(),
+        }
+    }
+
+    /// Returns the aggregate functions used in the expression.
+    pub fn aggregates(&self) -> Vec<&Aggregate> {
+        match self {
+            Expression::Aggregate(aggregate) => vec![aggregate],
+            Expression::Column(_) | Expression::Literal(_) => Vec::new(),
+            Expression::Binary { left, right, .. } => {
+                let mut aggregates = left.aggregates();
+                aggregates.extend(right.aggregates());
+                aggregates
+            }
+            Expression::Function { arguments, .. } => {
+                arguments.iter().flat_map(|a| a.aggregates()).collect()
+            }
+            Expression::Cast { expression, .. } => expression.aggregates(),
+        }
+    }
+
+    fn precedence(&self) -> u8 {
+        match self {
+            Expression::Binary { operator, .. } => operator.precedence(),
+            _ => u8::MAX,
+        }
+    }
+}
+
+fn parse_binary(tokens: &[Token], pos: &mut usize, precedence: u8) -> Result<Expression, SqlError> {
+    let mut left = parse_operand(tokens, pos)?;
+
+    while let Some(operator) = tokens
+        .get(*pos)
+        .filter(|t| t.kind == TokenKind::Operator)
+        .and_then(|t| ArithmeticOperator::new_from_token(&t.text))
+    {
+        if operator.precedence() < precedence {
+            break;
+        }
+        *pos += 1;
+        let right = parse_binary(tokens, pos, operator.precedence() + 1)?;
+        left = Expression::Binary {
+            left: Box::new(left),
+            operator,
+            right: Box::new(right),
+        };
+    }
+
+    Ok(left)
+}
+
+fn parse_operand(tokens: &[Token], pos: &mut usize) -> Result<Expression, SqlError> {
+    let token = tokens.get(*pos).ok_or_else(|| unexpected(tokens, *pos))?;
+    let is_literal_keyword = ["NULL", "TRUE", "FALSE"]
+        .iter()
+        .any(|keyword| token.is_keyword(keyword));
+
+    match token.kind {
+        TokenKind::Operator if token.text == "-" => {
+            *pos += 1;
+            match parse_operand(tokens, pos)? {
+                Expression::Literal(Value::Integer(integer)) => {
+                    Ok(Expression::Literal(Value::Integer(-integer)))
+                }
+                Expression::Literal(Value::Float(float)) => {
+                    Ok(Expression::Literal(Value::Float(-float)))
+                }
+                operand => Ok(Expression::Binary {
+                    left: Box::new(Expression::Literal(Value::Integer(0))),
+                    operator: ArithmeticOperator::Sub,
+                    right: Box::new(operand),
+                }),
+            }
+        }
+        TokenKind::NumberLiteral => {
+            *pos += 1;
+            Ok(Expression::Literal(Value::from_literal(&token.text)))
+        }
+        TokenKind::StringLiteral => {
+            *pos += 1;
+            Ok(Expression::Literal(Value::Text(token.text.clone())))
+        }
+        TokenKind::Keyword if is_literal_keyword => {
+            *pos += 1;
+            Ok(Expression::Literal(Value::from_literal(&token.text)))
+        }
+        TokenKind::Punctuation if token.is_punctuation('(') => {
+            *pos += 1;
+            let expression = parse_binary(tokens, pos, 1)?;
+            expect_punctuation(tokens, pos, ')')?;
+            Ok(expression)
+        }
+        TokenKind::Identifier => {
+            *pos += 1;
+            let name = token.text.to_uppercase();
+            let is_call = tokens.get(*pos).is_some_and(|t| t.is_punctuation('('));
+            if is_call && name == "CAST" {
+                parse_cast(tokens, pos)
+            } else if let Some(function) =
+                AggregateFunction::new_from_token(&name).filter(|_| is_call)
+            {
+                parse_aggregate(function, tokens, pos)
+            } else if is_call && is_function(&name) {
+                parse_function(name, tokens, pos)
+            } else {
+                Ok(Expression::Column(token.text.clone()))
+            }
+        }
+        _ => Err(unexpected(tokens, *pos)),
+    }
+}
+
+/// Parses `(expression AS type)`, the arguments of `CAST`.
+fn parse_cast(tokens: &[Token], pos: &mut usize) -> Result<Expression, SqlError> {
+    expect_punctuation(tokens, pos, '(')?;
+    let expression = parse_binary(tokens, pos, 1)?;
+    if !tokens.get(*pos).is_some_and(|t| t.is_keyword("AS")) {
+        return Err(unexpected(tokens, *pos));
+    }
+    *pos += 1;
+
+    let column_type = tokens
+        .get(*pos)
+        .filter(|t| t.kind == TokenKind::Identifier)
+        .and_then(|t| ColumnType::new_from_token(&t.text).ok())
+        .ok_or_else(|| unexpected(tokens, *pos))?;
+    *pos += 1;
+    expect_punctuation(tokens, pos, ')')?;
+
+    Ok(Expression::Cast {
+        expression: Box::new(expression),
+        column_type,
+    })
+}
+
+/// Parses `(column)`, or `(*)` for `COUNT`, the argument of an aggregate function.
+fn parse_aggregate(
+    function: AggregateFunction,
+    tokens: &[Token],
+    pos: &mut usize,
+) -> Result<Expression, SqlError> {
+    expect_punctuation(tokens, pos, '(')?;
+    let column = match tokens.get(*pos) {
+        Some(token) if token.kind == TokenKind::Identifier => Some(token.text.clone()),
+        Some(token) if token.text == "*" && function == AggregateFunction::Count => None,
+        _ => return Err(unexpected(tokens, *pos)),
+    };
+    *pos += 1;
+    expect_punctuation(tokens, pos, ')')?;
+
+    Ok(Expression::Aggregate(Aggregate { function, column }))
+}
+
+/// Parses `(argument, ...)`, the arguments of a scalar function, which can be none.
+fn parse_function(name: String, tokens: &[Token], pos: &mut usize) -> Result<Expression, SqlError> {
+    expect_punctuation(tokens, pos, '(')?;
+    let mut arguments = Vec::new();
+    if !tokens.get(*pos).is_some_and(|t| t.is_punctuation(')')) {
+        loop {
+            arguments.push(parse_binary(tokens, pos, 1)?);
+            if !tokens.get(*pos).is_some_and(|t| t.is_punctuation(',')) {
+                break;
+            }
+            *pos += 1;
+        }
+    }
+    expect_punctuation(tokens, pos, ')')?;
+
+    Ok(Expression::Function { name, arguments })
+}
+
+/// Parses a constant value, like the values of `INSERT` or of `IN`: a literal or an expression without columns, like `-5` or `'a' || 'b'`.
+///
+/// A single word without quotes that isn't followed by an operator or a parenthesis is read as text.
+///
+/// Returns the error of the expression, or `SqlError::ColumnNotFound` if it uses a column.
+///
+/// # Arguments
+///
+/// * `tokens` - The tokens read by `lexer::tokenize`.
+/// * `pos` - The position of the first token of the value.
+///
+/// # Examples
+///
+/// ```
+/// # use rustic_sql::Value;
+/// # use rustic_sql::clauses::expression::parse_constant;
+/// # use rustic_sql::lexer;
+/// let tokens = lexer::tokenize("(Alen, 20 + 5)").unwrap();
+/// let mut pos = 1;
+///
+/// assert_eq!(parse_constant(&tokens, &mut pos), Ok(Value::Text("Alen".to_string())));
+/// pos += 1;
+/// assert_eq!(parse_constant(&tokens, &mut pos), Ok(Value::Integer(25)));
+/// assert_eq!(pos, 6);
+/// ```
+///
+pub fn parse_constant(tokens: &[Token], pos: &mut usize) -> Result<Value, SqlError> {
+    let is_word = tokens
+        .get(*pos)
+        .is_some_and(|t| t.kind == TokenKind::Identifier);
+    let continues = tokens
+        .get(*pos + 1)
+        .is_some_and(|t| t.kind == TokenKind::Operator || t.is_punctuation('('));
+    if is_word && !continues {
+        *pos += 1;
+        return Ok(Value::Text(tokens[*pos - 1].text.clone()));
+    }
+    Expression::new_from_tokens(tokens, pos)?.evaluate(&HashMap::new())
+}
+
+impl Display for Expression {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Expression::Column(name) => write!(f, "{}", name),
+            Expression::Literal(Value::Text(text)) => write!(f, "'{}'", text.replace('\'', "''")),
+            Expression::Literal(Value::Null) => write!(f, "NULL"),
+            Expression::Literal(value) => write!(f, "{}", value),
+            Expression::Binary {
+                left,
+                operator,
+                right,
+            } => {
+                if left.precedence() < operator.precedence() {
+                    write!(f, "({})", left)?;
+                } else {
+                    write!(f, "{}", left)?;
+                }
+                write!(f, " {} ", operator)?;
+                if right.precedence() <= operator.precedence() {
+                    write!(f, "({})", right)
+                } else {
+                    write!(f, "{}", right)
+                }
+            }
+            Expression::Function { name, arguments } => {
+                let arguments: Vec<String> = arguments.iter().map(|a| a.to_string()).collect();
+                write!(f, "{}({})", name, arguments.join(", "))
+            }
+            Expression::Cast {
+                expression,
+                column_type,
+            } => write!(f, "CAST({} AS {})", expression, column_type),
+            Expression::Aggregate(aggregate) => write!(f, "{}", aggregate),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Expression;
+    use crate::{
+        arithmetic_operator::ArithmeticOperator,
+        clauses::aggregate::{Aggregate, AggregateFunction},
+        errors::SqlError,
+        lexer::tokenize,
+        value::Value,
+    };
+    use std::collections::HashMap;
+
+    fn parse(query: &str) -> Expression {
+        let tokens = tokenize(query).unwrap();
+        let mut pos = 0;
+        let expression = Expression::new_from_tokens(&tokens, &mut pos).unwrap();
+        assert_eq!(pos, tokens.len());
+        expression
+    }
+
+    fn parse_error(query: &str) -> SqlError {
+        let tokens = tokenize(query).unwrap();
+        let mut pos = 0;
+        Expression::new_from_tokens(&tokens, &mut pos).unwrap_err()
+    }
+
+    fn unexpected(token: &str, offset: usize) -> SqlError {
+        SqlError::UnexpectedToken {
+            token: token.to_string(),
+            offset: Some(offset),
+        }
+    }
+
+    #[test]
+    fn parse_with_precedence() {
+        let expression = parse("a + b * 2");
+
+        assert_eq!(
+            expression,
+            Expression::Binary {
+                left: Box::new(Expression::Column(String::from("a"))),
+                operator: ArithmeticOperator::Add,
+                right: Box::new(Expression::Binary {
+                    left: Box::new(Expression::Column(String::from("b"))),
+                    operator: ArithmeticOperator::Mul,
+                    right: Box::new(Expression::Literal(Value::Integer(2))),
+                }),
+            }
+        );
+        assert_eq!(expression.to_string(), "a + b * 2");
+    }
+
+    #[test]
+    fn parse_parentheses_and_negatives() {
+        let expression = parse("(a + b) * -2");
+        assert_eq!(expression.to_string(), "(a + b) * -2");
+
+        let expression = parse("a - (b - c)");
+        assert_eq!(expression.to_string(), "a - (b - c)");
+
+        let expression = parse("-(a)");
+        assert_eq!(expression.to_string(), "0 - a");
+
+        let expression = parse("'it''s' || nombre");
+        assert_eq!(expression.to_string(), "'it''s' || nombre");
+    }
+
+    #[test]
+    fn parse_invalid() {
+        assert_eq!(parse_error("a +"), unexpected("", 3));
+        assert_eq!(parse_error("(a + b"), unexpected("", 6));
+        assert_eq!(parse_error("a * , b"), unexpected(",", 4));
+    }
+
+    #[test]
+    fn parse_functions() {
+        let expression = parse("substr(UPPER(nombre), 1, LENGTH(nombre) - 1)");
+        assert_eq!(
+            expression.to_string(),
+            "SUBSTR(UPPER(nombre), 1, LENGTH(nombre) - 1)"
+        );
+        assert_eq!(expression.columns(), vec!["nombre", "nombre"]);
+
+        let expression = parse("CAST(cantidad * 2 AS TEXT) || 'x'");
+        assert_eq!(expression.to_string(), "CAST(cantidad * 2 AS TEXT) || 'x'");
+
+        let expression = parse("REPLACE(email, '@', ', ')");
+        assert_eq!(expression.to_string(), "REPLACE(email, '@', ', ')");
+
+        assert_eq!(parse_error("CAST(cantidad)"), unexpected(")", 13));
+        assert_eq!(
+            parse_error("CAST(cantidad AS NUMBER)"),
+            unexpected("NUMBER", 17)
+        );
+    }
+
+    #[test]
+    fn parse_aggregates() {
+        let expression = parse("sum(o.cantidad) * 2");
+        assert_eq!(expression.to_string(), "SUM(o.cantidad) * 2");
+        assert_eq!(
+            expression.aggregates(),
+            vec![&Aggregate {
+                function: AggregateFunction::Sum,
+                column: Some(String::from("o.cantidad")),
+            }]
+        );
+        assert!(expression.columns().is_empty());
+
+        assert_eq!(parse("COUNT(*)").to_string(), "COUNT(*)");
+        assert_eq!(parse_error("SUM(*)"), unexpected("*", 4));
+        assert_eq!(parse_error("MAX(cantidad + 1)"), unexpected("+", 13));
+    }
+
+    #[test]
+    fn evaluate_expressions() {
+        let register = HashMap::from([
+            (String::from("cantidad"), Value::Integer(3)),
+            (String::from("precio"), Value::Float(1.5)),
+            (String::from("nombre"), Value::Text(String::from("Ana"))),
+            (String::from("email"), Value::Null),
+            (String::from("COUNT(*)"), Value::Integer(2)),
+        ]);
+        let evaluate = |query: &str| parse(query).evaluate(&register);
+
+        assert_eq!(evaluate("cantidad * 2"), Ok(Value::Integer(6)));
+        assert_eq!(evaluate("cantidad % 2"), Ok(Value::Integer(1)));
+        assert_eq!(evaluate("cantidad * precio"), Ok(Value::Float(4.5)));
+        assert_eq!(
+            evaluate("nombre || ' ' || cantidad"),
+            Ok(Value::Text(String::from("Ana 3")))
+        );
+        assert_eq!(evaluate("email || nombre"), Ok(Value::Null));
+        assert_eq!(
+            evaluate("COALESCE(email, LOWER(nombre))"),
+            Ok(Value::Text(String::from("ana")))
+        );
+        assert_eq!(evaluate("CAST(precio AS INT) + 1"), Ok(Value::Integer(2)));
+        assert_eq!(evaluate("count(*) + 1"), Ok(Value::Integer(3)));
+        assert_eq!(
+            evaluate("edad + 1"),
+            Err(SqlError::ColumnNotFound(String::from("edad")))
+        );
+        assert_eq!(
+            evaluate("SUM(cantidad)"),
+            Err(SqlError::ColumnNotFound(String::from("SUM(cantidad)")))
+        );
+    }
+}
@@ -0,0 +1,89 @@
+use crate::{errors::SqlError, lexer::format_identifier, register::Register};
+use std::{collections::HashMap, fmt::Display};
+
+/// Struct that represents the `GROUP BY` SQL clause.
+/// The `GROUP BY` clause is used to group the registers that have the same values in some columns, so aggregate functions are applied to each group.
+///
+/// # Fields
+///
+/// * `columns` - The columns to group the registers by.
+///
+#[derive(Debug, PartialEq)]
+pub struct GroupBy {
+    pub columns: Vec<String>,
+}
+
+impl GroupBy {
+    /// Splits the registers into groups with the same values in the `GROUP BY` columns.
+    ///
+    /// The groups are returned in the order their first register appears.
+    ///
+    /// # Arguments
+    ///
+    /// * `registers` - The registers to group.
+    ///
+    pub fn execute(&self, registers: Vec<Register>) -> Result<Vec<Vec<Register>>, SqlError> {
+        let mut groups: Vec<Vec<Register>> = Vec::new();
+        let mut positions: HashMap<String, usize> = HashMap::new();
+
+        for register in registers {
+            let mut key = Vec::new();
+            for column in &self.columns {
+                key.push(register.0.get(column).ok_or(SqlError::InvalidColumn)?);
+            }
+            let key = format!("{:?}", key);
+
+            match positions.get(&key) {
+                Some(&idx) => groups[idx].push(register),
+                None => {
+                    positions.insert(key, groups.len());
+                    groups.push(vec![register]);
+                }
+            }
+        }
+
+        Ok(groups)
+    }
+}
+
+impl Display for GroupBy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let columns: Vec<String> = self.columns.iter().map(|c| format_identifier(c)).collect();
+        write!(f, "GROUP BY {}", columns.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::GroupBy;
+    use crate::{register::Register, value::Value};
+    use std::collections::HashMap;
+
+    #[test]
+    fn execute_groups() {
+        let register = |producto: &str, cantidad: i64| {
+            Register(HashMap::from([
+                (String::from("producto"), Value::Text(producto.to_string())),
+                (String::from("cantidad"), Value::Integer(cantidad)),
+            ]))
+        };
+        let group_by = GroupBy {
+            columns: vec![String::from("producto")],
+        };
+
+        let groups = group_by
+            .execute(vec![
+                register("Laptop", 1),
+                register("Mouse", 2),
+                register("Laptop", 3),
+            ])
+            .unwrap();
+
+        assert_eq!(groups.len(), 2);
+        assert_eq!(
+            groups[0],
+            vec![register("Laptop", 1), register("Laptop", 3)]
+        );
+        assert_eq!(groups[1], vec![register("Mouse", 2)]);
+    }
+}
@@ -1,155 +1,161 @@
+use super::expression::Expression;
 use super::into_sql::Into;
+use super::select_sql::Select;
+use crate::csv::Csv;
 use crate::errors::SqlError;
-use crate::utils::{find_file_in_folder, is_insert, is_values};
-use std::fs::{File, OpenOptions};
-use std::io::{BufRead, BufReader, Seek, SeekFrom, Write};
+use crate::schema::Schema;
+use crate::utils::find_file_in_folder;
+use crate::value::Value;
+use crate::wal::Operation;
+use std::fmt::Display;
+use std::fs::File;
+use std::io::{BufReader, Read};
+
+/// Enum for the sources of the registers inserted by an `INSERT` clause.
+///
+/// - `Values`: The rows of typed values of a `VALUES (...), (...)` list.
+/// - `Select`: A `SELECT` query, whose resulting rows are inserted.
+///
+#[derive(Debug, PartialEq)]
+pub enum InsertSource {
+    Values(Vec<Vec<Value>>),
+    Select(Box<Select>),
+}
 
 /// Struct that represents the `INSERT` SQL clause.
 /// The `INSERT` clause is used to insert new records into a table.
 ///
 /// # Fields
 ///
-/// * `values` - A vector of strings that contains the values to be inserted.
+/// * `source` - The rows to be inserted, or the `SELECT` that reads them.
 /// * `into_clause` - An `Into` struct that contains the table name and columns.
 ///
 #[derive(Debug, PartialEq)]
 pub struct Insert {
-    pub values: Vec<String>,
+    pub source: InsertSource,
     pub into_clause: Into,
 }
 
 impl Insert {
-    /// Creates and returns a new `Insert` instance from a vector of tokens.
+    /// Applies the `INSERT` clause to a table, appending all of its rows to the file with a single write.
+    ///
+    /// The rows are recorded in the write-ahead log before they are appended, so a crash can't leave only some of them in the file.
+    ///
+    /// Returns `SqlError::Error` if the rows come from a `SELECT` that was not executed yet.
     ///
     /// # Arguments
     ///
-    /// * `tokens` - A vector of strings that contains the tokens to be parsed.
+    /// * `file` - A mutable reference to a `File` instance that represents the table file.
+    /// * `folder_path` - A string slice that contains the path to the folder where the table file is located.
+    /// * `csv` - The codec of the table file.
     ///
-    /// The tokens should be in the following order: `INSERT`, `INTO`, `table_name`, `column_names`, `VALUES`, `values`.
+    pub fn apply_to_table(
+        &mut self,
+        file: &mut File,
+        folder_path: &str,
+        csv: &Csv,
+    ) -> Result<(), SqlError> {
+        let columns = csv.records(BufReader::new(file.by_ref())).header()?;
+
+        self.reorder_values(columns)?;
+
+        let lines: Vec<String> = self
+            .rows()?
+            .iter()
+            .map(|row| csv.format_values(row))
+            .collect();
+
+        let mut operation = Operation::start(folder_path)?;
+        operation.append(&self.into_clause.table_name, &lines)?;
+        operation.commit()
+    }
+
+    /// Returns the rows to insert.
     ///
-    /// The `column_names` and `values` should be comma-separated and between parentheses.
+    /// Returns `SqlError::Error` if the rows come from a `SELECT` that was not executed yet.
     ///
-    /// If a pair of col, value is missing for a column in the table, the value will be an empty string for that column.
+    pub fn rows(&self) -> Result<&Vec<Vec<Value>>, SqlError> {
+        match &self.source {
+            InsertSource::Values(rows) => Ok(rows),
+            InsertSource::Select(_) => Err(SqlError::Error),
+        }
+    }
+
+    fn rows_mut(&mut self) -> Result<&mut Vec<Vec<Value>>, SqlError> {
+        match &mut self.source {
+            InsertSource::Values(rows) => Ok(rows),
+            InsertSource::Select(_) => Err(SqlError::Error),
+        }
+    }
+
+    /// Validates the values to insert against the schema of the table.
     ///
-    /// # Examples
+    /// The values are converted to the declared type of their columns, and the columns without a value get their default value (or `Null`).
     ///
-    /// ```
-    /// let tokens = vec![
-    ///     String::from("INSERT"),
-    ///     String::from("INTO"),
-    ///     String::from("table"),
-    ///     String::from("name, age"),
-    ///     String::from("VALUES"),
-    ///     String::from("Alen, 25"),
-    /// ];
+    /// Returns `SqlError::ColumnNotFound` if a column is not in the schema, `SqlError::InvalidType` if a value doesn't match the type of its column and `SqlError::InvalidValue` if a `NOT NULL` column gets `Null`.
     ///
-    /// let insert = Insert::new_from_tokens(tokens).unwrap();
+    /// # Arguments
     ///
-    /// assert_eq!(
-    ///     insert,
-    ///     Insert {
-    ///         values: vec![String::from("Alen"), String::from("25")],
-    ///         into_clause: Into {
-    ///             table_name: String::from("table"),
-    ///             columns: vec![String::from("name"), String::from("age")]
-    ///         }
-    ///     }
-    /// );
-    /// ```
+    /// * `schema` - The schema of the table where the values will be inserted.
     ///
-    pub fn new_from_tokens(tokens: Vec<String>) -> Result<Self, SqlError> {
-        if tokens.len() < 6 {
+    pub fn validate(&mut self, schema: &Schema) -> Result<(), SqlError> {
+        let columns = &mut self.into_clause.columns;
+        let rows = match &mut self.source {
+            InsertSource::Values(rows) => rows,
+            InsertSource::Select(_) => return Err(SqlError::Error),
+        };
+        if rows.iter().any(|row| row.len() != columns.len()) {
             return Err(SqlError::InvalidSyntax);
         }
-        let mut into_tokens: Vec<&str> = Vec::new();
-        let mut values: Vec<String> = Vec::new();
-
-        let mut i = 0;
 
-        if is_insert(&tokens[i]) {
-            i += 1;
-            while !is_values(&tokens[i]) && i < tokens.len() {
-                into_tokens.push(tokens[i].as_str());
-                i += 1;
+        for row in rows.iter_mut() {
+            for (col, value) in columns.iter().zip(row.iter_mut()) {
+                let column = schema.column(col)?;
+                *value = column.validate(value.clone())?;
             }
         }
-        if is_values(&tokens[i]) {
-            i += 1;
 
-            let vals: Vec<String> = tokens[i]
-                .replace("\'", "")
-                .split(",")
-                .map(|c| c.trim().to_string())
-                .collect();
-
-            for val in vals {
-                values.push(val);
+        for column in &schema.columns {
+            if !columns.contains(&column.name) {
+                let value = column.validate(column.default.clone().unwrap_or(Value::Null))?;
+                for row in rows.iter_mut() {
+                    row.push(value.clone());
+                }
+                columns.push(column.name.clone());
             }
         }
 
-        if into_tokens.is_empty() || values.is_empty() {
-            return Err(SqlError::InvalidSyntax);
-        }
-
-        let into_clause = Into::new_from_tokens(into_tokens)?;
-
-        Ok(Self {
-            values,
-            into_clause,
-        })
+        Ok(())
     }
 
-    /// Applies the `INSERT` clause to a table.
+    /// Puts the values of every row in the order of the columns of the table, with `Null` for the columns without a value.
     ///
-    /// # Arguments
-    ///
-    /// * `file` - A mutable reference to a `File` instance that represents the table file.
+    /// Returns `SqlError::ColumnNotFound` if the clause has a column that is not in the table.
     ///
-    pub fn apply_to_table(&mut self, file: &mut File) -> Result<(), SqlError> {
-        let mut reader = BufReader::new(file.by_ref());
-
-        let mut first_line = String::new();
-
-        reader
-            .read_line(&mut first_line)
-            .map_err(|_| SqlError::InvalidTable)?;
-
-        let columns: Vec<String> = first_line
-            .trim()
-            .split(',')
-            .map(|col| col.to_string())
+    fn reorder_values(&mut self, columns: Vec<String>) -> Result<(), SqlError> {
+        if let Some(unknown) = self
+            .into_clause
+            .columns
+            .iter()
+            .find(|col| !columns.contains(col))
+        {
+            return Err(SqlError::ColumnNotFound(unknown.to_string()));
+        }
+        let positions: Vec<Option<usize>> = columns
+            .iter()
+            .map(|col| self.into_clause.columns.iter().position(|x| x == col))
             .collect();
 
-        self.reorder_values(columns);
-
-        let line = self.values.join(",");
-
-        file.seek(SeekFrom::End(0)).map_err(|_| SqlError::Error)?;
-
-        writeln!(file, "{}", line).map_err(|_| SqlError::Error)?;
-
-        Ok(())
-    }
-
-    fn reorder_values(&mut self, columns: Vec<String>) {
-        let mut reordered_values: Vec<&str> = Vec::new();
-        let mut reordered_cols: Vec<&str> = Vec::new();
-
-        for col in &columns {
-            if self.into_clause.columns.contains(col) {
-                if let Some(index) = self.into_clause.columns.iter().position(|x| x == col) {
-                    reordered_values.push(self.values[index].as_str());
-                }
-
-                reordered_cols.push(col);
-            } else {
-                reordered_values.push("");
-                reordered_cols.push(col);
-            }
+        for row in self.rows_mut()? {
+            *row = positions
+                .iter()
+                .map(|idx| idx.and_then(|idx| row.get(idx).cloned()))
+                .map(|value| value.unwrap_or(Value::Null))
+                .collect();
         }
 
-        self.into_clause.columns = reordered_cols.iter().map(|c| c.to_string()).collect();
-        self.values = reordered_values.iter().map(|c| c.to_string()).collect();
+        self.into_clause.columns = columns;
+        Ok(())
     }
 
     /// Opens the table file and returns a `File` instance.
@@ -161,103 +167,63 @@ impl Insert {
     pub fn open_table(&self, folder_path: &str) -> Result<File, SqlError> {
         let table_name = self.into_clause.table_name.to_string() + ".csv";
         if !find_file_in_folder(folder_path, &table_name) {
-            return Err(SqlError::InvalidTable);
+            return Err(SqlError::TableNotFound(
+                self.into_clause.table_name.to_string(),
+            ));
         }
         let table_path = folder_path.to_string() + "/" + &table_name;
 
-        let file = OpenOptions::new()
-            .read(true)
-            .append(true)
-            .open(&table_path)
-            .map_err(|_| SqlError::InvalidTable)?;
-
-        Ok(file)
+        File::open(&table_path).map_err(SqlError::from)
     }
 }
 
-#[cfg(test)]
-mod test {
-    use crate::errors::SqlError;
-    use std::io::BufRead;
-
-    #[test]
-    fn new_1_token() {
-        let tokens = vec![String::from("INSERT")];
-        let result = super::Insert::new_from_tokens(tokens);
-        assert_eq!(result, Err(SqlError::InvalidSyntax));
-    }
-
-    #[test]
-    fn new_3_tokens() {
-        let tokens = vec![
-            String::from("INSERT"),
-            String::from("INTO"),
-            String::from("table"),
-        ];
-
-        let result = super::Insert::new_from_tokens(tokens);
-        assert_eq!(result, Err(SqlError::InvalidSyntax));
-    }
-
-    #[test]
-    fn new_6_tokens() {
-        let tokens = vec![
-            String::from("INSERT"),
-            String::from("INTO"),
-            String::from("table"),
-            String::from("name"),
-            String::from("VALUES"),
-            String::from("Alen"),
-        ];
-        let result = super::Insert::new_from_tokens(tokens).unwrap();
-        assert_eq!(
-            result,
-            super::Insert {
-                values: vec![String::from("Alen")],
-                into_clause: super::Into {
-                    table_name: String::from("table"),
-                    columns: vec![String::from("name")]
-                }
+impl Display for Insert {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.source {
+            InsertSource::Values(rows) => {
+                let rows: Vec<String> = rows
+                    .iter()
+                    .map(|row| {
+                        let values: Vec<String> = row
+                            .iter()
+                            .map(|v| Expression::Literal(v.clone()).to_string())
+                            .collect();
+                        format!("({})", values.join(", "))
+                    })
+                    .collect();
+                write!(f, "INSERT {} VALUES {}", self.into_clause, rows.join(", "))
             }
-        );
+            InsertSource::Select(select) => write!(f, "INSERT {} {}", self.into_clause, select),
+        }
     }
+}
 
-    #[test]
-    fn new_more_values() {
-        let tokens = vec![
-            String::from("INSERT"),
-            String::from("INTO"),
-            String::from("table"),
-            String::from("name, age"),
-            String::from("VALUES"),
-            String::from("Alen, 25"),
-        ];
-        let result = super::Insert::new_from_tokens(tokens).unwrap();
-        assert_eq!(
-            result,
-            super::Insert {
-                values: vec![String::from("Alen"), String::from("25")],
-                into_clause: super::Into {
-                    table_name: String::from("table"),
-                    columns: vec![String::from("name"), String::from("age")]
-                }
-            }
-        );
-    }
+#[cfg(test)]
+mod test {
+    use super::InsertSource;
+    use crate::{csv::Csv, utils::temp_folder, value::Value};
+    use std::{fs, io::BufRead};
 
     #[test]
     fn insert_with_missing_values() {
         let mut insert = super::Insert {
-            values: vec![String::from("Alen")],
+            source: InsertSource::Values(vec![
+                vec![Value::Text(String::from("Alen"))],
+                vec![Value::Text(String::from("Beto"))],
+            ]),
             into_clause: super::Into {
                 table_name: String::from("testing_values"),
                 columns: vec![String::from("nombre")],
             },
         };
 
-        let mut file = insert.open_table("tablas").unwrap();
+        let folder = temp_folder("insert_missing", &["testing_values"]);
+        let mut file = insert.open_table(&folder).unwrap();
 
-        assert_eq!(insert.apply_to_table(&mut file), Ok(()));
+        assert_eq!(
+            insert.apply_to_table(&mut file, &folder, &Csv::default()),
+            Ok(())
+        );
 
         let expected = vec![
             "nombre,apellido,edad",
@@ -265,9 +231,10 @@ mod test {
             "Ana,López,18",
             "Carlos,Gómez,40",
             "Alen,,",
+            "Beto,,",
         ];
 
-        let file = std::fs::File::open("tablas/testing_values.csv").unwrap();
+        let file = fs::File::open(format!("{}/testing_values.csv", folder)).unwrap();
         let reader = std::io::BufReader::new(file);
         let mut result = Vec::new();
 
@@ -281,11 +248,11 @@ mod test {
     #[test]
     fn insert_all_values() {
         let mut insert = super::Insert {
-            values: vec![
-                String::from("Alen"),
-                String::from("Davies"),
-                String::from("25"),
-            ],
+            source: InsertSource::Values(vec![vec![
+                Value::Text(String::from("Alen")),
+                Value::Text(String::from("Davies")),
+                Value::Integer(25),
+            ]]),
             into_clause: super::Into {
                 table_name: String::from("testing_all"),
                 columns: vec![
@@ -296,9 +263,13 @@ mod test {
             },
         };
 
-        let mut file = insert.open_table("tablas").unwrap();
+        let folder = temp_folder("insert_all", &["testing_all"]);
+        let mut file = insert.open_table(&folder).unwrap();
 
-        assert_eq!(insert.apply_to_table(&mut file), Ok(()));
+        assert_eq!(
+            insert.apply_to_table(&mut file, &folder, &Csv::default()),
+            Ok(())
+        );
 
         let expected = vec![
             "nombre,apellido,edad",
@@ -308,7 +279,7 @@ mod test {
             "Alen,Davies,25",
         ];
 
-        let file = std::fs::File::open("tablas/testing_all.csv").unwrap();
+        let file = fs::File::open(format!("{}/testing_all.csv", folder)).unwrap();
         let reader = std::io::BufReader::new(file);
         let mut result = Vec::new();
 
@@ -322,11 +293,11 @@ mod test {
     #[test]
     fn insert_in_desorder() {
         let mut insert = super::Insert {
-            values: vec![
-                String::from("Davies"),
-                String::from("25"),
-                String::from("Alen"),
-            ],
+            source: InsertSource::Values(vec![vec![
+                Value::Text(String::from("Davies")),
+                Value::Integer(25),
+                Value::Text(String::from("Alen")),
+            ]]),
             into_clause: super::Into {
                 table_name: String::from("testing_desorder"),
                 columns: vec![
@@ -337,9 +308,13 @@ mod test {
             },
         };
 
-        let mut file = insert.open_table("tablas").unwrap();
+        let folder = temp_folder("insert_desorder", &["testing_desorder"]);
+        let mut file = insert.open_table(&folder).unwrap();
 
-        assert_eq!(insert.apply_to_table(&mut file), Ok(()));
+        assert_eq!(
+            insert.apply_to_table(&mut file, &folder, &Csv::default()),
+            Ok(())
+        );
 
         let expected = vec![
             "nombre,apellido,edad",
@@ -349,7 +324,7 @@ mod test {
             "Alen,Davies,25",
         ];
 
-        let file = std::fs::File::open("tablas/testing_desorder.csv").unwrap();
+        let file = fs::File::open(format!("{}/testing_desorder.csv", folder)).unwrap();
         let reader = std::io::BufReader::new(file);
         let mut result = Vec::new();
 
@@ -1,4 +1,5 @@
-use crate::{errors::SqlError, utils::is_into};
+use crate::lexer::format_identifier;
+use std::fmt::Display;
 
 /// Struct that represents the `INTO` SQL clause.
 /// The `INTO` clause is used to specify the table name and columns in the `INSERT` clause.
@@ -6,7 +7,7 @@ use crate::{errors::SqlError, utils::is_into};
 /// # Fields
 ///
 /// * `table_name` - The name of the table to insert data into.
-/// * `columns` - The columns of the table to insert data into.
+/// * `columns` - The columns of the table to insert data into. If the query doesn't list them, they are all the columns of the table, in order, and are filled in before inserting the values.
 ///
 #[derive(Debug, PartialEq)]
 pub struct Into {
@@ -14,41 +15,13 @@ pub struct Into {
     pub columns: Vec<String>,
 }
 
-impl Into {
-    /// Creates and returns a new `Into` instance from a vector of `&str` tokens.
-    ///
-    /// # Arguments
-    ///
-    /// * `tokens` - A vector of `&str` tokens that represent the `INTO` clause.
-    ///
-    /// The tokens should be in the following order: `INTO`, `table_name`, `columns`.
-    /// The `columns` should be comma-separated and between parentheses.
-    ///
-    pub fn new_from_tokens(tokens: Vec<&str>) -> Result<Self, SqlError> {
-        if tokens.len() < 3 {
-            return Err(SqlError::InvalidSyntax);
+impl Display for Into {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "INTO {}", format_identifier(&self.table_name))?;
+        if !self.columns.is_empty() {
+            let columns: Vec<String> = self.columns.iter().map(|c| format_identifier(c)).collect();
+            write!(f, " ({})", columns.join(", "))?;
         }
-        let mut i = 0;
-        let table_name;
-        let mut columns: Vec<String> = Vec::new();
-
-        if is_into(tokens[i]) {
-            i += 1;
-            table_name = tokens[i].to_string();
-            i += 1;
-
-            let cols: Vec<String> = tokens[i].split(",").map(|c| c.trim().to_string()).collect();
-
-            for col in cols {
-                columns.push(col);
-            }
-        } else {
-            return Err(SqlError::InvalidSyntax);
-        }
-
-        Ok(Self {
-            table_name,
-            columns,
-        })
+        Ok(())
     }
 }
@@ -0,0 +1,261 @@
+use crate::{errors::SqlError, lexer::format_identifier, value::Value};
+use std::{cmp::Ordering, fmt::Display};
+
+/// Enum for the kinds of `JOIN` between two tables.
+///
+/// - `Inner`: Only the pairs of registers that meet the `ON` condition.
+/// - `Left`: Like `Inner`, plus the registers of the left table without a pair, with `Null` in the columns of the right table.
+/// - `Cross`: Every pair of registers.
+///
+#[derive(Debug, PartialEq)]
+pub enum JoinKind {
+    Inner,
+    Left,
+    Cross,
+}
+
+/// Struct that represents a `JOIN` in a `SELECT` clause.
+///
+/// # Fields
+///
+/// * `kind` - The kind of join.
+/// * `table_name` - The name of the table to join.
+/// * `alias` - The alias of the table, used to qualify its columns.
+/// * `on` - The pairs of columns that must be equal, from the `ON a.x = b.y AND ...` condition. Empty for `CROSS JOIN`.
+///
+#[derive(Debug, PartialEq)]
+pub struct Join {
+    pub kind: JoinKind,
+    pub table_name: String,
+    pub alias: Option<String>,
+    pub on: Vec<(String, String)>,
+}
+
+/// Columns of the tables combined so far in a `SELECT`, as pairs of the table qualifier and the column name.
+pub type QualifiedColumns = Vec<(String, String)>;
+
+impl Join {
+    /// Returns the name used to qualify the columns of the joined table: its alias or, if it doesn't have one, its name.
+    pub fn qualifier(&self) -> &str {
+        self.alias.as_deref().unwrap_or(&self.table_name)
+    }
+
+    /// Combines the rows of the tables joined so far with the rows of the joined table.
+    ///
+    /// Returns the columns and rows of the combination, where each row has the values of the left row followed by the values of the right row.
+    ///
+    /// # Arguments
+    ///
+    /// * `left_columns` - The qualified columns of the tables joined so far.
+    /// * `left_rows` - The rows of the tables joined so far.
+    /// * `right_columns` - The columns of the joined table.
+    /// * `right_rows` - The rows of the joined table.
+    ///
+    pub fn apply(
+        &self,
+        left_columns: QualifiedColumns,
+        left_rows: Vec<Vec<Value>>,
+        right_columns: &[String],
+        right_rows: &[Vec<Value>],
+    ) -> Result<(QualifiedColumns, Vec<Vec<Value>>), SqlError> {
+        let right_qualified: QualifiedColumns = right_columns
+            .iter()
+            .map(|c| (self.qualifier().to_string(), c.to_string()))
+            .collect();
+
+        let mut keys = Vec::new();
+        for (a, b) in &self.on {
+            // The columns of the condition can be written in either order.
+            let written = (
+                resolve_column(&left_columns, a),
+                resolve_column(&right_qualified, b),
+            );
+            let swapped = (
+                resolve_column(&left_columns, b),
+                resolve_column(&right_qualified, a),
+            );
+            match (written, swapped) {
+                ((Ok(left), Ok(right)), _) | (_, (Ok(left), Ok(right))) => keys.push((left, right)),
+                // The error is the one of the column that is missing in the order where the other column was found.
+                ((Ok(_), Err(e)), _)
+                | ((Err(e), Ok(_)), _)
+                | (_, (Ok(_), Err(e)))
+                | (_, (Err(e), Ok(_)))
+                | ((Err(e), Err(_)), _) => return Err(e),
+            }
+        }
+
+        let mut rows = Vec::new();
+        for left in left_rows {
+            let mut matched = false;
+            for right in right_rows {
+                let is_pair = keys
+                    .iter()
+                    .all(|(l, r)| left[*l].compare(&right[*r]) == Some(Ordering::Equal));
+                if is_pair {
+                    matched = true;
+                    rows.push([left.clone(), right.clone()].concat());
+                }
+            }
+            if !matched && self.kind == JoinKind::Left {
+                rows.push([left, vec![Value::Null; right_columns.len()]].concat());
+            }
+        }
+
+        let mut columns = left_columns;
+        columns.extend(right_qualified);
+        Ok((columns, rows))
+    }
+}
+
+/// Returns the position of a column in the list of qualified columns.
+///
+/// The column can be qualified (`table.column`) or not, in which case its name must be unique among the columns.
+///
+/// Returns `SqlError::ColumnNotFound` if no column matches, and `SqlError::InvalidColumn` if the name is ambiguous.
+///
+pub fn resolve_column(columns: &QualifiedColumns, name: &str) -> Result<usize, SqlError> {
+    let matches: Vec<usize> = match name.split_once('.') {
+        Some((qualifier, column)) => columns
+            .iter()
+            .enumerate()
+            .filter(|(_, (q, c))| q == qualifier && c == column)
+            .map(|(idx, _)| idx)
+            .collect(),
+        None => columns
+            .iter()
+            .enumerate()
+            .filter(|(_, (_, c))| c == name)
+            .map(|(idx, _)| idx)
+            .collect(),
+    };
+
+    match matches.as_slice() {
+        [idx] => Ok(*idx),
+        [] => Err(SqlError::ColumnNotFound(name.to_string())),
+        _ => Err(SqlError::InvalidColumn),
+    }
+}
+
+impl Display for Join {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let kind = match self.kind {
+            JoinKind::Inner => "INNER JOIN",
+            JoinKind::Left => "LEFT JOIN",
+            JoinKind::Cross => "CROSS JOIN",
+        };
+        write!(f, "{} {}", kind, format_identifier(&self.table_name))?;
+        if let Some(alias) = &self.alias {
+            write!(f, " {}", format_identifier(alias))?;
+        }
+        if !self.on.is_empty() {
+            let pairs: Vec<String> = self
+                .on
+                .iter()
+                .map(|(a, b)| format!("{} = {}", format_identifier(a), format_identifier(b)))
+                .collect();
+            write!(f, " ON {}", pairs.join(" AND "))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{resolve_column, Join, JoinKind};
+    use crate::{errors::SqlError, value::Value};
+
+    fn qualified(columns: &[(&str, &str)]) -> Vec<(String, String)> {
+        columns
+            .iter()
+            .map(|(q, c)| (q.to_string(), c.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn resolve_columns() {
+        let columns = qualified(&[("c", "id"), ("c", "nombre"), ("o", "id")]);
+
+        assert_eq!(resolve_column(&columns, "nombre"), Ok(1));
+        assert_eq!(resolve_column(&columns, "o.id"), Ok(2));
+        assert_eq!(resolve_column(&columns, "id"), Err(SqlError::InvalidColumn));
+        assert_eq!(
+            resolve_column(&columns, "x.id"),
+            Err(SqlError::ColumnNotFound(String::from("x.id")))
+        );
+    }
+
+    #[test]
+    fn apply_joins() {
+        let left_columns = qualified(&[("c", "id"), ("c", "nombre")]);
+        let left_rows = vec![
+            vec![Value::Integer(1), Value::Text(String::from("Juan"))],
+            vec![Value::Integer(2), Value::Text(String::from("Ana"))],
+        ];
+        let right_columns = vec![String::from("id_cliente"), String::from("producto")];
+        let right_rows = vec![
+            vec![Value::Integer(1), Value::Text(String::from("Laptop"))],
+            vec![Value::Integer(1), Value::Text(String::from("Mouse"))],
+        ];
+
+        let mut join = Join {
+            kind: JoinKind::Inner,
+            table_name: String::from("ordenes"),
+            alias: Some(String::from("o")),
+            on: vec![(String::from("o.id_cliente"), String::from("c.id"))],
+        };
+        let (columns, rows) = join
+            .apply(
+                left_columns.clone(),
+                left_rows.clone(),
+                &right_columns,
+                &right_rows,
+            )
+            .unwrap();
+        assert_eq!(columns.len(), 4);
+        assert_eq!(columns[2], (String::from("o"), String::from("id_cliente")));
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[1][3], Value::Text(String::from("Mouse")));
+
+        join.kind = JoinKind::Left;
+        let (_, rows) = join
+            .apply(
+                left_columns.clone(),
+                left_rows.clone(),
+                &right_columns,
+                &right_rows,
+            )
+            .unwrap();
+        assert_eq!(rows.len(), 3);
+        assert_eq!(
+            rows[2],
+            vec![
+                Value::Integer(2),
+                Value::Text(String::from("Ana")),
+                Value::Null,
+                Value::Null
+            ]
+        );
+
+        // The error names the column that is missing, whichever side it is written on.
+        for on in [("c.falta", "o.id_cliente"), ("o.id_cliente", "c.falta")] {
+            join.on = vec![(String::from(on.0), String::from(on.1))];
+            assert_eq!(
+                join.apply(
+                    left_columns.clone(),
+                    left_rows.clone(),
+                    &right_columns,
+                    &right_rows
+                ),
+                Err(SqlError::ColumnNotFound(String::from("c.falta")))
+            );
+        }
+
+        join.kind = JoinKind::Cross;
+        join.on.clear();
+        let (_, rows) = join
+            .apply(left_columns, left_rows, &right_columns, &right_rows)
+            .unwrap();
+        assert_eq!(rows.len(), 4);
+    }
+}
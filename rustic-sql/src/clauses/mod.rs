@@ -1,10 +1,18 @@
+pub mod aggregate;
+pub mod alter_sql;
 pub mod condition;
+pub mod create_sql;
 pub mod delete_sql;
+pub mod drop_sql;
+pub mod expression;
+pub mod groupby_sql;
 pub mod insert_sql;
 pub mod into_sql;
+pub mod join_sql;
 pub mod orderby_sql;
 pub mod recursive_parser;
 pub mod select_sql;
 pub mod set_sql;
+pub mod truncate_sql;
 pub mod update_sql;
 pub mod where_sql;
@@ -1,98 +1,388 @@
+use super::{aggregate::Aggregate, expression::Expression};
 use crate::{
     errors::SqlError,
+    lexer::{unexpected, Token},
     register::Register,
-    utils::{is_by, is_order},
+    value::Value,
 };
-use std::cmp::Ordering;
+use std::{cmp::Ordering, fmt::Display};
 
-/// Struct that epresents the `ORDER BY` SQL clause.
+/// Enum for what a key of the `ORDER BY` clause sorts by.
+///
+/// - `Expression`: The value of an expression, like `apellido` or `cantidad * 2`.
+/// - `Position`: The selected column at this position, counting from 1, like in `ORDER BY 2`.
+///
+#[derive(Debug, PartialEq)]
+pub enum OrderTarget {
+    Expression(Expression),
+    Position(usize),
+}
+
+/// Struct that represents a key of the `ORDER BY` clause, like `edad DESC NULLS LAST`.
+///
+/// # Fields
+///
+/// * `target` - What the registers are sorted by.
+/// * `descending` - Whether the registers are sorted in descending order.
+/// * `nulls_first` - Whether `Null` values go before the others. By default, they go first in ascending order and last in descending order.
+///
+#[derive(Debug, PartialEq)]
+pub struct OrderKey {
+    pub target: OrderTarget,
+    pub descending: bool,
+    pub nulls_first: bool,
+}
+
+impl OrderKey {
+    /// Creates an ascending key that sorts by a column.
+    pub fn new_from_column(column: &str) -> Self {
+        Self {
+            target: OrderTarget::Expression(Expression::Column(column.to_string())),
+            descending: false,
+            nulls_first: true,
+        }
+    }
+
+    /// Returns the same key, sorting in descending order with `Null` values last.
+    pub fn desc(self) -> Self {
+        Self {
+            descending: true,
+            nulls_first: false,
+            ..self
+        }
+    }
+
+    fn value(&self, register: &Register, columns: &[String]) -> Result<Value, SqlError> {
+        match &self.target {
+            OrderTarget::Expression(expression) => expression.evaluate(&register.0),
+            OrderTarget::Position(position) => columns
+                .get(position - 1)
+                .and_then(|column| register.0.get(column))
+                .cloned()
+                .ok_or(SqlError::InvalidColumn),
+        }
+    }
+
+    fn compare(&self, a: &Value, b: &Value) -> Ordering {
+        match (a.is_null(), b.is_null()) {
+            (true, true) => Ordering::Equal,
+            (true, false) if self.nulls_first => Ordering::Less,
+            (true, false) => Ordering::Greater,
+            (false, true) if self.nulls_first => Ordering::Greater,
+            (false, true) => Ordering::Less,
+            (false, false) if self.descending => b.sort_cmp(a),
+            (false, false) => a.sort_cmp(b),
+        }
+    }
+}
+
+/// Struct that represents the `ORDER BY` SQL clause.
 /// The `ORDER BY` clause is used to sort the result set in ascending or descending order in a `SELECT` clause.
 ///
 /// # Fields
 ///
-/// * `columns` - The columns to sort the result set by.
-/// * `order` - The order to sort the result set by. It can be either `ASC` or `DESC`.
+/// * `keys` - The keys to sort the result set by. The registers are sorted by the first key, and the following keys break the ties.
 ///
 #[derive(Debug, PartialEq)]
 pub struct OrderBy {
-    pub columns: Vec<String>,
-    pub order: String,
+    pub keys: Vec<OrderKey>,
 }
 
 impl OrderBy {
-    /// Creates and returns a new `OrderBy` instance from a vector of `&str` tokens.
+    /// Creates and returns a new `OrderBy` instance from the tokens read by the lexer, starting at the position `pos`.
     ///
     /// # Arguments
     ///
-    /// * `tokens` - A vector of `&str` tokens that represent the `ORDER BY` clause.
+    /// * `tokens` - The tokens read by `lexer::tokenize`.
+    /// * `pos` - A mutable reference to the position of the `ORDER` token, which is left after the last key.
     ///
-    /// The tokens should be in the following order: `ORDER`, `BY`, `columns`, `order`.
+    /// The tokens should be in the following order: `ORDER`, `BY`, and then the keys, separated by commas.
     ///
-    /// The `columns` should be comma-separated.
+    /// Each key is an expression or the position of a selected column, optionally followed by `ASC` or `DESC` and by `NULLS FIRST` or `NULLS LAST`.
+    /// If the order is not specified, the key is sorted in ascending order.
     ///
-    /// The `order` can be `ASC` or `DESC`.
-    /// If the `order` is not specified, the result set will be sorted in ascending order.
+    /// Returns `SqlError::UnexpectedToken`, with its offset, for the first token that doesn't follow this syntax, and `SqlError::InvalidColumn` for a position below 1.
     ///
     /// # Examples
     ///
     /// ```
-    /// let tokens = vec!["ORDER", "BY", "name", "DESC"];
-    /// let order_by = OrderBy::new_from_tokens(tokens).unwrap();
-    /// assert_eq!(order_by., OrderBy { columns: vec!["name".to_string()], order: "DESC".to_string() });
+    /// # use rustic_sql::clauses::orderby_sql::OrderBy;
+    /// # use rustic_sql::clauses::orderby_sql::OrderKey;
+    /// # use rustic_sql::lexer;
+    /// let tokens = lexer::tokenize("ORDER BY apellido ASC, edad DESC").unwrap();
+    /// let mut pos = 0;
+    /// let order_by = OrderBy::new_from_tokens(&tokens, &mut pos).unwrap();
+    /// assert_eq!(order_by, OrderBy {
+    ///     keys: vec![
+    ///         OrderKey::new_from_column("apellido"),
+    ///         OrderKey::new_from_column("edad").desc(),
+    ///     ],
+    /// });
     /// ```
     ///
-    pub fn new_from_tokens(tokens: Vec<&str>) -> Result<Self, SqlError> {
-        if tokens.len() < 3 {
-            return Err(SqlError::InvalidSyntax);
+    pub fn new_from_tokens(tokens: &[Token], pos: &mut usize) -> Result<Self, SqlError> {
+        let is_keyword =
+            |pos: usize, keyword: &str| tokens.get(pos).is_some_and(|t| t.is_keyword(keyword));
+        for keyword in ["ORDER", "BY"] {
+            if !is_keyword(*pos, keyword) {
+                return Err(unexpected(tokens, *pos));
+            }
+            *pos += 1;
         }
 
-        let mut columns = Vec::new();
-        let mut order = String::new();
-        let mut i = 0;
+        let mut keys = Vec::new();
+        loop {
+            let target = match Expression::new_from_tokens(tokens, pos)? {
+                Expression::Literal(Value::Integer(position)) if position >= 1 => {
+                    OrderTarget::Position(position as usize)
+                }
+                Expression::Literal(Value::Integer(_)) => return Err(SqlError::InvalidColumn),
+                expression => OrderTarget::Expression(expression),
+            };
+
+            let mut descending = false;
+            if is_keyword(*pos, "ASC") || is_keyword(*pos, "DESC") {
+                descending = is_keyword(*pos, "DESC");
+                *pos += 1;
+            }
 
-        if !is_order(tokens[i]) && !is_by(tokens[i + 1]) {
-            return Err(SqlError::InvalidSyntax);
-        }
+            let mut nulls_first = !descending;
+            if is_keyword(*pos, "NULLS") {
+                nulls_first = is_keyword(*pos + 1, "FIRST");
+                if !nulls_first && !is_keyword(*pos + 1, "LAST") {
+                    return Err(unexpected(tokens, *pos + 1));
+                }
+                *pos += 2;
+            }
 
-        i += 2;
+            keys.push(OrderKey {
+                target,
+                descending,
+                nulls_first,
+            });
 
-        while i < tokens.len() && tokens[i] != "DESC" && tokens[i] != "ASC" {
-            columns.push(tokens[i].to_string());
-            i += 1;
+            if !tokens.get(*pos).is_some_and(|t| t.is_punctuation(',')) {
+                return Ok(Self { keys });
+            }
+            *pos += 1;
         }
+    }
 
-        if i < tokens.len() {
-            order = tokens[i].to_string();
-        }
+    /// Returns the aggregate functions used by the keys.
+    pub fn aggregates(&self) -> Vec<&Aggregate> {
+        self.keys
+            .iter()
+            .flat_map(|key| match &key.target {
+                OrderTarget::Expression(expression) => expression.aggregates(),
+                OrderTarget::Position(_) => Vec::new(),
+            })
+            .collect()
+    }
 
-        Ok(Self { columns, order })
+    /// Returns the names of the columns used by the keys.
+    pub fn columns(&self) -> Vec<&str> {
+        self.keys
+            .iter()
+            .flat_map(|key| match &key.target {
+                OrderTarget::Expression(expression) => expression.columns(),
+                OrderTarget::Position(_) => Vec::new(),
+            })
+            .collect()
     }
 
-    /// Sorts the registers by the columns and order specified in the `ORDER BY` clause.
+    /// Sorts the registers by the keys of the `ORDER BY` clause.
+    ///
+    /// The values are compared taking their types into account, so numbers are sorted numerically. Registers with equal keys keep their order.
+    ///
+    /// Returns `SqlError::InvalidColumn` if a key uses a column that is not in the registers or a position out of the selected columns.
     ///
     /// # Arguments
     ///
-    /// * `registers` - A mutable reference to a vector of `Register`.
+    /// * `registers` - The registers to sort.
+    /// * `columns` - The selected columns, used to resolve the positions.
     ///
-    pub fn execute<'a>(&self, registers: &'a mut Vec<Register>) -> &'a Vec<Register> {
-        registers.sort_by(|val_a, val_b| {
-            let mut result = Ordering::Equal;
-            for column in &self.columns {
-                if let Some(val_a) = val_a.0.get(column) {
-                    if let Some(val_b) = val_b.0.get(column) {
-                        result = if self.order == "DESC" {
-                            val_b.cmp(val_a)
-                        } else {
-                            val_a.cmp(val_b)
-                        };
-                        if result != Ordering::Equal {
-                            break;
-                        }
-                    }
-                }
-            }
-            result
+    pub fn execute(
+        &self,
+        registers: Vec<Register>,
+        columns: &[String],
+    ) -> Result<Vec<Register>, SqlError> {
+        let mut sorted = Vec::new();
+        for register in registers {
+            let values = self
+                .keys
+                .iter()
+                .map(|key| key.value(&register, columns))
+                .collect::<Result<Vec<Value>, SqlError>>()?;
+            sorted.push((values, register));
+        }
+
+        sorted.sort_by(|(values_a, _), (values_b, _)| {
+            self.keys
+                .iter()
+                .zip(values_a.iter().zip(values_b))
+                .map(|(key, (a, b))| key.compare(a, b))
+                .find(|ordering| *ordering != Ordering::Equal)
+                .unwrap_or(Ordering::Equal)
         });
-        registers
+
+        Ok(sorted.into_iter().map(|(_, register)| register).collect())
+    }
+}
+
+impl Display for OrderKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.target {
+            OrderTarget::Expression(expression) => write!(f, "{}", expression)?,
+            OrderTarget::Position(position) => write!(f, "{}", position)?,
+        }
+        if self.descending {
+            write!(f, " DESC")?;
+        }
+        // By default, `Null` values go first in ascending order and last in descending order.
+        match (self.nulls_first, self.descending) {
+            (true, true) => write!(f, " NULLS FIRST"),
+            (false, false) => write!(f, " NULLS LAST"),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl Display for OrderBy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let keys: Vec<String> = self.keys.iter().map(|key| key.to_string()).collect();
+        write!(f, "ORDER BY {}", keys.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{OrderBy, OrderKey, OrderTarget};
+    use crate::{
+        clauses::expression::Expression, errors::SqlError, lexer::tokenize, register::Register,
+        value::Value,
+    };
+    use std::collections::HashMap;
+
+    fn register(apellido: &str, edad: Value) -> Register {
+        Register(HashMap::from([
+            (String::from("apellido"), Value::Text(apellido.to_string())),
+            (String::from("edad"), edad),
+        ]))
+    }
+
+    fn parse(query: &str) -> Result<OrderBy, SqlError> {
+        let tokens = tokenize(query).unwrap();
+        let mut pos = 0;
+        let order_by = OrderBy::new_from_tokens(&tokens, &mut pos)?;
+        assert_eq!(pos, tokens.len());
+        Ok(order_by)
+    }
+
+    fn sorted_ages(query: &str) -> Vec<Value> {
+        let registers = vec![
+            register("López", Value::Integer(30)),
+            register("Pérez", Value::Null),
+            register("López", Value::Integer(18)),
+            register("Gómez", Value::Integer(40)),
+        ];
+        let columns = vec![String::from("apellido"), String::from("edad")];
+
+        parse(query)
+            .unwrap()
+            .execute(registers, &columns)
+            .unwrap()
+            .into_iter()
+            .map(|r| r.0["edad"].clone())
+            .collect()
+    }
+
+    #[test]
+    fn new_from_tokens() {
+        let order_by = parse("ORDER BY edad * 2 DESC, 1 NULLS LAST").unwrap();
+
+        assert_eq!(order_by.keys.len(), 2);
+        assert!(matches!(
+            order_by.keys[0].target,
+            OrderTarget::Expression(Expression::Binary { .. })
+        ));
+        assert!(order_by.keys[0].descending && !order_by.keys[0].nulls_first);
+        assert_eq!(
+            order_by.keys[1],
+            OrderKey {
+                target: OrderTarget::Position(1),
+                descending: false,
+                nulls_first: false,
+            }
+        );
+        assert_eq!(order_by.columns(), vec!["edad"]);
+
+        let unexpected = |token: &str, offset: usize| {
+            Err(SqlError::UnexpectedToken {
+                token: token.to_string(),
+                offset: Some(offset),
+            })
+        };
+        assert_eq!(parse("ORDER BY edad NULLS"), unexpected("", 19));
+        assert_eq!(parse("ORDER BY edad NULLS DESC"), unexpected("DESC", 20));
+        assert_eq!(parse("ORDER edad"), unexpected("edad", 6));
+        assert_eq!(parse("ORDER BY edad,"), unexpected("", 14));
+        assert_eq!(parse("ORDER BY 0"), Err(SqlError::InvalidColumn));
+    }
+
+    #[test]
+    fn execute_with_directions_and_nulls() {
+        assert_eq!(
+            sorted_ages("ORDER BY apellido DESC, edad ASC"),
+            vec![
+                Value::Null,
+                Value::Integer(18),
+                Value::Integer(30),
+                Value::Integer(40)
+            ]
+        );
+        assert_eq!(
+            sorted_ages("ORDER BY edad DESC"),
+            vec![
+                Value::Integer(40),
+                Value::Integer(30),
+                Value::Integer(18),
+                Value::Null
+            ]
+        );
+        assert_eq!(
+            sorted_ages("ORDER BY 2 NULLS LAST"),
+            vec![
+                Value::Integer(18),
+                Value::Integer(30),
+                Value::Integer(40),
+                Value::Null
+            ]
+        );
+        assert_eq!(
+            sorted_ages("ORDER BY 0 - edad"),
+            vec![
+                Value::Null,
+                Value::Integer(40),
+                Value::Integer(30),
+                Value::Integer(18)
+            ]
+        );
+    }
+
+    #[test]
+    fn execute_with_invalid_keys() {
+        let registers = vec![register("López", Value::Integer(30))];
+        let columns = vec![String::from("apellido")];
+
+        let order_by = parse("ORDER BY 3").unwrap();
+        assert_eq!(
+            order_by.execute(registers.clone(), &columns),
+            Err(SqlError::InvalidColumn)
+        );
+        let order_by = parse("ORDER BY nombre").unwrap();
+        assert_eq!(
+            order_by.execute(registers, &columns),
+            Err(SqlError::ColumnNotFound(String::from("nombre")))
+        );
     }
 }
@@ -1,37 +1,44 @@
 use super::condition::Condition;
 use crate::{
     errors::SqlError,
+    lexer::{expect_punctuation, Token},
     logical_operator::LogicalOperator,
-    utils::{is_and, is_left_paren, is_not, is_or, is_right_paren},
 };
 
-/// Parses a condition from a vector of tokens.
+/// Parses a condition from the tokens read by the lexer, starting at the position `pos`.
 ///
 /// The condition can be a simple condition or a complex condition.
 /// A simple condition contains a field, an operator and a value.
 /// A complex condition contains a left condition, a logical operator, such as AND, OR or NOT, and a right condition.
 ///
-/// Returns a `Condition` instance.
+/// Stops at the first token that can't continue the condition, leaving `pos` there.
+/// Returns `SqlError::UnexpectedToken`, with its offset, for the first token that doesn't follow the syntax of a condition.
 ///
 /// # Arguments
 ///
-/// * `tokens` - A vector of tokens that can be used to build a `Condition` instance.
-/// * `pos` - A mutable reference to a `usize` that represents the current position in the tokens vector.
+/// * `tokens` - The tokens read by `lexer::tokenize`.
+/// * `pos` - A mutable reference to a `usize` that represents the current position in the tokens.
 ///
 /// # Examples
 ///
 /// ```
-/// let tokens = vec!["city", "=", "Gaiman"];
+/// # use rustic_sql::Value;
+/// # use rustic_sql::clauses::condition::Condition;
+/// # use rustic_sql::clauses::recursive_parser::parse_condition;
+/// # use rustic_sql::lexer;
+/// # use rustic_sql::logical_operator::LogicalOperator;
+/// # use rustic_sql::operator::Operator;
+/// let tokens = lexer::tokenize("city = 'Gaiman'").unwrap();
 /// let mut pos = 0;
 /// let condition = parse_condition(&tokens, &mut pos).unwrap();
 ///
 /// assert_eq!(condition, Condition::Simple {
 ///     field: "city".to_string(),
 ///     operator: Operator::Equal,
-///     value: "Gaiman".to_string(),
+///     value: Value::Text("Gaiman".to_string()),
 /// });
 ///
-/// let tokens = vec!["city", "=", "Gaiman", "AND", "age", "<", "30"];
+/// let tokens = lexer::tokenize("city = 'Gaiman' AND age < 30").unwrap();
 /// let mut pos = 0;
 /// let condition = parse_condition(&tokens, &mut pos).unwrap();
 ///
@@ -39,22 +46,22 @@ use crate::{
 ///     left: Some(Box::new(Condition::Simple {
 ///         field: "city".to_string(),
 ///         operator: Operator::Equal,
-///         value: "Gaiman".to_string(),
+///         value: Value::Text("Gaiman".to_string()),
 ///         })),
 ///     operator: LogicalOperator::And,
 ///     right: Box::new(Condition::Simple {
 ///         field: "age".to_string(),
 ///         operator: Operator::Lesser,
-///         value: "30".to_string(),
+///         value: Value::Integer(30),
 ///         }),
 ///     });
 /// ```
 ///
-pub fn parse_condition(tokens: &Vec<&str>, pos: &mut usize) -> Result<Condition, SqlError> {
+pub fn parse_condition(tokens: &[Token], pos: &mut usize) -> Result<Condition, SqlError> {
     let mut left = parse_or(tokens, pos)?;
 
     while let Some(token) = tokens.get(*pos) {
-        if is_or(token) {
+        if token.is_keyword("OR") {
             *pos += 1;
             let right = parse_or(tokens, pos)?;
             left = Condition::new_complex(Some(left), LogicalOperator::Or, right);
@@ -65,11 +72,11 @@ pub fn parse_condition(tokens: &Vec<&str>, pos: &mut usize) -> Result<Condition,
     Ok(left)
 }
 
-fn parse_or(tokens: &Vec<&str>, pos: &mut usize) -> Result<Condition, SqlError> {
+fn parse_or(tokens: &[Token], pos: &mut usize) -> Result<Condition, SqlError> {
     let mut left = parse_and(tokens, pos)?;
 
     while let Some(token) = tokens.get(*pos) {
-        if is_and(token) {
+        if token.is_keyword("AND") {
             *pos += 1;
             let right = parse_and(tokens, pos)?;
             left = Condition::new_complex(Some(left), LogicalOperator::And, right);
@@ -80,54 +87,61 @@ fn parse_or(tokens: &Vec<&str>, pos: &mut usize) -> Result<Condition, SqlError>
     Ok(left)
 }
 
-fn parse_and(tokens: &Vec<&str>, pos: &mut usize) -> Result<Condition, SqlError> {
-    if let Some(token) = tokens.get(*pos) {
-        if is_not(token) {
-            *pos += 1;
-            let expr = parse_and(tokens, pos)?;
-            Ok(Condition::new_complex(None, LogicalOperator::Not, expr))
-        } else {
-            parse_base(tokens, pos)
-        }
+fn parse_and(tokens: &[Token], pos: &mut usize) -> Result<Condition, SqlError> {
+    if tokens.get(*pos).is_some_and(|t| t.is_keyword("NOT")) {
+        *pos += 1;
+        let expr = parse_and(tokens, pos)?;
+        Ok(Condition::new_complex(None, LogicalOperator::Not, expr))
     } else {
         parse_base(tokens, pos)
     }
 }
 
-fn parse_base(tokens: &Vec<&str>, pos: &mut usize) -> Result<Condition, SqlError> {
-    if let Some(token) = tokens.get(*pos) {
-        if is_left_paren(token) {
-            *pos += 1;
-            let expr = parse_condition(tokens, pos)?;
-            let next_token = tokens.get(*pos).ok_or(SqlError::Error)?;
-            if is_right_paren(next_token) {
-                *pos += 1;
-                Ok(expr)
-            } else {
-                Err(SqlError::Error)
-            }
+/// Parses a condition between parentheses, like `(a = 1 OR b = 2)`, or a simple condition.
+///
+/// A parenthesis can also open the expression of a simple condition, like in `(a + b) > 2`, so that is tried when the tokens are not a whole condition between parentheses.
+/// If neither works, the error is the one found further in the tokens.
+///
+fn parse_base(tokens: &[Token], pos: &mut usize) -> Result<Condition, SqlError> {
+    if !tokens.get(*pos).is_some_and(|t| t.is_punctuation('(')) {
+        return Condition::new_simple_from_tokens(tokens, pos);
+    }
+
+    let start = *pos;
+    *pos += 1;
+    let group = parse_condition(tokens, pos)
+        .and_then(|condition| expect_punctuation(tokens, pos, ')').map(|_| condition));
+    let group_error = match group {
+        Ok(condition) => return Ok(condition),
+        Err(error) => error,
+    };
+
+    *pos = start;
+    Condition::new_simple_from_tokens(tokens, pos).map_err(|error| {
+        if error.offset() >= group_error.offset() {
+            error
         } else {
-            let simple_condition = Condition::new_simple_from_tokens(tokens, pos)?;
-            Ok(simple_condition)
+            group_error
         }
-    } else {
-        Err(SqlError::Error)
-    }
+    })
 }
 
 #[cfg(test)]
 mod tests {
     use crate::{
         clauses::recursive_parser::{parse_condition, Condition},
+        errors::SqlError,
+        lexer::tokenize,
         logical_operator::LogicalOperator,
         operator::Operator,
+        value::Value,
     };
 
     #[test]
     fn simple_conditions() {
-        let tokens1 = vec!["city", "=", "Gaiman"];
-        let tokens2 = vec!["age", "<", "30"];
-        let tokens3 = vec!["age", ">", "18"];
+        let tokens1 = tokenize("city = 'Gaiman'").unwrap();
+        let tokens2 = tokenize("age < 30").unwrap();
+        let tokens3 = tokenize("age > 18").unwrap();
         let mut pos = 0;
         let condition1 = parse_condition(&tokens1, &mut pos).unwrap();
         pos = 0;
@@ -140,7 +154,7 @@ mod tests {
             Condition::Simple {
                 field: String::from("city"),
                 operator: Operator::Equal,
-                value: String::from("Gaiman"),
+                value: Value::Text(String::from("Gaiman")),
             }
         );
         assert_eq!(
@@ -148,7 +162,7 @@ mod tests {
             Condition::Simple {
                 field: String::from("age"),
                 operator: Operator::Lesser,
-                value: String::from("30"),
+                value: Value::Integer(30),
             }
         );
         assert_eq!(
@@ -156,14 +170,14 @@ mod tests {
             Condition::Simple {
                 field: String::from("age"),
                 operator: Operator::Greater,
-                value: String::from("18"),
+                value: Value::Integer(18),
             }
         );
     }
 
     #[test]
     fn not() {
-        let tokens = vec!["NOT", "city", "=", "Gaiman"];
+        let tokens = tokenize("NOT city = 'Gaiman'").unwrap();
         let mut pos = 0;
         let condition = parse_condition(&tokens, &mut pos).unwrap();
         assert_eq!(
@@ -174,7 +188,7 @@ mod tests {
                 right: Box::new(Condition::Simple {
                     field: String::from("city"),
                     operator: Operator::Equal,
-                    value: String::from("Gaiman")
+                    value: Value::Text(String::from("Gaiman"))
                 })
             }
         )
@@ -182,7 +196,7 @@ mod tests {
 
     #[test]
     fn one_or() {
-        let tokens = vec!["city", "=", "Gaiman", "OR", "age", "<", "30"];
+        let tokens = tokenize("city = 'Gaiman' OR age < 30").unwrap();
         let mut pos = 0;
         let condition = parse_condition(&tokens, &mut pos).unwrap();
         assert_eq!(
@@ -191,13 +205,13 @@ mod tests {
                 left: Some(Box::new(Condition::Simple {
                     field: String::from("city"),
                     operator: Operator::Equal,
-                    value: String::from("Gaiman")
+                    value: Value::Text(String::from("Gaiman"))
                 })),
                 operator: LogicalOperator::Or,
                 right: Box::new(Condition::Simple {
                     field: String::from("age"),
                     operator: Operator::Lesser,
-                    value: String::from("30")
+                    value: Value::Integer(30)
                 })
             }
         )
@@ -205,9 +219,7 @@ mod tests {
 
     #[test]
     fn two_or() {
-        let tokens = vec![
-            "city", "=", "Gaiman", "OR", "age", "<", "30", "OR", "lastname", "=", "Davies",
-        ];
+        let tokens = tokenize("city = 'Gaiman' OR age < 30 OR lastname = 'Davies'").unwrap();
         let mut pos = 0;
         let condition = parse_condition(&tokens, &mut pos).unwrap();
         assert_eq!(
@@ -217,20 +229,20 @@ mod tests {
                     left: Some(Box::new(Condition::Simple {
                         field: String::from("city"),
                         operator: Operator::Equal,
-                        value: String::from("Gaiman")
+                        value: Value::Text(String::from("Gaiman"))
                     })),
                     operator: LogicalOperator::Or,
                     right: Box::new(Condition::Simple {
                         field: String::from("age"),
                         operator: Operator::Lesser,
-                        value: String::from("30")
+                        value: Value::Integer(30)
                     })
                 })),
                 operator: LogicalOperator::Or,
                 right: Box::new(Condition::Simple {
                     field: String::from("lastname"),
                     operator: Operator::Equal,
-                    value: String::from("Davies")
+                    value: Value::Text(String::from("Davies"))
                 })
             }
         )
@@ -238,7 +250,7 @@ mod tests {
 
     #[test]
     fn one_and() {
-        let tokens = vec!["city", "=", "Gaiman", "AND", "age", "<", "30"];
+        let tokens = tokenize("city = 'Gaiman' AND age < 30").unwrap();
         let mut pos = 0;
         let condition = parse_condition(&tokens, &mut pos).unwrap();
         assert_eq!(
@@ -247,13 +259,13 @@ mod tests {
                 left: Some(Box::new(Condition::Simple {
                     field: String::from("city"),
                     operator: Operator::Equal,
-                    value: String::from("Gaiman")
+                    value: Value::Text(String::from("Gaiman"))
                 })),
                 operator: LogicalOperator::And,
                 right: Box::new(Condition::Simple {
                     field: String::from("age"),
                     operator: Operator::Lesser,
-                    value: String::from("30")
+                    value: Value::Integer(30)
                 })
             }
         )
@@ -261,9 +273,7 @@ mod tests {
 
     #[test]
     fn two_and() {
-        let tokens = vec![
-            "city", "=", "Gaiman", "AND", "age", "<", "30", "AND", "lastname", "=", "Davies",
-        ];
+        let tokens = tokenize("city = 'Gaiman' AND age < 30 AND lastname = 'Davies'").unwrap();
         let mut pos = 0;
         let condition = parse_condition(&tokens, &mut pos).unwrap();
         assert_eq!(
@@ -273,20 +283,20 @@ mod tests {
                     left: Some(Box::new(Condition::Simple {
                         field: String::from("city"),
                         operator: Operator::Equal,
-                        value: String::from("Gaiman")
+                        value: Value::Text(String::from("Gaiman"))
                     })),
                     operator: LogicalOperator::And,
                     right: Box::new(Condition::Simple {
                         field: String::from("age"),
                         operator: Operator::Lesser,
-                        value: String::from("30")
+                        value: Value::Integer(30)
                     })
                 })),
                 operator: LogicalOperator::And,
                 right: Box::new(Condition::Simple {
                     field: String::from("lastname"),
                     operator: Operator::Equal,
-                    value: String::from("Davies")
+                    value: Value::Text(String::from("Davies"))
                 })
             }
         )
@@ -294,9 +304,7 @@ mod tests {
 
     #[test]
     fn and_or() {
-        let tokens = vec![
-            "city", "=", "Gaiman", "AND", "age", ">", "18", "OR", "lastname", "=", "Davies",
-        ];
+        let tokens = tokenize("city = 'Gaiman' AND age > 18 OR lastname = 'Davies'").unwrap();
         let mut pos = 0;
         let condition = parse_condition(&tokens, &mut pos).unwrap();
         assert_eq!(
@@ -306,20 +314,20 @@ mod tests {
                     left: Some(Box::new(Condition::Simple {
                         field: String::from("city"),
                         operator: Operator::Equal,
-                        value: String::from("Gaiman")
+                        value: Value::Text(String::from("Gaiman"))
                     })),
                     operator: LogicalOperator::And,
                     right: Box::new(Condition::Simple {
                         field: String::from("age"),
                         operator: Operator::Greater,
-                        value: String::from("18")
+                        value: Value::Integer(18)
                     })
                 })),
                 operator: LogicalOperator::Or,
                 right: Box::new(Condition::Simple {
                     field: String::from("lastname"),
                     operator: Operator::Equal,
-                    value: String::from("Davies")
+                    value: Value::Text(String::from("Davies"))
                 })
             }
         )
@@ -327,9 +335,7 @@ mod tests {
 
     #[test]
     fn not_and_or() {
-        let tokens = vec![
-            "NOT", "city", "=", "Gaiman", "AND", "age", ">", "18", "OR", "lastname", "=", "Davies",
-        ];
+        let tokens = tokenize("NOT city = 'Gaiman' AND age > 18 OR lastname = 'Davies'").unwrap();
         let mut pos = 0;
         let condition = parse_condition(&tokens, &mut pos).unwrap();
         assert_eq!(
@@ -342,21 +348,21 @@ mod tests {
                         right: Box::new(Condition::Simple {
                             field: String::from("city"),
                             operator: Operator::Equal,
-                            value: String::from("Gaiman")
+                            value: Value::Text(String::from("Gaiman"))
                         })
                     })),
                     operator: LogicalOperator::And,
                     right: Box::new(Condition::Simple {
                         field: String::from("age"),
                         operator: Operator::Greater,
-                        value: String::from("18")
+                        value: Value::Integer(18)
                     })
                 })),
                 operator: LogicalOperator::Or,
                 right: Box::new(Condition::Simple {
                     field: String::from("lastname"),
                     operator: Operator::Equal,
-                    value: String::from("Davies")
+                    value: Value::Text(String::from("Davies"))
                 })
             }
         )
@@ -364,7 +370,7 @@ mod tests {
 
     #[test]
     fn and_not() {
-        let tokens = vec!["city", "=", "Gaiman", "AND", "NOT", "age", ">", "18"];
+        let tokens = tokenize("city = 'Gaiman' AND NOT age > 18").unwrap();
         let mut pos = 0;
         let condition = parse_condition(&tokens, &mut pos).unwrap();
         assert_eq!(
@@ -373,7 +379,7 @@ mod tests {
                 left: Some(Box::new(Condition::Simple {
                     field: String::from("city"),
                     operator: Operator::Equal,
-                    value: String::from("Gaiman")
+                    value: Value::Text(String::from("Gaiman"))
                 })),
                 operator: LogicalOperator::And,
                 right: Box::new(Condition::Complex {
@@ -382,7 +388,7 @@ mod tests {
                     right: Box::new(Condition::Simple {
                         field: String::from("age"),
                         operator: Operator::Greater,
-                        value: String::from("18")
+                        value: Value::Integer(18)
                     })
                 })
             }
@@ -391,7 +397,7 @@ mod tests {
 
     #[test]
     fn or_not() {
-        let tokens = vec!["city", "=", "Gaiman", "OR", "NOT", "age", ">", "18"];
+        let tokens = tokenize("city = 'Gaiman' OR NOT age > 18").unwrap();
         let mut pos = 0;
         let condition = parse_condition(&tokens, &mut pos).unwrap();
         assert_eq!(
@@ -400,7 +406,7 @@ mod tests {
                 left: Some(Box::new(Condition::Simple {
                     field: String::from("city"),
                     operator: Operator::Equal,
-                    value: String::from("Gaiman")
+                    value: Value::Text(String::from("Gaiman"))
                 })),
                 operator: LogicalOperator::Or,
                 right: Box::new(Condition::Complex {
@@ -409,7 +415,7 @@ mod tests {
                     right: Box::new(Condition::Simple {
                         field: String::from("age"),
                         operator: Operator::Greater,
-                        value: String::from("18")
+                        value: Value::Integer(18)
                     })
                 })
             }
@@ -418,10 +424,7 @@ mod tests {
 
     #[test]
     fn and_or_with_paren() {
-        let tokens = vec![
-            "city", "=", "Gaiman", "AND", "(", "age", ">", "18", "OR", "lastname", "=", "Davies",
-            ")",
-        ];
+        let tokens = tokenize("city = 'Gaiman' AND (age > 18 OR lastname = 'Davies')").unwrap();
         let mut pos = 0;
         let condition = parse_condition(&tokens, &mut pos).unwrap();
         assert_eq!(
@@ -430,20 +433,20 @@ mod tests {
                 left: Some(Box::new(Condition::Simple {
                     field: String::from("city"),
                     operator: Operator::Equal,
-                    value: String::from("Gaiman")
+                    value: Value::Text(String::from("Gaiman"))
                 })),
                 operator: LogicalOperator::And,
                 right: Box::new(Condition::Complex {
                     left: Some(Box::new(Condition::Simple {
                         field: String::from("age"),
                         operator: Operator::Greater,
-                        value: String::from("18")
+                        value: Value::Integer(18)
                     })),
                     operator: LogicalOperator::Or,
                     right: Box::new(Condition::Simple {
                         field: String::from("lastname"),
                         operator: Operator::Equal,
-                        value: String::from("Davies")
+                        value: Value::Text(String::from("Davies"))
                     })
                 })
             }
@@ -452,10 +455,8 @@ mod tests {
 
     #[test]
     fn not_and_or_with_paren() {
-        let tokens = vec![
-            "NOT", "(", "city", "=", "Gaiman", "AND", "(", "age", ">", "18", "OR", "lastname", "=",
-            "Davies", ")", ")",
-        ];
+        let tokens =
+            tokenize("NOT (city = 'Gaiman' AND (age > 18 OR lastname = 'Davies'))").unwrap();
         let mut pos = 0;
         let condition = parse_condition(&tokens, &mut pos).unwrap();
         assert_eq!(
@@ -467,24 +468,56 @@ mod tests {
                     left: Some(Box::new(Condition::Simple {
                         field: String::from("city"),
                         operator: Operator::Equal,
-                        value: String::from("Gaiman")
+                        value: Value::Text(String::from("Gaiman"))
                     })),
                     operator: LogicalOperator::And,
                     right: Box::new(Condition::Complex {
                         left: Some(Box::new(Condition::Simple {
                             field: String::from("age"),
                             operator: Operator::Greater,
-                            value: String::from("18")
+                            value: Value::Integer(18)
                         })),
                         operator: LogicalOperator::Or,
                         right: Box::new(Condition::Simple {
                             field: String::from("lastname"),
                             operator: Operator::Equal,
-                            value: String::from("Davies")
+                            value: Value::Text(String::from("Davies"))
                         })
                     })
                 })
             }
         )
     }
+
+    #[test]
+    fn parentheses_around_expressions() {
+        let tokens = tokenize("(age + 1) * 2 > 18 AND (city) = 'Gaiman' ORDER").unwrap();
+        let mut pos = 0;
+        let condition = parse_condition(&tokens, &mut pos).unwrap();
+
+        assert_eq!(
+            condition.to_string(),
+            "(age + 1) * 2 > 18 AND city = 'Gaiman'"
+        );
+        assert_eq!(pos, tokens.len() - 1);
+    }
+
+    #[test]
+    fn errors_with_offsets() {
+        let error = |query: &str| {
+            let tokens = tokenize(query).unwrap();
+            let mut pos = 0;
+            parse_condition(&tokens, &mut pos).unwrap_err()
+        };
+        let unexpected = |token: &str, offset: usize| SqlError::UnexpectedToken {
+            token: token.to_string(),
+            offset: Some(offset),
+        };
+
+        assert_eq!(error("age >"), unexpected("", 5));
+        assert_eq!(error("age 18"), unexpected("18", 4));
+        assert_eq!(error("(age > 18 OR city = )"), unexpected(")", 20));
+        assert_eq!(error("(age + 1 > 18"), unexpected("", 13));
+        assert_eq!(error("NOT"), unexpected("", 3));
+    }
 }
@@ -1,14 +1,20 @@
-use super::{orderby_sql::OrderBy, where_sql::Where};
+use super::{
+    aggregate::Aggregate,
+    expression::Expression,
+    groupby_sql::GroupBy,
+    join_sql::{resolve_column, Join, QualifiedColumns},
+    orderby_sql::OrderBy,
+    where_sql::Where,
+};
 use crate::{
-    errors::SqlError,
-    register::Register,
-    table::Table,
-    utils::{find_file_in_folder, is_by, is_from, is_order, is_select, is_where},
+    csv::Csv, errors::SqlError, lexer::format_identifier, register::Register, table::Table,
+    utils::find_file_in_folder, value::Value,
 };
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
+    fmt::Display,
     fs::File,
-    io::{BufRead, BufReader},
+    io::BufReader,
 };
 
 /// Struct that represents the `SELECT` SQL clause.
@@ -17,132 +23,54 @@ use std::{
 /// # Fields
 ///
 /// * `table_name` - The name of the table to select data from.
-/// * `columns` - The columns to select from the table.
+/// * `table_alias` - The alias of the table, used to qualify its columns.
+/// * `joins` - The tables joined to the table, in order.
+/// * `columns` - The names of the selected columns, which are the header of the result set. A column with an alias, like `nombre AS name`, is named by its alias, and a computed column without one by its expression. These names can be used in `ORDER BY`.
+/// * `expressions` - The expressions of the computed columns, like `cantidad * 2 AS doble`, by column name.
 /// * `where_clause` - The `WHERE` clause to filter the result set.
+/// * `groupby_clause` - The `GROUP BY` clause to group the result set before applying the aggregate functions.
+/// * `having_clause` - The `HAVING` clause to filter the groups.
 /// * `orderby_clause` - The `ORDER BY` clause to sort the result set.
+/// * `distinct` - Whether repeated registers are removed from the result set.
+/// * `limit` - The maximum number of registers in the result set.
+/// * `offset` - The number of registers skipped at the start of the result set.
 ///
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Default, PartialEq)]
 pub struct Select {
     pub table_name: String,
+    pub table_alias: Option<String>,
+    pub joins: Vec<Join>,
     pub columns: Vec<String>,
+    pub expressions: Vec<(String, Expression)>,
     pub where_clause: Option<Where>,
+    pub groupby_clause: Option<GroupBy>,
+    pub having_clause: Option<Where>,
     pub orderby_clause: Option<OrderBy>,
+    pub distinct: bool,
+    pub limit: Option<usize>,
+    pub offset: usize,
 }
 
-fn parse_columns<'a>(tokens: &'a [String], i: &mut usize) -> Result<Vec<&'a String>, SqlError> {
-    let mut columns = Vec::new();
-    if is_select(&tokens[*i]) {
-        if *i < tokens.len() {
-            *i += 1;
-            while !is_from(&tokens[*i]) && *i < tokens.len() {
-                columns.push(&tokens[*i]);
-                *i += 1;
-            }
-        }
-    } else {
-        return Err(SqlError::InvalidSyntax);
+fn read_table(
+    table: BufReader<File>,
+    csv: &Csv,
+) -> Result<(Vec<String>, Vec<Vec<Value>>), SqlError> {
+    let mut records = csv.records(table);
+    let columns = records.header()?;
+
+    let mut rows = Vec::new();
+    for record in records {
+        let fields = record?;
+        let row = (0..columns.len())
+            .map(|idx| Value::from_field(fields.get(idx)))
+            .collect();
+        rows.push(row);
     }
-    Ok(columns)
-}
 
-fn parse_table_name(tokens: &[String], i: &mut usize) -> Result<String, SqlError> {
-    if *i < tokens.len() && is_from(&tokens[*i]) {
-        *i += 1;
-        let table_name = tokens[*i].to_string();
-        *i += 1;
-        Ok(table_name)
-    } else {
-        Err(SqlError::InvalidSyntax)
-    }
-}
-
-fn parse_where_and_orderby<'a>(
-    tokens: &'a [String],
-    i: &mut usize,
-) -> Result<(Vec<&'a str>, Vec<&'a str>), SqlError> {
-    let mut where_tokens = Vec::new();
-    let mut orderby_tokens = Vec::new();
-
-    if *i < tokens.len() {
-        if is_where(&tokens[*i]) {
-            while *i < tokens.len() && !is_order(&tokens[*i]) {
-                where_tokens.push(tokens[*i].as_str());
-                *i += 1;
-            }
-        }
-        if *i < tokens.len() && is_order(&tokens[*i]) {
-            orderby_tokens.push(tokens[*i].as_str());
-            *i += 1;
-            if *i < tokens.len() && is_by(&tokens[*i]) {
-                while *i < tokens.len() {
-                    orderby_tokens.push(tokens[*i].as_str());
-                    *i += 1;
-                }
-            }
-        }
-    }
-    Ok((where_tokens, orderby_tokens))
-}
-
-fn convert_line_to_register(line: String, columns: &[String]) -> Register {
-    let attributes: Vec<String> = line.split(',').map(|s| s.to_string()).collect();
-    let mut original = Register(HashMap::new());
-    for (idx, col) in columns.iter().enumerate() {
-        original
-            .0
-            .insert(col.to_string(), attributes[idx].to_string());
-    }
-
-    original
+    Ok((columns, rows))
 }
 
 impl Select {
-    /// Creates and returns a new `Select` instance from a vector of `String` tokens.
-    ///
-    /// # Arguments
-    ///
-    /// * `tokens` - A vector of `String` tokens that represent the `SELECT` clause.
-    ///
-    /// The tokens should be in the following order: `SELECT`, `columns`, `FROM`, `table_name`, `WHERE`, `condition`, `ORDER`, `BY`, `columns`, `order`.
-    ///
-    /// The `columns` should be comma-separated.
-    ///
-    pub fn new_from_tokens(tokens: Vec<String>) -> Result<Self, SqlError> {
-        if tokens.len() < 4 {
-            return Err(SqlError::InvalidSyntax);
-        }
-
-        let mut i = 0;
-
-        let columns = parse_columns(&tokens, &mut i)?;
-        let table_name = parse_table_name(&tokens, &mut i)?;
-
-        if columns.is_empty() || table_name.is_empty() {
-            return Err(SqlError::InvalidSyntax);
-        }
-
-        let (where_tokens, orderby_tokens) = parse_where_and_orderby(&tokens, &mut i)?;
-
-        let where_clause = if !where_tokens.is_empty() {
-            Some(Where::new_from_tokens(where_tokens)?)
-        } else {
-            None
-        };
-
-        let orderby_clause = if !orderby_tokens.is_empty() {
-            Some(OrderBy::new_from_tokens(orderby_tokens)?)
-        } else {
-            None
-        };
-
-        Ok(Self {
-            table_name,
-            columns: columns.iter().map(|c| c.to_string()).collect(),
-            where_clause,
-            orderby_clause,
-        })
-    }
-
     fn filter_columns(&self, columns: &Vec<String>, registers: Vec<Register>) -> Vec<Register> {
         let mut cols_selected = Vec::new();
         if self.columns[0] == "*" {
@@ -157,7 +85,7 @@ impl Select {
 
         let mut filtered_registers = Vec::new();
         for register in registers {
-            let filtered: HashMap<String, String> = register
+            let filtered: HashMap<String, Value> = register
                 .0
                 .into_iter()
                 .filter(|(key, _)| cols_selected.contains(key))
@@ -174,39 +102,283 @@ impl Select {
     /// # Arguments
     ///
     /// * `table` - A `BufReader<File>` that represents the table to apply the `SELECT` clause to.
+    /// * `csv` - The codec of the table file.
     ///
-    pub fn apply_to_table(&self, table: BufReader<File>) -> Result<Table, SqlError> {
+    pub fn apply_to_table(&self, table: BufReader<File>, csv: &Csv) -> Result<Table, SqlError> {
         let mut result = Table::new();
 
-        for (idx, line) in table.lines().enumerate() {
-            let line = line.map_err(|_| SqlError::Error)?;
-            if idx == 0 {
-                result.columns = line.split(',').map(|s| s.to_string()).collect();
-                continue;
-            }
-            let register = self.execute(line, &result.columns)?;
+        let mut records = csv.records(table);
+        result.columns = records.header()?;
+
+        for record in records {
+            let fields = record?;
+            let register = self.execute(&fields, &result.columns)?;
 
             if !register.0.is_empty() {
                 result.registers.push(register);
             }
+
+            // Without sorting or removing repeated registers, the first ones found are the result.
+            if let Some(limit) = self.limit {
+                let is_streaming = self.orderby_clause.is_none() && !self.distinct;
+                if is_streaming && result.registers.len() >= self.offset + limit {
+                    break;
+                }
+            }
+        }
+
+        if let Some(orderby) = &self.orderby_clause {
+            result.registers = orderby.execute(result.registers, self.selected(&result.columns))?;
+        }
+        result.registers = self.filter_columns(&result.columns, result.registers);
+        result.registers = self.apply_limits(&result.columns, result.registers);
+
+        Ok(result)
+    }
+
+    /// Applies the `SELECT` clause to a table and the tables joined to it, and returns the resulting `Table`.
+    ///
+    /// The registers of the result have every column qualified with the alias or name of its table (`table.column`), and also unqualified if the name of the column is unique.
+    /// The columns of the result use the unqualified name when possible.
+    ///
+    /// # Arguments
+    ///
+    /// * `table` - A `BufReader<File>` that represents the table after `FROM`.
+    /// * `joined` - The `BufReader<File>` of each joined table, in the same order as the joins.
+    /// * `csv` - The codec of the table files.
+    ///
+    pub fn apply_to_tables(
+        &self,
+        table: BufReader<File>,
+        joined: Vec<BufReader<File>>,
+        csv: &Csv,
+    ) -> Result<Table, SqlError> {
+        let is_simple = self.joins.is_empty()
+            && self.table_alias.is_none()
+            && self.expressions.is_empty()
+            && !self.is_aggregated()
+            && !self.columns.iter().any(|c| c.contains('.'));
+        if is_simple {
+            return self.apply_to_table(table, csv);
+        }
+
+        let qualifier = self.table_alias.as_deref().unwrap_or(&self.table_name);
+        let (columns, mut rows) = read_table(table, csv)?;
+        let mut qualified: QualifiedColumns = columns
+            .into_iter()
+            .map(|c| (qualifier.to_string(), c))
+            .collect();
+
+        for (join, reader) in self.joins.iter().zip(joined) {
+            let (right_columns, right_rows) = read_table(reader, csv)?;
+            (qualified, rows) = join.apply(qualified, rows, &right_columns, &right_rows)?;
+        }
+
+        self.validate_columns(&qualified)?;
+
+        let is_unique = |name: &str| qualified.iter().filter(|(_, c)| c == name).count() == 1;
+        let mut result = Table::new();
+        result.columns = qualified
+            .iter()
+            .map(|(q, c)| match is_unique(c) {
+                true => c.to_string(),
+                false => format!("{}.{}", q, c),
+            })
+            .collect();
+
+        for row in rows {
+            let mut register = Register(HashMap::new());
+            for ((q, c), value) in qualified.iter().zip(row) {
+                if is_unique(c) {
+                    register.0.insert(c.to_string(), value.clone());
+                }
+                register.0.insert(format!("{}.{}", q, c), value);
+            }
+
+            let selected = match &self.where_clause {
+                Some(where_clause) => where_clause.execute(&register)?,
+                None => true,
+            };
+            if selected {
+                result.registers.push(register);
+            }
+        }
+
+        if self.is_aggregated() {
+            result.registers = self.aggregate(&qualified, result.registers)?;
+        }
+        for register in result.registers.iter_mut() {
+            for (name, expression) in &self.expressions {
+                let value = expression.evaluate(&register.0)?;
+                register.0.insert(name.to_string(), value);
+            }
         }
 
         if let Some(orderby) = &self.orderby_clause {
-            let ordered_registers = orderby.execute(&mut result.registers).to_vec();
-            result.registers = self.filter_columns(&result.columns, ordered_registers);
+            result.registers = orderby.execute(result.registers, self.selected(&result.columns))?;
+        }
+        result.registers = self.filter_columns(&result.columns, result.registers);
+        result.registers = self.apply_limits(&result.columns, result.registers);
+
+        Ok(result)
+    }
+
+    /// Removes the repeated registers if the `SELECT` is `DISTINCT`, and then applies `OFFSET` and `LIMIT`.
+    fn apply_limits(&self, columns: &[String], registers: Vec<Register>) -> Vec<Register> {
+        let selected = self.selected(columns);
+
+        let mut seen = HashSet::new();
+        registers
+            .into_iter()
+            .filter(|register| {
+                if !self.distinct {
+                    return true;
+                }
+                let values: Vec<Option<&Value>> =
+                    selected.iter().map(|c| register.0.get(c)).collect();
+                seen.insert(format!("{:?}", values))
+            })
+            .skip(self.offset)
+            .take(self.limit.unwrap_or(usize::MAX))
+            .collect()
+    }
+
+    /// Returns the names of the selected columns, which are all the `columns` of the table for `SELECT *`.
+    fn selected<'a>(&'a self, columns: &'a [String]) -> &'a [String] {
+        if self.columns[0] == "*" {
+            columns
         } else {
-            result.registers = self.filter_columns(&result.columns, result.registers);
+            &self.columns
+        }
+    }
+
+    fn is_aggregated(&self) -> bool {
+        self.groupby_clause.is_some()
+            || self.having_clause.is_some()
+            || self
+                .expressions
+                .iter()
+                .any(|(_, expression)| !expression.aggregates().is_empty())
+    }
+
+    fn expression(&self, name: &str) -> Option<&Expression> {
+        self.expressions
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, expression)| expression)
+    }
+
+    fn group_columns(&self, qualified: &QualifiedColumns) -> Result<Vec<usize>, SqlError> {
+        let mut group_columns = Vec::new();
+        if let Some(groupby) = &self.groupby_clause {
+            for col in &groupby.columns {
+                group_columns.push(resolve_column(qualified, col)?);
+            }
+        }
+        Ok(group_columns)
+    }
+
+    fn validate_columns(&self, qualified: &QualifiedColumns) -> Result<(), SqlError> {
+        if self.columns[0] == "*" {
+            if self.is_aggregated() {
+                return Err(SqlError::InvalidColumn);
+            }
+            return Ok(());
+        }
+
+        let group_columns = self.group_columns(qualified)?;
+        for col in &self.columns {
+            let (references, aggregates) = match self.expression(col) {
+                Some(expression) => (expression.columns(), expression.aggregates()),
+                None => (vec![col.as_str()], Vec::new()),
+            };
+            for aggregate in aggregates {
+                if let Some(column) = &aggregate.column {
+                    resolve_column(qualified, column)?;
+                }
+            }
+            for reference in references {
+                let idx = resolve_column(qualified, reference)?;
+                if self.is_aggregated() && !group_columns.contains(&idx) {
+                    return Err(SqlError::InvalidColumn);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Groups the registers and returns one register per group, with the `GROUP BY` columns and the aggregate functions used in the query, each named as it is written, like `SUM(cantidad)`.
+    ///
+    /// Without `GROUP BY`, every register is in the same group. The groups that don't meet the `HAVING` condition are discarded.
+    ///
+    fn aggregate(
+        &self,
+        qualified: &QualifiedColumns,
+        registers: Vec<Register>,
+    ) -> Result<Vec<Register>, SqlError> {
+        let groups = match &self.groupby_clause {
+            Some(groupby) => groupby.execute(registers)?,
+            None => vec![registers],
+        };
+        let group_columns = self.group_columns(qualified)?;
+
+        let mut names: Vec<&str> = self.columns.iter().map(|c| c.as_str()).collect();
+        let mut aggregates: Vec<&Aggregate> = Vec::new();
+        if let Some(having) = &self.having_clause {
+            names.extend(having.condition.fields());
+            aggregates.extend(having.condition.aggregates());
+        }
+        if let Some(orderby) = &self.orderby_clause {
+            names.extend(orderby.columns());
+            aggregates.extend(orderby.aggregates());
+        }
+        for (_, expression) in &self.expressions {
+            names.extend(expression.columns());
+            aggregates.extend(expression.aggregates());
+        }
+
+        let mut result = Vec::new();
+        for group in groups {
+            let mut register = Register(HashMap::new());
+            for name in &names {
+                let value = match (resolve_column(qualified, name), group.first()) {
+                    (Ok(idx), Some(first)) if group_columns.contains(&idx) => {
+                        first.0.get(*name).cloned().unwrap_or(Value::Null)
+                    }
+                    _ => continue,
+                };
+                register.0.insert(name.to_string(), value);
+            }
+            for aggregate in &aggregates {
+                register
+                    .0
+                    .insert(aggregate.to_string(), aggregate.apply(&group)?);
+            }
+
+            let selected = match &self.having_clause {
+                Some(having) => having.execute(&register)?,
+                None => true,
+            };
+            if selected {
+                result.push(register);
+            }
         }
 
         Ok(result)
     }
 
-    fn execute(&self, line: String, columns: &Vec<String>) -> Result<Register, SqlError> {
-        if !self.columns.iter().all(|col| columns.contains(col)) && self.columns[0] != "*" {
-            return Err(SqlError::InvalidColumn);
+    fn execute(
+        &self,
+        fields: &[Option<String>],
+        columns: &Vec<String>,
+    ) -> Result<Register, SqlError> {
+        if self.columns[0] != "*" {
+            if let Some(col) = self.columns.iter().find(|col| !columns.contains(col)) {
+                return Err(SqlError::ColumnNotFound(col.to_string()));
+            }
         }
 
-        let original = convert_line_to_register(line, columns);
+        let original = Register::from_fields(columns, fields);
         let mut result = Register(HashMap::new());
 
         if let Some(where_clause) = &self.where_clause {
@@ -215,7 +387,7 @@ impl Select {
                 for col in columns {
                     result.0.insert(
                         col.to_string(),
-                        original.0.get(col).unwrap_or(&String::new()).to_string(),
+                        original.0.get(col).cloned().unwrap_or(Value::Null),
                     );
                 }
             }
@@ -223,7 +395,7 @@ impl Select {
             for col in columns {
                 result.0.insert(
                     col.to_string(),
-                    original.0.get(col).unwrap_or(&String::new()).to_string(),
+                    original.0.get(col).cloned().unwrap_or(Value::Null),
                 );
             }
         }
@@ -239,160 +411,141 @@ impl Select {
     pub fn open_table(&self, folder_path: &str) -> Result<BufReader<File>, SqlError> {
         let table_name = self.table_name.to_string() + ".csv";
         if !find_file_in_folder(folder_path, &table_name) {
-            return Err(SqlError::InvalidTable);
+            return Err(SqlError::TableNotFound(self.table_name.to_string()));
         }
         let table_path = folder_path.to_string() + "/" + &table_name;
-        let file = File::open(&table_path).map_err(|_| SqlError::InvalidTable)?;
+        let file = File::open(&table_path).map_err(SqlError::from)?;
 
         let reader = BufReader::new(file);
 
         Ok(reader)
     }
+
+    /// Opens the file of every joined table and returns their `BufReader<File>`, in the same order as the joins.
+    ///
+    /// # Arguments
+    ///
+    /// * `folder_path` - A `&str` that represents the path to the folder where the table files are located.
+    ///
+    pub fn open_joined_tables(&self, folder_path: &str) -> Result<Vec<BufReader<File>>, SqlError> {
+        let mut readers = Vec::new();
+        for join in &self.joins {
+            let table_name = join.table_name.to_string() + ".csv";
+            if !find_file_in_folder(folder_path, &table_name) {
+                return Err(SqlError::TableNotFound(join.table_name.to_string()));
+            }
+            let file =
+                File::open(folder_path.to_string() + "/" + &table_name).map_err(SqlError::from)?;
+            readers.push(BufReader::new(file));
+        }
+
+        Ok(readers)
+    }
+}
+
+impl Display for Select {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "SELECT ")?;
+        if self.distinct {
+            write!(f, "DISTINCT ")?;
+        }
+        let columns: Vec<String> = self
+            .columns
+            .iter()
+            .map(|column| match self.expression(column) {
+                Some(expression) if expression.to_string() == *column => expression.to_string(),
+                Some(expression) => format!("{} AS {}", expression, format_identifier(column)),
+                None if column == "*" => column.to_string(),
+                None => format_identifier(column),
+            })
+            .collect();
+        write!(
+            f,
+            "{} FROM {}",
+            columns.join(", "),
+            format_identifier(&self.table_name)
+        )?;
+        if let Some(alias) = &self.table_alias {
+            write!(f, " {}", format_identifier(alias))?;
+        }
+        for join in &self.joins {
+            write!(f, " {}", join)?;
+        }
+        if let Some(where_clause) = &self.where_clause {
+            write!(f, " WHERE {}", where_clause.condition)?;
+        }
+        if let Some(groupby) = &self.groupby_clause {
+            write!(f, " {}", groupby)?;
+        }
+        if let Some(having) = &self.having_clause {
+            write!(f, " HAVING {}", having.condition)?;
+        }
+        if let Some(orderby) = &self.orderby_clause {
+            write!(f, " {}", orderby)?;
+        }
+        if let Some(limit) = self.limit {
+            write!(f, " LIMIT {}", limit)?;
+        }
+        if self.offset > 0 {
+            write!(f, " OFFSET {}", self.offset)?;
+        }
+        Ok(())
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use std::collections::HashMap;
+    use std::{collections::HashMap, fs};
 
     use super::Select;
     use crate::{
-        clauses::{condition::Condition, orderby_sql::OrderBy, where_sql::Where},
+        clauses::{
+            condition::Condition,
+            orderby_sql::{OrderBy, OrderKey},
+            where_sql::Where,
+        },
+        csv::Csv,
         errors::SqlError,
         logical_operator::LogicalOperator,
         operator::Operator,
         register::Register,
         table::Table,
+        utils::temp_folder,
+        value::Value,
     };
 
     #[test]
-    fn new_1_tokens() {
-        let tokens = vec![String::from("SELECT")];
-        let select = Select::new_from_tokens(tokens);
-        assert_eq!(select, Err(SqlError::InvalidSyntax));
-    }
-
-    #[test]
-    fn new_2_tokens() {
-        let tokens = vec![String::from("SELECT"), String::from("col")];
-        let select = Select::new_from_tokens(tokens);
-        assert_eq!(select, Err(SqlError::InvalidSyntax));
-    }
-    #[test]
-    fn new_3_tokens() {
-        let tokens = vec![
-            String::from("SELECT"),
-            String::from("col"),
-            String::from("FROM"),
-        ];
-        let select = Select::new_from_tokens(tokens);
-        assert_eq!(select, Err(SqlError::InvalidSyntax));
-    }
-
-    #[test]
-    fn new_4_tokens() {
-        let tokens = vec![
-            String::from("SELECT"),
-            String::from("col"),
-            String::from("FROM"),
-            String::from("table"),
-        ];
-        let select = Select::new_from_tokens(tokens).unwrap();
-        assert_eq!(select.columns, ["col"]);
-        assert_eq!(select.table_name, "table");
-        assert_eq!(select.where_clause, None);
-        assert_eq!(select.orderby_clause, None);
-    }
-
-    #[test]
-    fn new_with_where() {
-        let tokens = vec![
-            String::from("SELECT"),
-            String::from("col"),
-            String::from("FROM"),
-            String::from("table"),
-            String::from("WHERE"),
-            String::from("cantidad"),
-            String::from(">"),
-            String::from("1"),
-        ];
-        let select = Select::new_from_tokens(tokens).unwrap();
-        assert_eq!(select.columns, ["col"]);
-        assert_eq!(select.table_name, "table");
-        let where_clause = select.where_clause.unwrap();
+    fn limit_stops_reading() {
+        let folder = temp_folder("limit", &[]);
+        let mut content = b"id\n1\n2\n".to_vec();
+        content.extend([0xff, 0xfe, b'\n']);
+        fs::write(folder.clone() + "/numeros.csv", content).unwrap();
+
+        let mut select = Select {
+            table_name: String::from("numeros"),
+            columns: vec![String::from("*")],
+            limit: Some(1),
+            offset: 1,
+            ..Default::default()
+        };
+        let table = select
+            .apply_to_table(select.open_table(&folder).unwrap(), &Csv::default())
+            .unwrap();
         assert_eq!(
-            where_clause.condition,
-            Condition::Simple {
-                field: String::from("cantidad"),
-                operator: Operator::Greater,
-                value: String::from("1"),
-            }
+            table.registers,
+            vec![Register(HashMap::from([(
+                String::from("id"),
+                Value::Integer(2)
+            )]))]
         );
-        assert_eq!(select.orderby_clause, None);
-    }
 
-    #[test]
-    fn new_with_orderby() {
-        let tokens = vec![
-            String::from("SELECT"),
-            String::from("col"),
-            String::from("FROM"),
-            String::from("table"),
-            String::from("ORDER"),
-            String::from("BY"),
-            String::from("cantidad"),
-            String::from("DESC"),
-        ];
-        let select = Select::new_from_tokens(tokens).unwrap();
-        assert_eq!(select.columns, ["col"]);
-        assert_eq!(select.table_name, "table");
-        let orderby_clause = select.orderby_clause.unwrap();
-        assert_eq!(
-            orderby_clause,
-            OrderBy {
-                columns: vec![String::from("cantidad")],
-                order: String::from("DESC")
-            }
-        );
-        assert_eq!(select.where_clause, None);
-    }
+        select.limit = None;
+        assert!(matches!(
+            select.apply_to_table(select.open_table(&folder).unwrap(), &Csv::default()),
+            Err(SqlError::Io(_))
+        ));
 
-    #[test]
-    fn new_with_where_orderby() {
-        let tokens = vec![
-            String::from("SELECT"),
-            String::from("col"),
-            String::from("FROM"),
-            String::from("table"),
-            String::from("WHERE"),
-            String::from("cantidad"),
-            String::from(">"),
-            String::from("1"),
-            String::from("ORDER"),
-            String::from("BY"),
-            String::from("email"),
-        ];
-        let select = Select::new_from_tokens(tokens).unwrap();
-        assert_eq!(select.columns, ["col"]);
-        assert_eq!(select.table_name, "table");
-        let where_clause = select.where_clause.unwrap();
-        assert_eq!(
-            where_clause.condition,
-            Condition::Simple {
-                field: String::from("cantidad"),
-                operator: Operator::Greater,
-                value: String::from("1"),
-            }
-        );
-        let orderby_clause = select.orderby_clause.unwrap();
-        let mut columns = Vec::new();
-        columns.push(String::from("email"));
-        assert_eq!(
-            orderby_clause,
-            OrderBy {
-                columns,
-                order: String::new()
-            }
-        );
+        fs::remove_dir_all(&folder).unwrap();
     }
 
     #[test]
@@ -402,11 +555,12 @@ mod tests {
             columns: vec![String::from("*")],
             where_clause: None,
             orderby_clause: None,
+            ..Default::default()
         };
         let folder_path = String::from("tablas");
         let reader = select.open_table(&folder_path).unwrap();
 
-        let table = select.apply_to_table(reader).unwrap();
+        let table = select.apply_to_table(reader, &Csv::default()).unwrap();
         let expected = Table {
             columns: vec![
                 String::from("nombre"),
@@ -415,19 +569,19 @@ mod tests {
             ],
             registers: vec![
                 Register(HashMap::from([
-                    (String::from("nombre"), String::from("Juan")),
-                    (String::from("apellido"), String::from("Pérez")),
-                    (String::from("edad"), String::from("30")),
+                    (String::from("nombre"), Value::Text(String::from("Juan"))),
+                    (String::from("apellido"), Value::Text(String::from("Pérez"))),
+                    (String::from("edad"), Value::Integer(30)),
                 ])),
                 Register(HashMap::from([
-                    (String::from("nombre"), String::from("Ana")),
-                    (String::from("apellido"), String::from("López")),
-                    (String::from("edad"), String::from("18")),
+                    (String::from("nombre"), Value::Text(String::from("Ana"))),
+                    (String::from("apellido"), Value::Text(String::from("López"))),
+                    (String::from("edad"), Value::Integer(18)),
                 ])),
                 Register(HashMap::from([
-                    (String::from("nombre"), String::from("Carlos")),
-                    (String::from("apellido"), String::from("Gómez")),
-                    (String::from("edad"), String::from("40")),
+                    (String::from("nombre"), Value::Text(String::from("Carlos"))),
+                    (String::from("apellido"), Value::Text(String::from("Gómez"))),
+                    (String::from("edad"), Value::Integer(40)),
                 ])),
             ],
         };
@@ -443,14 +597,14 @@ mod tests {
             columns: vec![String::from("*")],
             where_clause: None,
             orderby_clause: Some(OrderBy {
-                columns: vec![String::from("edad")],
-                order: String::new(),
+                keys: vec![OrderKey::new_from_column("edad")],
             }),
+            ..Default::default()
         };
         let folder_path = String::from("tablas");
         let reader = select.open_table(&folder_path).unwrap();
 
-        let table = select.apply_to_table(reader).unwrap();
+        let table = select.apply_to_table(reader, &Csv::default()).unwrap();
         let expected = Table {
             columns: vec![
                 String::from("nombre"),
@@ -459,19 +613,19 @@ mod tests {
             ],
             registers: vec![
                 Register(HashMap::from([
-                    (String::from("nombre"), String::from("Ana")),
-                    (String::from("apellido"), String::from("López")),
-                    (String::from("edad"), String::from("18")),
+                    (String::from("nombre"), Value::Text(String::from("Ana"))),
+                    (String::from("apellido"), Value::Text(String::from("López"))),
+                    (String::from("edad"), Value::Integer(18)),
                 ])),
                 Register(HashMap::from([
-                    (String::from("nombre"), String::from("Juan")),
-                    (String::from("apellido"), String::from("Pérez")),
-                    (String::from("edad"), String::from("30")),
+                    (String::from("nombre"), Value::Text(String::from("Juan"))),
+                    (String::from("apellido"), Value::Text(String::from("Pérez"))),
+                    (String::from("edad"), Value::Integer(30)),
                 ])),
                 Register(HashMap::from([
-                    (String::from("nombre"), String::from("Carlos")),
-                    (String::from("apellido"), String::from("Gómez")),
-                    (String::from("edad"), String::from("40")),
+                    (String::from("nombre"), Value::Text(String::from("Carlos"))),
+                    (String::from("apellido"), Value::Text(String::from("Gómez"))),
+                    (String::from("edad"), Value::Integer(40)),
                 ])),
             ],
         };
@@ -489,15 +643,16 @@ mod tests {
                 condition: Condition::Simple {
                     field: String::from("edad"),
                     operator: Operator::Greater,
-                    value: String::from("18"),
+                    value: Value::Integer(18),
                 },
             }),
             orderby_clause: None,
+            ..Default::default()
         };
         let folder_path = String::from("tablas");
         let reader = select.open_table(&folder_path).unwrap();
 
-        let table = select.apply_to_table(reader).unwrap();
+        let table = select.apply_to_table(reader, &Csv::default()).unwrap();
         let expected = Table {
             columns: vec![
                 String::from("nombre"),
@@ -506,14 +661,14 @@ mod tests {
             ],
             registers: vec![
                 Register(HashMap::from([
-                    (String::from("nombre"), String::from("Juan")),
-                    (String::from("apellido"), String::from("Pérez")),
-                    (String::from("edad"), String::from("30")),
+                    (String::from("nombre"), Value::Text(String::from("Juan"))),
+                    (String::from("apellido"), Value::Text(String::from("Pérez"))),
+                    (String::from("edad"), Value::Integer(30)),
                 ])),
                 Register(HashMap::from([
-                    (String::from("nombre"), String::from("Carlos")),
-                    (String::from("apellido"), String::from("Gómez")),
-                    (String::from("edad"), String::from("40")),
+                    (String::from("nombre"), Value::Text(String::from("Carlos"))),
+                    (String::from("apellido"), Value::Text(String::from("Gómez"))),
+                    (String::from("edad"), Value::Integer(40)),
                 ])),
             ],
         };
@@ -531,18 +686,18 @@ mod tests {
                 condition: Condition::Simple {
                     field: String::from("edad"),
                     operator: Operator::Greater,
-                    value: String::from("18"),
+                    value: Value::Integer(18),
                 },
             }),
             orderby_clause: Some(OrderBy {
-                columns: vec![String::from("edad")],
-                order: String::from("DESC"),
+                keys: vec![OrderKey::new_from_column("edad").desc()],
             }),
+            ..Default::default()
         };
         let folder_path = String::from("tablas");
         let reader = select.open_table(&folder_path).unwrap();
 
-        let table = select.apply_to_table(reader).unwrap();
+        let table = select.apply_to_table(reader, &Csv::default()).unwrap();
         let expected = Table {
             columns: vec![
                 String::from("nombre"),
@@ -551,14 +706,14 @@ mod tests {
             ],
             registers: vec![
                 Register(HashMap::from([
-                    (String::from("nombre"), String::from("Carlos")),
-                    (String::from("apellido"), String::from("Gómez")),
-                    (String::from("edad"), String::from("40")),
+                    (String::from("nombre"), Value::Text(String::from("Carlos"))),
+                    (String::from("apellido"), Value::Text(String::from("Gómez"))),
+                    (String::from("edad"), Value::Integer(40)),
                 ])),
                 Register(HashMap::from([
-                    (String::from("nombre"), String::from("Juan")),
-                    (String::from("apellido"), String::from("Pérez")),
-                    (String::from("edad"), String::from("30")),
+                    (String::from("nombre"), Value::Text(String::from("Juan"))),
+                    (String::from("apellido"), Value::Text(String::from("Pérez"))),
+                    (String::from("edad"), Value::Integer(30)),
                 ])),
             ],
         };
@@ -577,25 +732,25 @@ mod tests {
                     left: Some(Box::new(Condition::Simple {
                         field: String::from("edad"),
                         operator: Operator::Greater,
-                        value: String::from("18"),
+                        value: Value::Integer(18),
                     })),
                     operator: LogicalOperator::And,
                     right: Box::new(Condition::Simple {
                         field: String::from("nombre"),
                         operator: Operator::Equal,
-                        value: String::from("Carlos"),
+                        value: Value::Text(String::from("Carlos")),
                     }),
                 },
             }),
             orderby_clause: Some(OrderBy {
-                columns: vec![String::from("edad")],
-                order: String::from("DESC"),
+                keys: vec![OrderKey::new_from_column("edad").desc()],
             }),
+            ..Default::default()
         };
         let folder_path = String::from("tablas");
         let reader = select.open_table(&folder_path).unwrap();
 
-        let table = select.apply_to_table(reader).unwrap();
+        let table = select.apply_to_table(reader, &Csv::default()).unwrap();
         let expected = Table {
             columns: vec![
                 String::from("nombre"),
@@ -603,8 +758,8 @@ mod tests {
                 String::from("edad"),
             ],
             registers: vec![Register(HashMap::from([
-                (String::from("nombre"), String::from("Carlos")),
-                (String::from("apellido"), String::from("Gómez")),
+                (String::from("nombre"), Value::Text(String::from("Carlos"))),
+                (String::from("apellido"), Value::Text(String::from("Gómez"))),
             ]))],
         };
 
@@ -1,4 +1,6 @@
-use crate::{errors::SqlError, utils::is_set};
+use super::expression::Expression;
+use crate::lexer::format_identifier;
+use std::fmt::Display;
 
 /// Struct representing the `SET` SQL clause.
 ///
@@ -6,46 +8,18 @@ use crate::{errors::SqlError, utils::is_set};
 ///
 /// # Fields
 ///
-/// * A vector of tuples containing the column name and the new value.
+/// * A vector of tuples containing the column name and the expression of its new value.
 ///
 #[derive(PartialEq, Debug)]
-pub struct Set(pub Vec<(String, String)>);
+pub struct Set(pub Vec<(String, Expression)>);
 
-impl Set {
-    /// Creates and returns a new `Set` instance from a vector of tokens.
-    ///
-    /// # Arguments
-    ///
-    /// * `tokens` - A vector of tokens that can be used to build a `Set` instance.
-    ///
-    /// The tokens should be in the following order: `SET`, `column`, `=`, `value`.
-    ///
-    /// # Examples
-    ///
-    /// ```
-    /// let tokens = vec!["SET", "age", "=", "18"];
-    /// let set_from_tokens = Set::new_from_tokens(tokens).unwrap();
-    /// let set_clause = Set(vec![("age".to_string(), "18".to_string())]);
-    ///
-    /// assert_eq!(set_from_tokens, set_clause);
-    /// ```
-    ///
-    pub fn new_from_tokens(tokens: Vec<&str>) -> Result<Self, SqlError> {
-        let mut set = Vec::new();
-        let mut i = 0;
-
-        if !is_set(tokens[i]) || !tokens.contains(&"=") {
-            return Err(SqlError::InvalidSyntax);
-        }
-        i += 1;
-
-        while i < tokens.len() {
-            if tokens[i] == "=" && i + 1 < tokens.len() {
-                set.push((tokens[i - 1].to_string(), tokens[i + 1].to_string()));
-            }
-            i += 1;
-        }
-
-        Ok(Self(set))
+impl Display for Set {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let assignments: Vec<String> = self
+            .0
+            .iter()
+            .map(|(column, expression)| format!("{} = {}", format_identifier(column), expression))
+            .collect();
+        write!(f, "SET {}", assignments.join(", "))
     }
 }
@@ -0,0 +1,81 @@
+use crate::{
+    csv::Csv, errors::SqlError, lexer::format_identifier, utils::find_file_in_folder,
+    wal::Operation,
+};
+use std::{fmt::Display, fs::File, io::BufReader};
+
+/// Struct that represents the `TRUNCATE TABLE` SQL clause.
+/// The `TRUNCATE TABLE` clause is used to delete every record of a table, keeping its columns.
+///
+/// # Fields
+///
+/// * `table_name` - The name of the table to truncate.
+///
+#[derive(Debug, PartialEq)]
+pub struct Truncate {
+    pub table_name: String,
+}
+
+impl Truncate {
+    /// Deletes every record of the table, rewriting its file with only the header.
+    ///
+    /// Returns the number of deleted records.
+    ///
+    /// # Arguments
+    ///
+    /// * `folder_path` - A string slice that contains the path to the folder where the table is located.
+    /// * `csv` - The codec of the table file.
+    ///
+    pub fn apply_to_folder(&self, folder_path: &str, csv: &Csv) -> Result<usize, SqlError> {
+        let file_name = self.table_name.to_string() + ".csv";
+        if !find_file_in_folder(folder_path, &file_name) {
+            return Err(SqlError::TableNotFound(self.table_name.to_string()));
+        }
+        let path = folder_path.to_string() + "/" + &file_name;
+
+        let file = File::open(&path).map_err(SqlError::from)?;
+        let mut records = csv.records(BufReader::new(file));
+        let header = records.header()?;
+        let mut deleted = 0;
+        for record in records {
+            record?;
+            deleted += 1;
+        }
+
+        let mut operation = Operation::start(folder_path)?;
+        operation.replace(&self.table_name, &[csv.format_record(&header)])?;
+        operation.commit()?;
+
+        Ok(deleted)
+    }
+}
+
+impl Display for Truncate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "TRUNCATE TABLE {}", format_identifier(&self.table_name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Truncate;
+    use crate::{csv::Csv, utils::temp_folder};
+    use std::fs;
+
+    #[test]
+    fn truncate_keeps_header() {
+        let folder = temp_folder("truncate", &["testing"]);
+
+        let truncate = Truncate {
+            table_name: String::from("testing"),
+        };
+
+        assert_eq!(truncate.apply_to_folder(&folder, &Csv::default()), Ok(3));
+        assert_eq!(
+            fs::read_to_string(folder.clone() + "/testing.csv").unwrap(),
+            "nombre,apellido,edad\n"
+        );
+
+        fs::remove_dir_all(&folder).unwrap();
+    }
+}
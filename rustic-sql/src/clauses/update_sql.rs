@@ -1,13 +1,14 @@
+use super::expression::Expression;
 use super::set_sql::Set;
 use super::where_sql::Where;
-use crate::utils::{is_set, is_update, is_where};
-use crate::{errors::SqlError, register::Register, table::Table, utils::find_file_in_folder};
-use std::io::Write;
-use std::{
-    collections::HashMap,
-    fs::{self, File},
-    io::{BufRead, BufReader},
+use crate::lexer::format_identifier;
+use crate::schema::Schema;
+use crate::{
+    csv::Csv, errors::SqlError, register::Register, table::Table, utils::find_file_in_folder,
+    wal::Operation,
 };
+use std::fmt::Display;
+use std::{fs::File, io::BufReader};
 
 /// Struct representing the `UPDATE` SQL clause.
 /// The `UPDATE` clause is used to modify records in a table.
@@ -26,136 +27,116 @@ pub struct Update {
 }
 
 impl Update {
-    /// Creates and returns a new `Update` instance from a vector of tokens.
-    ///
-    /// # Arguments
-    ///
-    /// * `tokens` - A vector of tokens that can be used to build a `Update` instance.
+    /// Applies the `UPDATE` clause to a given table.
     ///
-    /// The tokens should be in the following order: `UPDATE`, `table`, `SET`, `column`, `=`, `value`.
+    /// Reads the table and applies the set clause to the registers that meet the where clause if it exist or to all the registers if it doesn't.
+    /// Returns a new table with the updated registers.
     ///
-    /// # Examples
+    /// # Arguments
     ///
-    /// ```
-    /// let tokens = vec!["UPDATE", "table", "SET", "nombre", "=", "Alen"];
-    /// let update_from_tokens = Update::new_from_tokens(tokens).unwrap();
-    /// let update = Update {
-    ///     table_name: "table".to_string(),
-    ///     set_clause: Set(vec![("nombre".to_string(), "Alen".to_string())]),
-    ///     where_clause: None,
-    /// };
+    /// * `table` - A `BufReader<File>` that contains a reader for the table to be updated.
+    /// * `csv` - The codec of the table file.
     ///
-    /// assert_eq!(update_from_tokens, update);
-    /// ```
-    pub fn new_from_tokens(tokens: Vec<String>) -> Result<Self, SqlError> {
-        if tokens.len() < 6 {
-            return Err(SqlError::InvalidSyntax);
-        }
-        let mut where_tokens = Vec::new();
-        let mut set_tokens = Vec::new();
-        let mut table_name = String::new();
-
-        let mut i = 0;
-
-        while i < tokens.len() {
-            if i == 0 && !is_update(&tokens[i]) || i == 2 && !is_set(&tokens[i]) {
-                return Err(SqlError::InvalidSyntax);
-            }
-
-            if i == 0 && is_update(&tokens[i]) && i + 1 < tokens.len() {
-                table_name = tokens[i + 1].to_string();
-            }
-
-            if i == 2 && is_set(&tokens[i]) {
-                while i < tokens.len() && !is_where(&tokens[i]) {
-                    set_tokens.push(tokens[i].as_str());
-                    i += 1;
-                }
-                if i < tokens.len() && is_where(&tokens[i]) {
-                    while i < tokens.len() {
-                        where_tokens.push(tokens[i].as_str());
-                        i += 1;
-                    }
-                }
-            }
-            i += 1;
-        }
-
-        if table_name.is_empty() || set_tokens.is_empty() {
-            return Err(SqlError::InvalidSyntax);
-        }
-
-        let mut where_clause = None;
-
-        if !where_tokens.is_empty() {
-            where_clause = Some(Where::new_from_tokens(where_tokens)?);
-        }
-
-        let set_clause = Set::new_from_tokens(set_tokens)?;
-
-        Ok(Self {
-            table_name,
-            where_clause,
-            set_clause,
-        })
+    pub fn apply_to_table(&self, table: BufReader<File>, csv: &Csv) -> Result<Table, SqlError> {
+        let (result, _) = self.apply_to_table_counting(table, None, csv)?;
+        Ok(result)
     }
 
-    /// Applies the `UPDATE` clause to a given table.
+    /// Applies the `UPDATE` clause to a given table, like `apply_to_table`, and also returns the number of updated registers.
     ///
-    /// Reads the table and applies the set clause to the registers that meet the where clause if it exist or to all the registers if it doesn't.
-    /// Returns a new table with the updated registers.
+    /// Returns `SqlError::ColumnNotFound` if the set clause has a column that is not in the table.
     ///
     /// # Arguments
     ///
     /// * `table` - A `BufReader<File>` that contains a reader for the table to be updated.
+    /// * `schema` - The schema of the table, if it has one, used to validate the new values.
+    /// * `csv` - The codec of the table file.
     ///
-    pub fn apply_to_table(&self, table: BufReader<File>) -> Result<Table, SqlError> {
+    pub fn apply_to_table_counting(
+        &self,
+        table: BufReader<File>,
+        schema: Option<&Schema>,
+        csv: &Csv,
+    ) -> Result<(Table, usize), SqlError> {
         let mut result = Table::new();
+        let mut updated = 0;
+
+        let mut records = csv.records(table);
+        result.columns = records.header()?;
+        if let Some((unknown, _)) = self
+            .set_clause
+            .0
+            .iter()
+            .find(|(col, _)| !result.columns.contains(col))
+        {
+            return Err(SqlError::ColumnNotFound(unknown.to_string()));
+        }
 
-        for (idx, line) in table.lines().enumerate() {
-            let line = line.map_err(|_| SqlError::Error)?;
-            if idx == 0 {
-                result.columns = line.split(',').map(|s| s.to_string()).collect();
-                continue;
-            }
-            let register = self.execute(line, &result.columns)?;
+        for record in records {
+            let fields = record?;
+            let (register, matched) = self.execute(&fields, &result.columns, schema)?;
 
+            if matched {
+                updated += 1;
+            }
             if !register.0.is_empty() {
                 result.registers.push(register);
             }
         }
-        Ok(result)
+        Ok((result, updated))
     }
 
-    fn execute(&self, line: String, columns: &[String]) -> Result<Register, SqlError> {
-        let atributes: Vec<String> = line.split(',').map(|s| s.to_string()).collect();
-
-        let mut register = Register(HashMap::new());
+    fn execute(
+        &self,
+        fields: &[Option<String>],
+        columns: &[String],
+        schema: Option<&Schema>,
+    ) -> Result<(Register, bool), SqlError> {
+        let mut register = Register::from_fields(columns, fields);
+
+        let matched = match &self.where_clause {
+            Some(where_clause) => where_clause.execute(&register)?,
+            None => true,
+        };
 
-        for (idx, col) in columns.iter().enumerate() {
-            register
-                .0
-                .insert(col.to_string(), atributes[idx].to_string());
+        if matched {
+            // Every expression is evaluated with the values the register had before the update.
+            let mut values = Vec::new();
+            for (col, expression) in &self.set_clause.0 {
+                let mut value = expression.evaluate(&register.0)?;
+                if let Some(schema) = schema {
+                    value = schema.column(col)?.validate(value)?;
+                }
+                values.push((col.to_string(), value));
+            }
+            register.0.extend(values);
         }
 
-        if let Some(where_clause) = &self.where_clause {
-            let op_result = where_clause.execute(&register)?;
+        Ok((register, matched))
+    }
 
-            if op_result {
-                for (col, val) in &self.set_clause.0 {
-                    register.0.insert(col.to_string(), val.to_string());
-                }
-            }
-        } else {
-            for (col, val) in &self.set_clause.0 {
-                register.0.insert(col.to_string(), val.to_string());
+    /// Validates the values of the set clause against the schema of the table, converting the literal values to the declared type of their columns.
+    ///
+    /// The values computed from other columns are validated when the update is applied.
+    ///
+    /// Returns `SqlError::ColumnNotFound` if a column is not in the schema, `SqlError::InvalidType` if a value doesn't match the type of its column and `SqlError::InvalidValue` if a `NOT NULL` column gets `Null`.
+    ///
+    /// # Arguments
+    ///
+    /// * `schema` - The schema of the table to be updated.
+    ///
+    pub fn validate(&mut self, schema: &Schema) -> Result<(), SqlError> {
+        for (col, expression) in self.set_clause.0.iter_mut() {
+            let column = schema.column(col)?;
+            if let Expression::Literal(value) = expression {
+                *value = column.validate(value.clone())?;
             }
         }
-
-        Ok(register)
+        Ok(())
     }
 
     /// Writes the updated table in csv format to the file that contains the table in the given folder path.
+    /// The new data is recorded in the write-ahead log before it replaces the file, so a crash can't leave it half written.
     ///
     /// # Arguments
     ///
@@ -163,15 +144,9 @@ impl Update {
     /// * `folder_path` - A string slice that contains the path to the folder where the table is located.
     ///
     pub fn write_table(&self, csv: Vec<String>, folder_path: &str) -> Result<(), SqlError> {
-        let temp_file_path = folder_path.to_string() + "/" + "temp.csv";
-        let mut temp_file = File::create(&temp_file_path).map_err(|_| SqlError::Error)?;
-        for line in csv {
-            writeln!(temp_file, "{}", line).map_err(|_| SqlError::Error)?;
-        }
-        let path = folder_path.to_string() + "/" + &self.table_name + ".csv";
-        fs::rename(&temp_file_path, path).map_err(|_| SqlError::Error)?;
-
-        Ok(())
+        let mut operation = Operation::start(folder_path)?;
+        operation.replace(&self.table_name, &csv)?;
+        operation.commit()
     }
 
     /// Opens the table file in the given folder path.
@@ -184,10 +159,10 @@ impl Update {
     pub fn open_table(&self, folder_path: &str) -> Result<BufReader<File>, SqlError> {
         let table_name = self.table_name.to_string() + ".csv";
         if !find_file_in_folder(folder_path, &table_name) {
-            return Err(SqlError::InvalidTable);
+            return Err(SqlError::TableNotFound(self.table_name.to_string()));
         }
         let table_path = folder_path.to_string() + "/" + &table_name;
-        let file = File::open(&table_path).map_err(|_| SqlError::InvalidTable)?;
+        let file = File::open(&table_path).map_err(SqlError::from)?;
 
         let reader = BufReader::new(file);
 
@@ -195,100 +170,52 @@ impl Update {
     }
 }
 
+impl Display for Update {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "UPDATE {} {}",
+            format_identifier(&self.table_name),
+            self.set_clause
+        )?;
+        if let Some(where_clause) = &self.where_clause {
+            write!(f, " WHERE {}", where_clause.condition)?;
+        }
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::HashMap;
 
     use crate::{
-        clauses::{condition::Condition, set_sql::Set, update_sql::Update, where_sql::Where},
-        errors::SqlError,
+        clauses::{
+            condition::Condition, expression::Expression, set_sql::Set, update_sql::Update,
+            where_sql::Where,
+        },
+        csv::Csv,
         operator::Operator,
         register::Register,
         table::Table,
+        value::Value,
     };
 
-    #[test]
-    fn new_1_token() {
-        let tokens = vec![String::from("UPDATE")];
-        let update = Update::new_from_tokens(tokens);
-        assert_eq!(update, Err(SqlError::InvalidSyntax));
-    }
-
-    #[test]
-    fn new_3_tokens() {
-        let tokens = vec![
-            String::from("UPDATE"),
-            String::from("table"),
-            String::from("SET"),
-        ];
-        let update = Update::new_from_tokens(tokens);
-        assert_eq!(update, Err(SqlError::InvalidSyntax));
-    }
-
-    #[test]
-    fn new_without_where() {
-        let tokens = vec![
-            String::from("UPDATE"),
-            String::from("table"),
-            String::from("SET"),
-            String::from("nombre"),
-            String::from("="),
-            String::from("Alen"),
-        ];
-        let update = Update::new_from_tokens(tokens).unwrap();
-        assert_eq!(
-            update,
-            Update {
-                table_name: String::from("table"),
-                set_clause: Set(vec![(String::from("nombre"), String::from("Alen"))]),
-                where_clause: None
-            }
-        );
-    }
-
-    #[test]
-    fn new_with_where() {
-        let tokens = vec![
-            String::from("UPDATE"),
-            String::from("table"),
-            String::from("SET"),
-            String::from("nombre"),
-            String::from("="),
-            String::from("Alen"),
-            String::from("WHERE"),
-            String::from("edad"),
-            String::from("<"),
-            String::from("30"),
-        ];
-        let update = Update::new_from_tokens(tokens).unwrap();
-        assert_eq!(
-            update,
-            Update {
-                table_name: String::from("table"),
-                set_clause: Set(vec![(String::from("nombre"), String::from("Alen"))]),
-                where_clause: Some(Where {
-                    condition: Condition::Simple {
-                        field: String::from("edad"),
-                        operator: Operator::Lesser,
-                        value: String::from("30"),
-                    },
-                }),
-            }
-        );
-    }
-
     #[test]
     fn update_without_where() {
         let update = Update {
             table_name: String::from("testing"),
-            set_clause: Set(vec![(String::from("nombre"), String::from("Alen"))]),
+            set_clause: Set(vec![(
+                String::from("nombre"),
+                Expression::Literal(Value::Text(String::from("Alen"))),
+            )]),
             where_clause: None,
         };
 
         let folder_path = String::from("tablas");
         let reader = update.open_table(&folder_path).unwrap();
 
-        let table = update.apply_to_table(reader).unwrap();
+        let table = update.apply_to_table(reader, &Csv::default()).unwrap();
 
         let expected = Table {
             columns: vec![
@@ -298,19 +225,19 @@ mod tests {
             ],
             registers: vec![
                 Register(HashMap::from([
-                    (String::from("nombre"), String::from("Alen")),
-                    (String::from("apellido"), String::from("Pérez")),
-                    (String::from("edad"), String::from("30")),
+                    (String::from("nombre"), Value::Text(String::from("Alen"))),
+                    (String::from("apellido"), Value::Text(String::from("Pérez"))),
+                    (String::from("edad"), Value::Integer(30)),
                 ])),
                 Register(HashMap::from([
-                    (String::from("nombre"), String::from("Alen")),
-                    (String::from("apellido"), String::from("López")),
-                    (String::from("edad"), String::from("18")),
+                    (String::from("nombre"), Value::Text(String::from("Alen"))),
+                    (String::from("apellido"), Value::Text(String::from("López"))),
+                    (String::from("edad"), Value::Integer(18)),
                 ])),
                 Register(HashMap::from([
-                    (String::from("nombre"), String::from("Alen")),
-                    (String::from("apellido"), String::from("Gómez")),
-                    (String::from("edad"), String::from("40")),
+                    (String::from("nombre"), Value::Text(String::from("Alen"))),
+                    (String::from("apellido"), Value::Text(String::from("Gómez"))),
+                    (String::from("edad"), Value::Integer(40)),
                 ])),
             ],
         };
@@ -323,19 +250,22 @@ mod tests {
     fn delete_with_where() {
         let update = Update {
             table_name: String::from("testing"),
-            set_clause: Set(vec![(String::from("nombre"), String::from("Alen"))]),
+            set_clause: Set(vec![(
+                String::from("nombre"),
+                Expression::Literal(Value::Text(String::from("Alen"))),
+            )]),
             where_clause: Some(Where {
                 condition: Condition::Simple {
                     field: String::from("edad"),
                     operator: Operator::Greater,
-                    value: String::from("20"),
+                    value: Value::Integer(20),
                 },
             }),
         };
         let folder_path = String::from("tablas");
         let reader = update.open_table(&folder_path).unwrap();
 
-        let table = update.apply_to_table(reader).unwrap();
+        let table = update.apply_to_table(reader, &Csv::default()).unwrap();
         let expected = Table {
             columns: vec![
                 String::from("nombre"),
@@ -344,19 +274,19 @@ mod tests {
             ],
             registers: vec![
                 Register(HashMap::from([
-                    (String::from("nombre"), String::from("Alen")),
-                    (String::from("apellido"), String::from("Pérez")),
-                    (String::from("edad"), String::from("30")),
+                    (String::from("nombre"), Value::Text(String::from("Alen"))),
+                    (String::from("apellido"), Value::Text(String::from("Pérez"))),
+                    (String::from("edad"), Value::Integer(30)),
                 ])),
                 Register(HashMap::from([
-                    (String::from("nombre"), String::from("Ana")),
-                    (String::from("apellido"), String::from("López")),
-                    (String::from("edad"), String::from("18")),
+                    (String::from("nombre"), Value::Text(String::from("Ana"))),
+                    (String::from("apellido"), Value::Text(String::from("López"))),
+                    (String::from("edad"), Value::Integer(18)),
                 ])),
                 Register(HashMap::from([
-                    (String::from("nombre"), String::from("Alen")),
-                    (String::from("apellido"), String::from("Gómez")),
-                    (String::from("edad"), String::from("40")),
+                    (String::from("nombre"), Value::Text(String::from("Alen"))),
+                    (String::from("apellido"), Value::Text(String::from("Gómez"))),
+                    (String::from("edad"), Value::Integer(40)),
                 ])),
             ],
         };
@@ -1,5 +1,9 @@
 use super::{condition::Condition, recursive_parser::parse_condition};
-use crate::{errors::SqlError, register::Register};
+use crate::{
+    errors::SqlError,
+    lexer::{unexpected, Token},
+    register::Register,
+};
 
 /// Struct representing the `WHERE` SQL clause.
 ///
@@ -15,36 +19,50 @@ pub struct Where {
 }
 
 impl Where {
-    /// Creates and returns a new `Where` instance from a vector of tokens.
+    /// Creates and returns a new `Where` instance from the tokens read by the lexer, starting at the position `pos`.
     ///
     /// # Arguments
     ///
-    /// * `tokens` - A vector of tokens that can be used to build a `Where` instance.
+    /// * `tokens` - The tokens read by `lexer::tokenize`.
+    /// * `pos` - A mutable reference to the position of the `WHERE` token, which is left after the condition.
     ///
     /// The tokens should be in the following order: `WHERE`, `column`, `operator`, `value` in the case of a simple condition, and `WHERE`, `condition`, `AND` or `OR`, `condition` for a complex condition.
+    /// The condition of a `HAVING` clause is read the same way, starting at the `HAVING` token.
+    ///
+    /// Returns `SqlError::UnexpectedToken`, with its offset, for the first token that doesn't follow this syntax.
     ///
     /// # Examples
     ///
     /// ```
-    /// let tokens = vec!["WHERE", "age", ">", "18"];
-    /// let where_from_tokens = Where::new_from_tokens(tokens).unwrap();
+    /// # use rustic_sql::clauses::condition::Condition;
+    /// # use rustic_sql::clauses::where_sql::Where;
+    /// # use rustic_sql::lexer;
+    /// # use rustic_sql::operator::Operator;
+    /// # use rustic_sql::Value;
+    /// let tokens = lexer::tokenize("WHERE age > 18 ORDER BY age").unwrap();
+    /// let mut pos = 0;
+    /// let where_from_tokens = Where::new_from_tokens(&tokens, &mut pos).unwrap();
     /// let where_clause = Where {
     ///    condition: Condition::Simple {
-    ///         column: "age".to_string(),
+    ///         field: "age".to_string(),
     ///         operator: Operator::Greater,
-    ///         value: "18".to_string(),
+    ///         value: Value::Integer(18),
     ///     },
     /// };
     ///
     /// assert_eq!(where_from_tokens, where_clause);
+    /// assert_eq!(pos, 4);
     /// ```
     ///
-    pub fn new_from_tokens(tokens: Vec<&str>) -> Result<Self, SqlError> {
-        if tokens.len() < 4 {
-            return Err(SqlError::InvalidSyntax);
+    pub fn new_from_tokens(tokens: &[Token], pos: &mut usize) -> Result<Self, SqlError> {
+        let is_clause = tokens
+            .get(*pos)
+            .is_some_and(|t| t.is_keyword("WHERE") || t.is_keyword("HAVING"));
+        if !is_clause {
+            return Err(unexpected(tokens, *pos));
         }
-        let mut pos = 1;
-        let condition = parse_condition(&tokens, &mut pos)?;
+        *pos += 1;
+        let condition = parse_condition(tokens, pos)?;
 
         Ok(Self { condition })
     }
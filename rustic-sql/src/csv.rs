@@ -0,0 +1,345 @@
+use crate::{errors::SqlError, value::Value};
+use std::io::BufRead;
+
+/// Struct that reads and writes the records of the table files, following RFC 4180.
+///
+/// A field that contains the delimiter, a quote or a line break is written between quotes, with its quotes doubled.
+/// An empty field without quotes is a `Null` value, while `""` is an empty text.
+/// When reading, the records can end with `\n` or `\r\n`, and a quoted field can span several lines.
+///
+/// # Fields
+///
+/// * `delimiter` - The character that separates the fields of a record. It is `,` by default.
+///
+/// # Examples
+///
+/// ```
+/// # use rustic_sql::csv::Csv;
+/// let csv = Csv::default();
+/// let record = csv.format_record(&["1", "Pérez, Juan", "dice \"hola\""]);
+///
+/// assert_eq!(record, "1,\"Pérez, Juan\",\"dice \"\"hola\"\"\"");
+/// assert_eq!(csv.parse_record(&record).unwrap(), vec!["1", "Pérez, Juan", "dice \"hola\""]);
+/// ```
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Csv {
+    pub delimiter: char,
+}
+
+impl Default for Csv {
+    fn default() -> Self {
+        Self { delimiter: ',' }
+    }
+}
+
+impl Csv {
+    /// Creates a codec that separates the fields with the given delimiter.
+    ///
+    /// Returns `SqlError::Error` if the delimiter is a quote or a line break.
+    ///
+    pub fn new(delimiter: char) -> Result<Self, SqlError> {
+        if matches!(delimiter, '"' | '\n' | '\r') {
+            return Err(SqlError::Error);
+        }
+        Ok(Self { delimiter })
+    }
+
+    /// Returns an iterator over the records read from a reader.
+    ///
+    /// Empty lines are skipped, except after the header of a table with a single column, where an empty line is a record with a `Null` value.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - The reader of the table file.
+    ///
+    pub fn records<R: BufRead>(&self, reader: R) -> Records<R> {
+        Records {
+            csv: *self,
+            reader,
+            line: 0,
+            columns: 0,
+        }
+    }
+
+    /// Splits a record into its fields, removing the quotes of the quoted fields.
+    ///
+    /// Returns `SqlError::InvalidTable` if a quoted field is not closed or has text after its closing quote.
+    ///
+    pub fn parse_record(&self, record: &str) -> Result<Vec<String>, SqlError> {
+        let fields = self.parse_fields(record)?;
+        Ok(fields.into_iter().map(Option::unwrap_or_default).collect())
+    }
+
+    /// Splits a record into its fields like `parse_record`, with `None` for the empty fields without quotes, which hold `Null` values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use rustic_sql::csv::Csv;
+    /// let fields = Csv::default().parse_fields("1,,\"\"").unwrap();
+    /// assert_eq!(fields, vec![Some(String::from("1")), None, Some(String::new())]);
+    /// ```
+    ///
+    pub fn parse_fields(&self, record: &str) -> Result<Vec<Option<String>>, SqlError> {
+        let mut fields = Vec::new();
+        let mut field = String::new();
+        let mut chars = record.chars().peekable();
+        let mut in_quotes = false;
+        let mut was_quoted = false;
+
+        let finish = |field: String, was_quoted: bool| match field.is_empty() && !was_quoted {
+            true => None,
+            false => Some(field),
+        };
+
+        while let Some(char) = chars.next() {
+            if in_quotes {
+                match char {
+                    '"' if chars.peek() == Some(&'"') => {
+                        field.push('"');
+                        chars.next();
+                    }
+                    '"' => in_quotes = false,
+                    _ => field.push(char),
+                }
+            } else if char == self.delimiter {
+                fields.push(finish(std::mem::take(&mut field), was_quoted));
+                was_quoted = false;
+            } else if was_quoted {
+                return Err(SqlError::InvalidTable);
+            } else if char == '"' && field.is_empty() {
+                in_quotes = true;
+                was_quoted = true;
+            } else {
+                field.push(char);
+            }
+        }
+        if in_quotes {
+            return Err(SqlError::InvalidTable);
+        }
+        fields.push(finish(field, was_quoted));
+
+        Ok(fields)
+    }
+
+    /// Returns true if the text ends inside a quoted field.
+    fn is_in_quotes(&self, text: &str) -> bool {
+        let mut in_quotes = false;
+        let mut can_open = true;
+
+        for char in text.chars() {
+            if in_quotes {
+                if char == '"' {
+                    in_quotes = false;
+                    // A second quote right after is an escaped quote, which opens the field again.
+                    can_open = true;
+                }
+            } else if char == '"' && can_open {
+                in_quotes = true;
+            } else {
+                can_open = char == self.delimiter;
+            }
+        }
+        in_quotes
+    }
+
+    /// Joins the fields into a record, quoting the ones that need it.
+    pub fn format_record<S: AsRef<str>>(&self, fields: &[S]) -> String {
+        let fields: Vec<String> = fields
+            .iter()
+            .map(|f| self.format_field(f.as_ref()))
+            .collect();
+        fields.join(&self.delimiter.to_string())
+    }
+
+    /// Joins the values of a row into a record. `Null` values are written as empty fields, and the other values are quoted when they need it.
+    pub fn format_values(&self, values: &[Value]) -> String {
+        let fields: Vec<String> = values
+            .iter()
+            .map(|value| match value {
+                Value::Null => String::new(),
+                value => self.format_field(&value.to_string()),
+            })
+            .collect();
+        fields.join(&self.delimiter.to_string())
+    }
+
+    /// Returns the field as it is written in a record, between quotes if it is empty or contains the delimiter, a quote or a line break.
+    pub fn format_field(&self, field: &str) -> String {
+        if field.is_empty() || field.contains([self.delimiter, '"', '\n', '\r']) {
+            format!("\"{}\"", field.replace('"', "\"\""))
+        } else {
+            field.to_string()
+        }
+    }
+}
+
+/// Iterator over the records of a table file, created with `Csv::records`.
+///
+/// Each item is the list of fields of a record, as read by `Csv::parse_fields`, or `SqlError::MalformedRecord` with the line where the record starts if it is malformed.
+///
+pub struct Records<R> {
+    csv: Csv,
+    reader: R,
+    line: usize,
+    columns: usize,
+}
+
+impl<R: BufRead> Records<R> {
+    /// Reads the first record of the table file, which holds the names of its columns.
+    ///
+    /// Returns `SqlError::InvalidTable` if the file is empty.
+    ///
+    pub fn header(&mut self) -> Result<Vec<String>, SqlError> {
+        let fields = self.next().ok_or(SqlError::InvalidTable)??;
+        self.columns = fields.len();
+        Ok(fields.into_iter().map(Option::unwrap_or_default).collect())
+    }
+}
+
+impl<R: BufRead> Iterator for Records<R> {
+    type Item = Result<Vec<Option<String>>, SqlError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut record = String::new();
+
+        // Empty lines are skipped, so the record is read again until it has text or the file ends.
+        // In a table with a single column, an empty line is the record of a `Null` value.
+        loop {
+            record.clear();
+            let start = self.line + 1;
+
+            loop {
+                let read = match self.reader.read_line(&mut record) {
+                    Ok(read) => read,
+                    Err(e) => return Some(Err(SqlError::from(e))),
+                };
+                if read > 0 {
+                    self.line += 1;
+                }
+                // A quoted field can have line breaks, so the record continues in the next line.
+                let is_complete = !self.csv.is_in_quotes(&record);
+                if read == 0 && !is_complete {
+                    return Some(Err(SqlError::MalformedRecord { line: start }));
+                }
+                if read == 0 || is_complete {
+                    break;
+                }
+            }
+            if record.is_empty() {
+                return None;
+            }
+
+            let line = record
+                .strip_suffix('\n')
+                .map(|line| line.strip_suffix('\r').unwrap_or(line))
+                .unwrap_or(&record);
+            if !line.is_empty() || self.columns == 1 {
+                return Some(
+                    self.csv
+                        .parse_fields(line)
+                        .map_err(|_| SqlError::MalformedRecord { line: start }),
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Csv;
+    use crate::{errors::SqlError, value::Value};
+
+    fn read(text: &str) -> Vec<Result<Vec<Option<String>>, SqlError>> {
+        Csv::default().records(text.as_bytes()).collect()
+    }
+
+    #[test]
+    fn parse_quoted_fields() {
+        let csv = Csv::default();
+
+        assert_eq!(csv.parse_record("a,,c").unwrap(), vec!["a", "", "c"]);
+        assert_eq!(
+            csv.parse_record("\"a,b\",\"\"\"c\"\"\",\"\"").unwrap(),
+            vec!["a,b", "\"c\"", ""]
+        );
+        assert_eq!(csv.parse_record("di\"ce").unwrap(), vec!["di\"ce"]);
+        assert_eq!(
+            csv.parse_fields("a,,\"\",").unwrap(),
+            vec![Some(String::from("a")), None, Some(String::new()), None]
+        );
+        assert_eq!(csv.parse_record("\"a\"b"), Err(SqlError::InvalidTable));
+        assert_eq!(csv.parse_record("\"a"), Err(SqlError::InvalidTable));
+    }
+
+    #[test]
+    fn format_and_parse_round_trip() {
+        let csv = Csv::new(';').unwrap();
+        let fields = vec!["1", "a;b", "línea\nnueva", "dice \"hola\"", "x,y"];
+        let record = csv.format_record(&fields);
+
+        assert_eq!(
+            record,
+            "1;\"a;b\";\"línea\nnueva\";\"dice \"\"hola\"\"\";x,y"
+        );
+        assert_eq!(csv.parse_record(&record).unwrap(), fields);
+        assert_eq!(Csv::new('"'), Err(SqlError::Error));
+
+        let values = vec![Value::Integer(1), Value::Null, Value::Text(String::new())];
+        let record = csv.format_values(&values);
+        assert_eq!(record, "1;;\"\"");
+        let fields = csv.parse_fields(&record).unwrap();
+        let read: Vec<Value> = fields.iter().map(|f| Value::from_field(Some(f))).collect();
+        assert_eq!(read, values);
+    }
+
+    #[test]
+    fn read_records() {
+        let field = |text: &str| Some(String::from(text));
+        let text = "id,texto\r\n1,\"dos\r\nlíneas\"\r\n\n2,\"a,b\"\n3,di\"ce\n4,\n5,\"\"";
+        let mut records = Csv::default().records(text.as_bytes());
+
+        assert_eq!(
+            records.header(),
+            Ok(vec![String::from("id"), String::from("texto")])
+        );
+        assert_eq!(
+            records.collect::<Vec<_>>(),
+            vec![
+                Ok(vec![field("1"), field("dos\r\nlíneas")]),
+                Ok(vec![field("2"), field("a,b")]),
+                Ok(vec![field("3"), field("di\"ce")]),
+                Ok(vec![field("4"), None]),
+                Ok(vec![field("5"), field("")]),
+            ]
+        );
+        assert_eq!(
+            read("id\n\"sin cerrar\n"),
+            vec![
+                Ok(vec![field("id")]),
+                Err(SqlError::MalformedRecord { line: 2 })
+            ]
+        );
+        assert_eq!(
+            read("id,texto\n1,\"dos\nlíneas\"\n\n2,\"a\"b\n").last(),
+            Some(&Err(SqlError::MalformedRecord { line: 5 }))
+        );
+        assert_eq!(
+            Csv::default().records("".as_bytes()).header(),
+            Err(SqlError::InvalidTable)
+        );
+
+        // In a table with a single column, an empty line is a `Null` value.
+        let mut records = Csv::default().records("\nid\n\n1\n\n".as_bytes());
+        assert_eq!(records.header(), Ok(vec![String::from("id")]));
+        assert_eq!(
+            records.collect::<Vec<_>>(),
+            vec![Ok(vec![None]), Ok(vec![field("1")]), Ok(vec![None])]
+        );
+
+        // Many empty lines in a row don't grow the stack.
+        let blank = format!("id{}1\n", "\n".repeat(200_000));
+        assert_eq!(read(&blank).len(), 2);
+    }
+}
@@ -0,0 +1,1742 @@
+use crate::{
+    clauses::{
+        alter_sql::{Alter, AlterAction},
+        create_sql::Create,
+        delete_sql::Delete,
+        drop_sql::Drop,
+        insert_sql::{Insert, InsertSource},
+        select_sql::Select,
+        truncate_sql::Truncate,
+        update_sql::Update,
+    },
+    csv::Csv,
+    errors::SqlError,
+    lexer::{fold_identifiers, tokenize, IdentifierCase},
+    parser::{parse_tokens, Statement},
+    query_result::QueryResult,
+    schema::Schema,
+    script::split_statements,
+    storage::{lock_tables, LockMode},
+    transaction::{recover_transactions, Transaction},
+    utils::{find_file_in_folder, table_to_csv},
+    wal::recover_log,
+};
+use std::{
+    fs::{self, File},
+    io::BufReader,
+    path::Path,
+    sync::Mutex,
+    time::Duration,
+};
+
+/// Struct that represents a database, which is a folder where every table is stored as a csv file.
+///
+/// # Fields
+///
+/// * `folder_path` - The path to the folder where the tables are stored.
+/// * `identifier_case` - What is done with the case of the table and column names that are not between double quotes. They are kept as written by default.
+/// * `lock_timeout` - How long a statement waits for the locks of its tables, held by other processes, before failing with `SqlError::TableLocked`.
+/// * `csv` - The codec used to read and write the table files. It separates the fields with `,` by default.
+/// * `transaction` - The transaction started with `BEGIN`, if there is one running.
+///
+/// # Examples
+///
+/// ```
+/// # use rustic_sql::Database;
+/// # use rustic_sql::QueryResult;
+/// # use rustic_sql::Value;
+/// let db = Database::open("tablas").unwrap();
+/// let result = db.execute("SELECT id, nombre FROM clientes WHERE id = 1").unwrap();
+///
+/// assert_eq!(
+///     result,
+///     QueryResult::Rows {
+///         columns: vec!["id".to_string(), "nombre".to_string()],
+///         rows: vec![vec![Value::Integer(1), Value::Text("Juan".to_string())]],
+///     }
+/// );
+/// ```
+///
+#[derive(Debug)]
+pub struct Database {
+    folder_path: String,
+    identifier_case: IdentifierCase,
+    lock_timeout: Duration,
+    csv: Csv,
+    transaction: Mutex<Option<Transaction>>,
+}
+
+/// Time that a statement waits for the locks of its tables by default.
+pub const DEFAULT_LOCK_TIMEOUT: Duration = Duration::from_secs(5);
+
+impl Database {
+    /// Opens the database stored in the given folder.
+    ///
+    /// The operations of the write-ahead log that were committed are applied again, and the ones that were not are discarded.
+    /// Then the interrupted transactions are rolled back, since the ones that were committing are already in the write-ahead log.
+    ///
+    /// Returns `SqlError::FolderNotFound` if the folder does not exist.
+    ///
+    /// # Arguments
+    ///
+    /// * `folder_path` - A string slice that holds the path to the folder where the tables are stored.
+    ///
+    pub fn open(folder_path: &str) -> Result<Self, SqlError> {
+        if !Path::new(folder_path).is_dir() {
+            return Err(SqlError::FolderNotFound(folder_path.to_string()));
+        }
+        recover_log(folder_path)?;
+        recover_transactions(folder_path)?;
+
+        Ok(Self {
+            folder_path: folder_path.to_string(),
+            identifier_case: IdentifierCase::default(),
+            lock_timeout: DEFAULT_LOCK_TIMEOUT,
+            csv: Csv::default(),
+            transaction: Mutex::new(None),
+        })
+    }
+
+    /// Returns the same database, converting the identifiers of the queries to the given case.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use rustic_sql::Database;
+    /// # use rustic_sql::lexer::IdentifierCase;
+    /// let db = Database::open("tablas").unwrap().with_identifier_case(IdentifierCase::Lower);
+    /// assert!(db.execute("SELECT Nombre FROM Clientes").is_ok());
+    /// ```
+    ///
+    pub fn with_identifier_case(self, identifier_case: IdentifierCase) -> Self {
+        Self {
+            identifier_case,
+            ..self
+        }
+    }
+
+    /// Returns the same database, waiting up to the given time for the locks of the tables used by each statement.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use rustic_sql::Database;
+    /// # use std::time::Duration;
+    /// let db = Database::open("tablas").unwrap().with_lock_timeout(Duration::from_millis(200));
+    /// ```
+    ///
+    pub fn with_lock_timeout(self, lock_timeout: Duration) -> Self {
+        Self {
+            lock_timeout,
+            ..self
+        }
+    }
+
+    /// Returns the same database, reading and writing the table files with the given codec.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use rustic_sql::Database;
+    /// # use rustic_sql::csv::Csv;
+    /// let db = Database::open("tablas").unwrap().with_csv(Csv::new(';').unwrap());
+    /// ```
+    ///
+    pub fn with_csv(self, csv: Csv) -> Self {
+        Self { csv, ..self }
+    }
+
+    /// Returns the path to the folder where the tables are stored.
+    pub fn folder_path(&self) -> &str {
+        &self.folder_path
+    }
+
+    /// Returns whether a transaction started with `BEGIN` is running.
+    pub fn in_transaction(&self) -> bool {
+        self.current_transaction().is_some()
+    }
+
+    fn current_transaction(&self) -> std::sync::MutexGuard<'_, Option<Transaction>> {
+        // A thread that panicked while holding the transaction leaves it as it was before the statement.
+        self.transaction
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Returns the names of the tables stored in the database, sorted alphabetically.
+    ///
+    /// Every csv file in the folder is a table, and its name is the file name without the extension.
+    ///
+    pub fn tables(&self) -> Result<Vec<String>, SqlError> {
+        let entries = fs::read_dir(&self.folder_path).map_err(SqlError::from)?;
+        let mut tables = Vec::new();
+
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_file() && path.extension().is_some_and(|ext| ext == "csv") {
+                if let Some(name) = path.file_stem() {
+                    tables.push(name.to_string_lossy().to_string());
+                }
+            }
+        }
+        tables.sort();
+
+        Ok(tables)
+    }
+
+    /// Returns the column names of the given table, as they appear in the first record of its csv file.
+    ///
+    /// # Arguments
+    ///
+    /// * `table_name` - A string slice that holds the name of the table.
+    ///
+    pub fn columns(&self, table_name: &str) -> Result<Vec<String>, SqlError> {
+        let path = Path::new(&self.folder_path).join(table_name.to_string() + ".csv");
+        if !path.is_file() {
+            return Err(SqlError::TableNotFound(table_name.to_string()));
+        }
+        let file = File::open(path).map_err(SqlError::from)?;
+
+        self.csv.records(BufReader::new(file)).header()
+    }
+
+    /// Parses the query and executes the statement it holds.
+    ///
+    /// Returns `QueryResult::Rows` with the selected columns and rows for SELECT clauses.
+    ///
+    /// Returns `QueryResult::Affected` with the number of affected registers for INSERT, DELETE, UPDATE and TRUNCATE clauses, and with 0 for CREATE TABLE, DROP TABLE, ALTER TABLE, BEGIN, COMMIT and ROLLBACK.
+    ///
+    /// Returns an error for invalid syntax or unknown clauses. When the error is caused by a token, it has the offset of the token in the query.
+    /// Like any statement that fails, a query that can't be parsed rolls back the running transaction.
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - A string slice that holds the SQL query to be executed.
+    ///
+    pub fn execute(&self, query: &str) -> Result<QueryResult, SqlError> {
+        let parsed = tokenize(query).and_then(|mut lexed| {
+            fold_identifiers(query, &mut lexed, self.identifier_case);
+            parse_tokens(query, &lexed)
+        });
+        let statement = match parsed {
+            Ok(statement) => statement,
+            Err(e) => {
+                // A statement that can't be read fails like any other, so it rolls back the running transaction.
+                self.current_transaction().take();
+                return Err(e.locate(query));
+            }
+        };
+
+        self.execute_statement(statement)
+            .map_err(|e| e.locate(query))
+    }
+
+    /// Executes a statement read by `parser::parse`, like `execute` does.
+    ///
+    /// The tables that the statement reads are locked with a shared lock and the ones it writes with an exclusive lock, until it ends.
+    /// Returns `SqlError::TableLocked` if another process holds a conflicting lock for longer than the lock timeout.
+    ///
+    /// After `BEGIN`, the statements run on copies of their tables, which stay locked with an exclusive lock until the transaction ends.
+    /// `COMMIT` replaces the written tables with their copies all at once, while `ROLLBACK` discards the copies.
+    /// A statement that fails inside a transaction rolls it back. `CREATE TABLE`, `DROP TABLE` and `ALTER TABLE` can't run inside a transaction.
+    ///
+    /// # Arguments
+    ///
+    /// * `statement` - The statement to execute.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use rustic_sql::Database;
+    /// let db = Database::open("tablas").unwrap();
+    /// db.execute("BEGIN").unwrap();
+    /// db.execute("UPDATE ordenes SET id_cliente = 2 WHERE id = 101").unwrap();
+    /// db.execute("UPDATE clientes SET email = 'ana@email.com' WHERE id = 2").unwrap();
+    /// db.execute("COMMIT").unwrap();
+    /// ```
+    ///
+    pub fn execute_statement(&self, statement: Statement) -> Result<QueryResult, SqlError> {
+        let mut transaction = self.current_transaction();
+        let no_transaction =
+            || SqlError::InvalidTransaction(String::from("No transaction is running"));
+
+        match statement {
+            Statement::Begin => match transaction.as_ref() {
+                Some(_) => Err(SqlError::InvalidTransaction(String::from(
+                    "A transaction is already running",
+                ))),
+                None => {
+                    *transaction = Some(Transaction::begin(&self.folder_path, self.lock_timeout)?);
+                    Ok(QueryResult::Affected(0))
+                }
+            },
+            Statement::Commit => transaction
+                .take()
+                .ok_or_else(no_transaction)?
+                .commit()
+                .map(|_| QueryResult::Affected(0)),
+            Statement::Rollback => transaction
+                .take()
+                .ok_or_else(no_transaction)
+                .map(|_| QueryResult::Affected(0)),
+            statement => match transaction.as_mut() {
+                Some(running) => {
+                    let result = self.run_in_transaction(running, statement);
+                    if result.is_err() {
+                        transaction.take();
+                    }
+                    result
+                }
+                None => {
+                    drop(transaction);
+                    let _locks = lock_tables(
+                        &self.folder_path,
+                        &self.statement_locks(&statement),
+                        self.lock_timeout,
+                    )?;
+                    self.run(statement)
+                }
+            },
+        }
+    }
+
+    /// Runs a statement on the copies of its tables kept by a transaction, copying the tables that the transaction didn't use yet.
+    fn run_in_transaction(
+        &self,
+        transaction: &mut Transaction,
+        statement: Statement,
+    ) -> Result<QueryResult, SqlError> {
+        if let Statement::Create(_) | Statement::Drop(_) | Statement::Alter(_) = statement {
+            return Err(SqlError::InvalidTransaction(String::from(
+                "CREATE, DROP and ALTER TABLE can't run inside a transaction",
+            )));
+        }
+        transaction.stage(&self.statement_locks(&statement))?;
+
+        let staged = Database {
+            folder_path: transaction.staging_path().to_string_lossy().to_string(),
+            identifier_case: self.identifier_case,
+            lock_timeout: self.lock_timeout,
+            csv: self.csv,
+            transaction: Mutex::new(None),
+        };
+        staged.run(statement)
+    }
+
+    /// Runs a statement on the tables of the folder, without locking them.
+    ///
+    /// The methods of each clause are private, so a statement only runs here after `execute_statement` locks its tables or stages them in the running transaction.
+    ///
+    fn run(&self, statement: Statement) -> Result<QueryResult, SqlError> {
+        match statement {
+            Statement::Select(clause) => self.select(&clause),
+            Statement::Insert(mut clause) => self.insert(&mut clause),
+            Statement::Update(mut clause) => self.update(&mut clause),
+            Statement::Delete(clause) => self.delete(&clause),
+            Statement::Create(clause) => self.create(&clause),
+            Statement::Drop(clause) => self.drop(&clause),
+            Statement::Truncate(clause) => self.truncate(&clause),
+            Statement::Alter(clause) => self.alter(&clause),
+            Statement::Begin | Statement::Commit | Statement::Rollback => Err(
+                SqlError::InvalidTransaction(String::from("Transactions can't be nested")),
+            ),
+        }
+    }
+
+    /// Returns the tables that a statement uses, with the lock that each one needs.
+    ///
+    /// Only the tables that exist are locked, except for the ones that the statement creates, so a query on a missing table doesn't leave a lock file behind.
+    ///
+    fn statement_locks(&self, statement: &Statement) -> Vec<(String, LockMode)> {
+        let select_tables = |select: &Select| {
+            let mut tables = vec![select.table_name.clone()];
+            tables.extend(select.joins.iter().map(|join| join.table_name.clone()));
+            tables
+        };
+
+        let (read, written, created) = match statement {
+            Statement::Select(select) => (select_tables(select), Vec::new(), Vec::new()),
+            Statement::Insert(insert) => {
+                let read = match &insert.source {
+                    InsertSource::Select(select) => select_tables(select),
+                    InsertSource::Values(_) => Vec::new(),
+                };
+                (
+                    read,
+                    vec![insert.into_clause.table_name.clone()],
+                    Vec::new(),
+                )
+            }
+            Statement::Update(update) => (Vec::new(), vec![update.table_name.clone()], Vec::new()),
+            Statement::Delete(delete) => (Vec::new(), vec![delete.table_name.clone()], Vec::new()),
+            Statement::Create(create) => (Vec::new(), Vec::new(), vec![create.table_name.clone()]),
+            Statement::Drop(drop) => (Vec::new(), vec![drop.table_name.clone()], Vec::new()),
+            Statement::Truncate(truncate) => {
+                (Vec::new(), vec![truncate.table_name.clone()], Vec::new())
+            }
+            Statement::Alter(alter) => match &alter.action {
+                AlterAction::RenameTo(new_name) => (
+                    Vec::new(),
+                    vec![alter.table_name.clone()],
+                    vec![new_name.clone()],
+                ),
+                _ => (Vec::new(), vec![alter.table_name.clone()], Vec::new()),
+            },
+            Statement::Begin | Statement::Commit | Statement::Rollback => {
+                (Vec::new(), Vec::new(), Vec::new())
+            }
+        };
+
+        let exists =
+            |table: &String| find_file_in_folder(&self.folder_path, &(table.to_string() + ".csv"));
+        let read = read
+            .into_iter()
+            .filter(exists)
+            .map(|t| (t, LockMode::Shared));
+        let written = written
+            .into_iter()
+            .filter(exists)
+            .map(|t| (t, LockMode::Exclusive));
+        let created = created.into_iter().map(|t| (t, LockMode::Exclusive));
+        read.chain(written).chain(created).collect()
+    }
+
+    /// Returns the schema of the given table, or `None` if the table was not created with `CREATE TABLE`.
+    ///
+    /// # Arguments
+    ///
+    /// * `table_name` - A string slice that holds the name of the table.
+    ///
+    pub fn schema(&self, table_name: &str) -> Result<Option<Schema>, SqlError> {
+        Schema::load(&self.folder_path, table_name)
+    }
+
+    /// Splits a script into statements and executes them in order.
+    ///
+    /// Returns the result of each executed statement.
+    /// If `continue_on_error` is false, the execution stops at the first statement that fails, which is the last result returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `script` - A string slice that holds the statements, terminated by `;`.
+    /// * `continue_on_error` - Whether to keep executing the following statements after one fails.
+    ///
+    pub fn execute_script(
+        &self,
+        script: &str,
+        continue_on_error: bool,
+    ) -> Vec<Result<QueryResult, SqlError>> {
+        let mut results = Vec::new();
+
+        for statement in split_statements(script) {
+            let result = self.execute(&statement);
+            let failed = result.is_err();
+            results.push(result);
+
+            if failed && !continue_on_error {
+                break;
+            }
+        }
+        results
+    }
+
+    /// Executes a `SELECT` clause and returns the selected rows.
+    fn select(&self, clause: &Select) -> Result<QueryResult, SqlError> {
+        let table = clause.open_table(&self.folder_path)?;
+        let joined = clause.open_joined_tables(&self.folder_path)?;
+        let result = clause.apply_to_tables(table, joined, &self.csv)?;
+
+        if clause.columns[0] == "*" {
+            QueryResult::from_table(&result, &result.columns)
+        } else {
+            QueryResult::from_table(&result, &clause.columns)
+        }
+    }
+
+    /// Executes a `CREATE TABLE` clause, creating the table file and its schema.
+    fn create(&self, clause: &Create) -> Result<QueryResult, SqlError> {
+        clause.apply_to_folder(&self.folder_path, &self.csv)?;
+
+        Ok(QueryResult::Affected(0))
+    }
+
+    /// Executes a `DROP TABLE` clause, deleting the table file and its schema.
+    fn drop(&self, clause: &Drop) -> Result<QueryResult, SqlError> {
+        clause.apply_to_folder(&self.folder_path)?;
+
+        Ok(QueryResult::Affected(0))
+    }
+
+    /// Executes a `TRUNCATE TABLE` clause and returns the number of deleted registers.
+    fn truncate(&self, clause: &Truncate) -> Result<QueryResult, SqlError> {
+        let deleted = clause.apply_to_folder(&self.folder_path, &self.csv)?;
+
+        Ok(QueryResult::Affected(deleted))
+    }
+
+    /// Executes an `ALTER TABLE` clause, rewriting the table file and its schema.
+    fn alter(&self, clause: &Alter) -> Result<QueryResult, SqlError> {
+        clause.apply_to_folder(&self.folder_path, &self.csv)?;
+
+        Ok(QueryResult::Affected(0))
+    }
+
+    /// Executes an `INSERT` clause and returns the number of inserted registers.
+    ///
+    /// If the clause doesn't list its columns, the values are for all the columns of the table, in order.
+    /// The rows of an `INSERT ... SELECT` are read with the `SELECT` before any of them is inserted, so the table can be copied into itself.
+    /// If the table has a schema, the values are validated against it.
+    ///
+    fn insert(&self, clause: &mut Insert) -> Result<QueryResult, SqlError> {
+        let mut file = clause.open_table(&self.folder_path)?;
+        if clause.into_clause.columns.is_empty() {
+            clause.into_clause.columns = self.columns(&clause.into_clause.table_name)?;
+        }
+        if let InsertSource::Select(select) = &clause.source {
+            let rows = match self.select(select)? {
+                QueryResult::Rows { rows, .. } => rows,
+                QueryResult::Affected(_) => Vec::new(),
+            };
+            clause.source = InsertSource::Values(rows);
+        }
+
+        let rows = clause.rows()?;
+        if rows
+            .iter()
+            .any(|row| row.len() != clause.into_clause.columns.len())
+        {
+            return Err(SqlError::InvalidSyntax);
+        }
+        let inserted = rows.len();
+
+        if let Some(schema) = self.schema(&clause.into_clause.table_name)? {
+            clause.validate(&schema)?;
+        }
+        clause.apply_to_table(&mut file, &self.folder_path, &self.csv)?;
+
+        Ok(QueryResult::Affected(inserted))
+    }
+
+    /// Executes a `DELETE` clause and returns the number of deleted registers.
+    fn delete(&self, clause: &Delete) -> Result<QueryResult, SqlError> {
+        let table = clause.open_table(&self.folder_path)?;
+        let (result, deleted) = clause.apply_to_table_counting(table, &self.csv)?;
+
+        let lines = table_to_csv(&result, &result.columns, &self.csv)?;
+        clause.write_table(lines, &self.folder_path)?;
+
+        Ok(QueryResult::Affected(deleted))
+    }
+
+    /// Executes an `UPDATE` clause and returns the number of updated registers.
+    ///
+    /// If the table has a schema, the new values are validated against it.
+    ///
+    fn update(&self, clause: &mut Update) -> Result<QueryResult, SqlError> {
+        let table = clause.open_table(&self.folder_path)?;
+        let schema = self.schema(&clause.table_name)?;
+        if let Some(schema) = &schema {
+            clause.validate(schema)?;
+        }
+        let (result, updated) =
+            clause.apply_to_table_counting(table, schema.as_ref(), &self.csv)?;
+
+        let lines = table_to_csv(&result, &result.columns, &self.csv)?;
+        clause.write_table(lines, &self.folder_path)?;
+
+        Ok(QueryResult::Affected(updated))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Database;
+    use crate::{
+        csv::Csv,
+        errors::SqlError,
+        functions::register_function,
+        lexer::IdentifierCase,
+        parser::parse,
+        query_result::QueryResult,
+        storage::{lock_table, LockMode},
+        utils::temp_folder,
+        value::Value,
+    };
+    use std::{fs, path::Path, time::Duration};
+
+    #[test]
+    fn open_missing_folder() {
+        let db = Database::open("no_existe");
+        assert_eq!(
+            db.unwrap_err(),
+            SqlError::FolderNotFound(String::from("no_existe"))
+        );
+    }
+
+    #[test]
+    fn rewrites_survive_crashes_and_concurrency() {
+        let folder = temp_folder("rewrites", &["testing"]);
+        fs::copy("tablas/testing.csv", format!("{}/copia.csv", folder)).unwrap();
+
+        let db = Database::open(&folder).unwrap();
+        assert_eq!(
+            db.execute("SELECT COUNT(*) FROM testing").unwrap().to_csv(),
+            vec!["COUNT(*)", "3"]
+        );
+
+        std::thread::scope(|scope| {
+            for table in ["testing", "copia"] {
+                let db = &db;
+                scope.spawn(move || {
+                    for _ in 0..20 {
+                        db.execute(&format!("UPDATE {} SET edad = edad + 1", table))
+                            .unwrap();
+                    }
+                });
+            }
+        });
+        for table in ["testing", "copia"] {
+            assert_eq!(
+                db.execute(&format!("SELECT SUM(edad) FROM {}", table))
+                    .unwrap()
+                    .to_csv(),
+                vec!["SUM(edad)", "148"]
+            );
+        }
+
+        fs::remove_dir_all(&folder).unwrap();
+    }
+
+    #[test]
+    fn numbers_written_differently_are_typed() {
+        let folder = temp_folder("typed_fields", &["testing"]);
+        fs::write(
+            format!("{}/productos.csv", folder),
+            "codigo,precio,stock\n007,10.50,1e3\n012,9.25,5\nA01,2,x\n",
+        )
+        .unwrap();
+        let db = Database::open(&folder).unwrap();
+
+        assert_eq!(
+            db.execute("SELECT codigo FROM productos WHERE precio > 10")
+                .unwrap()
+                .to_csv(),
+            vec!["codigo", "007"]
+        );
+        assert_eq!(
+            db.execute("SELECT codigo FROM productos WHERE codigo BETWEEN 5 AND 10")
+                .unwrap()
+                .to_csv(),
+            vec!["codigo", "007"]
+        );
+        assert_eq!(
+            db.execute("SELECT SUM(precio) FROM productos")
+                .unwrap()
+                .to_csv(),
+            vec!["SUM(precio)", "21.75"]
+        );
+        assert_eq!(
+            db.execute("SELECT precio FROM productos ORDER BY precio")
+                .unwrap()
+                .to_csv(),
+            vec!["precio", "2", "9.25", "10.50"]
+        );
+        // Comparing a number with a text is unknown, so the row is not selected.
+        assert_eq!(
+            db.execute("SELECT codigo FROM productos WHERE stock > 100")
+                .unwrap()
+                .to_csv(),
+            vec!["codigo", "007"]
+        );
+
+        fs::remove_dir_all(&folder).unwrap();
+    }
+
+    #[test]
+    fn rewrites_keep_untouched_fields() {
+        let folder = temp_folder("untouched_fields", &[]);
+        let path = format!("{}/productos.csv", folder);
+        fs::write(
+            &path,
+            "codigo,precio,stock,activo\n007,10.50,1e3,true\n012,9.25,5,false\n",
+        )
+        .unwrap();
+        let db = Database::open(&folder).unwrap();
+
+        assert_eq!(
+            db.execute("UPDATE productos SET stock = 6 WHERE codigo = 12"),
+            Ok(QueryResult::Affected(1))
+        );
+        assert_eq!(
+            db.execute("DELETE FROM productos WHERE codigo = 99"),
+            Ok(QueryResult::Affected(0))
+        );
+        db.execute("ALTER TABLE productos ADD COLUMN nota TEXT")
+            .unwrap();
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "codigo,precio,stock,activo,nota\n007,10.50,1e3,true,\n012,9.25,6,false,\n"
+        );
+
+        fs::remove_dir_all(&folder).unwrap();
+    }
+
+    #[test]
+    fn tables_with_another_delimiter() {
+        let folder = temp_folder("delimiter", &["testing"]);
+        fs::write(
+            format!("{}/precios.csv", folder),
+            "producto;precio\nPan;1,5\n",
+        )
+        .unwrap();
+        let db = Database::open(&folder)
+            .unwrap()
+            .with_csv(Csv::new(';').unwrap());
+
+        assert_eq!(db.columns("precios").unwrap(), vec!["producto", "precio"]);
+        db.execute("INSERT INTO precios VALUES ('Té; verde', '2,0')")
+            .unwrap();
+        db.execute("UPDATE precios SET precio = '1,75' WHERE producto = 'Pan'")
+            .unwrap();
+        db.execute("ALTER TABLE precios ADD COLUMN stock").unwrap();
+        assert_eq!(
+            fs::read_to_string(format!("{}/precios.csv", folder)).unwrap(),
+            "producto;precio;stock\nPan;1,75;\n\"Té; verde\";2,0;\n"
+        );
+        assert_eq!(
+            db.execute("SELECT producto FROM precios WHERE precio = '2,0'")
+                .unwrap()
+                .to_csv(),
+            vec!["producto", "Té; verde"]
+        );
+
+        db.execute("CREATE TABLE stock (id INT, cantidad INT)")
+            .unwrap();
+        assert_eq!(
+            fs::read_to_string(format!("{}/stock.csv", folder)).unwrap(),
+            "id;cantidad\n"
+        );
+
+        fs::remove_dir_all(&folder).unwrap();
+    }
+
+    #[test]
+    fn statements_wait_for_locked_tables() {
+        let folder = temp_folder("locked_tables", &["testing"]);
+        let db = Database::open(&folder)
+            .unwrap()
+            .with_lock_timeout(Duration::from_millis(50));
+        let locked = Err(SqlError::TableLocked(String::from("testing")));
+
+        let reader = lock_table(&folder, "testing", LockMode::Shared, Duration::ZERO).unwrap();
+        assert!(db.execute("SELECT * FROM testing").is_ok());
+        assert_eq!(db.execute("DELETE FROM testing"), locked);
+        assert_eq!(
+            db.execute("INSERT INTO testing VALUES ('Beto', 'Ruiz', 25)"),
+            locked
+        );
+        drop(reader);
+
+        let writer = lock_table(&folder, "testing", LockMode::Exclusive, Duration::ZERO).unwrap();
+        assert_eq!(db.execute("SELECT * FROM testing"), locked);
+        let statement = parse("SELECT * FROM testing").unwrap();
+        assert_eq!(db.execute_statement(statement), locked);
+        assert_eq!(
+            db.execute("CREATE TABLE copia (nombre TEXT)"),
+            Ok(QueryResult::Affected(0))
+        );
+        assert_eq!(
+            db.execute("INSERT INTO copia SELECT nombre FROM testing"),
+            locked
+        );
+        assert_eq!(
+            db.execute("SELECT * FROM no_existe"),
+            Err(SqlError::TableNotFound(String::from("no_existe")))
+        );
+        drop(writer);
+
+        assert_eq!(
+            db.execute("INSERT INTO copia SELECT nombre FROM testing"),
+            Ok(QueryResult::Affected(3))
+        );
+        assert!(!Path::new(&folder).join(".no_existe.lock").exists());
+
+        fs::remove_dir_all(&folder).unwrap();
+    }
+
+    #[test]
+    fn transactions_are_all_or_nothing() {
+        let folder = temp_folder("transactions", &["testing"]);
+        for table in ["clientes", "ordenes"] {
+            fs::copy(
+                format!("tablas/{}.csv", table),
+                format!("{}/{}.csv", folder, table),
+            )
+            .unwrap();
+        }
+        let db = Database::open(&folder).unwrap();
+        let other = Database::open(&folder)
+            .unwrap()
+            .with_lock_timeout(Duration::from_millis(50));
+        let owner = "SELECT id_cliente FROM ordenes WHERE id = 101";
+        let move_order = [
+            "UPDATE ordenes SET id_cliente = 2 WHERE id = 101",
+            "UPDATE clientes SET email = 'ana@email.com' WHERE id = 2",
+        ];
+
+        assert_eq!(db.execute("BEGIN"), Ok(QueryResult::Affected(0)));
+        for statement in move_order {
+            assert_eq!(db.execute(statement), Ok(QueryResult::Affected(1)));
+        }
+        assert_eq!(db.execute(owner).unwrap().to_csv(), vec!["id_cliente", "2"]);
+        assert_eq!(
+            other.execute(owner),
+            Err(SqlError::TableLocked(String::from("ordenes")))
+        );
+        assert!(other.execute("SELECT * FROM testing").is_ok());
+        assert_eq!(db.execute("ROLLBACK"), Ok(QueryResult::Affected(0)));
+        assert_eq!(
+            other.execute(owner).unwrap().to_csv(),
+            vec!["id_cliente", "1"]
+        );
+
+        db.execute("BEGIN TRANSACTION").unwrap();
+        for statement in move_order {
+            db.execute(statement).unwrap();
+        }
+        assert!(db.in_transaction());
+        assert_eq!(db.execute("COMMIT;"), Ok(QueryResult::Affected(0)));
+        assert!(!db.in_transaction());
+        assert_eq!(
+            other.execute(owner).unwrap().to_csv(),
+            vec!["id_cliente", "2"]
+        );
+        assert_eq!(
+            other
+                .execute("SELECT email FROM clientes WHERE id = 2")
+                .unwrap()
+                .to_csv(),
+            vec!["email", "ana@email.com"]
+        );
+
+        // A statement that fails rolls back the whole transaction.
+        db.execute("BEGIN").unwrap();
+        db.execute("UPDATE ordenes SET id_cliente = 3 WHERE id = 101")
+            .unwrap();
+        assert!(db.execute("INSERT INTO clientes VALUES (4)").is_err());
+        assert!(!db.in_transaction());
+        assert_eq!(db.execute(owner).unwrap().to_csv(), vec!["id_cliente", "2"]);
+
+        // So does a statement with a syntax error.
+        db.execute("BEGIN").unwrap();
+        db.execute("UPDATE ordenes SET id_cliente = 3 WHERE id = 101")
+            .unwrap();
+        assert!(db.execute("INSRT INTO clientes VALUES (4)").is_err());
+        assert!(!db.in_transaction());
+        assert!(db.execute("COMMIT").is_err());
+        assert_eq!(db.execute(owner).unwrap().to_csv(), vec!["id_cliente", "2"]);
+
+        let invalid = |message: &str| Err(SqlError::InvalidTransaction(message.to_string()));
+        assert_eq!(db.execute("COMMIT"), invalid("No transaction is running"));
+        assert_eq!(db.execute("ROLLBACK"), invalid("No transaction is running"));
+        db.execute("BEGIN").unwrap();
+        assert_eq!(
+            db.execute("BEGIN"),
+            invalid("A transaction is already running")
+        );
+        assert_eq!(
+            db.execute("DROP TABLE ordenes"),
+            invalid("CREATE, DROP and ALTER TABLE can't run inside a transaction")
+        );
+        assert!(!db.in_transaction());
+
+        // A transaction of another process that stopped in the middle of its commit is finished when the database is opened.
+        let other_process = std::process::id() + 1;
+        let staging = Path::new(&folder).join(format!(".tx-{}-0", other_process));
+        fs::create_dir(&staging).unwrap();
+        fs::copy("tablas/clientes.csv", staging.join("clientes.csv")).unwrap();
+        let log = Path::new(&folder).join(".wal");
+        fs::create_dir_all(&log).unwrap();
+        fs::write(
+            log.join(format!("{}-0.0.csv", other_process)),
+            "id,id_cliente,producto,cantidad\n",
+        )
+        .unwrap();
+        fs::write(
+            log.join(format!("{}-0.log", other_process)),
+            format!("replace,ordenes.csv,{}-0.0.csv\n", other_process),
+        )
+        .unwrap();
+        let db = Database::open(&folder).unwrap();
+        assert_eq!(
+            db.execute("SELECT COUNT(*) FROM ordenes").unwrap().to_csv(),
+            vec!["COUNT(*)", "0"]
+        );
+        let leftovers = fs::read_dir(&folder)
+            .unwrap()
+            .flatten()
+            .filter(|entry| entry.file_name().to_string_lossy().starts_with(".tx-"));
+        assert_eq!(leftovers.count(), 0);
+        assert_eq!(fs::read_dir(&log).unwrap().count(), 0);
+
+        fs::remove_dir_all(&folder).unwrap();
+    }
+
+    #[test]
+    fn select_columns() {
+        let db = Database::open("tablas").unwrap();
+        let result = db
+            .execute("SELECT nombre, edad FROM testing WHERE edad > 20 ORDER BY edad DESC")
+            .unwrap();
+
+        assert_eq!(
+            result,
+            QueryResult::Rows {
+                columns: vec![String::from("nombre"), String::from("edad")],
+                rows: vec![
+                    vec![Value::Text(String::from("Carlos")), Value::Integer(40)],
+                    vec![Value::Text(String::from("Juan")), Value::Integer(30)],
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn select_inner_join() {
+        let db = Database::open("tablas").unwrap();
+        let result = db
+            .execute(
+                "SELECT c.nombre, o.producto FROM clientes AS c JOIN ordenes o ON c.id = o.id_cliente WHERE cantidad > 1 ORDER BY o.id",
+            )
+            .unwrap();
+
+        assert_eq!(
+            result,
+            QueryResult::Rows {
+                columns: vec![String::from("c.nombre"), String::from("o.producto")],
+                rows: vec![
+                    vec![
+                        Value::Text(String::from("Ana")),
+                        Value::Text(String::from("Teléfono"))
+                    ],
+                    vec![
+                        Value::Text(String::from("María")),
+                        Value::Text(String::from("Mouse"))
+                    ],
+                    vec![
+                        Value::Text(String::from("Laura")),
+                        Value::Text(String::from("Teléfono"))
+                    ],
+                ],
+            }
+        );
+
+        assert_eq!(
+            db.execute("SELECT id FROM clientes JOIN ordenes ON clientes.id = ordenes.id_cliente"),
+            Err(SqlError::InvalidColumn)
+        );
+    }
+
+    #[test]
+    fn select_left_and_cross_join() {
+        let folder = temp_folder("join", &["testing"]);
+        fs::write(
+            folder.clone() + "/personas.csv",
+            "id,nombre\n1,Juan\n2,Ana\n",
+        )
+        .unwrap();
+        fs::write(
+            folder.clone() + "/mascotas.csv",
+            "duenio,mascota\n1,Perro\n",
+        )
+        .unwrap();
+        let db = Database::open(&folder).unwrap();
+
+        let result = db
+            .execute("SELECT * FROM personas p LEFT JOIN mascotas ON mascotas.duenio = p.id")
+            .unwrap();
+        assert_eq!(
+            result,
+            QueryResult::Rows {
+                columns: vec![
+                    String::from("id"),
+                    String::from("nombre"),
+                    String::from("duenio"),
+                    String::from("mascota"),
+                ],
+                rows: vec![
+                    vec![
+                        Value::Integer(1),
+                        Value::Text(String::from("Juan")),
+                        Value::Integer(1),
+                        Value::Text(String::from("Perro")),
+                    ],
+                    vec![
+                        Value::Integer(2),
+                        Value::Text(String::from("Ana")),
+                        Value::Null,
+                        Value::Null,
+                    ],
+                ],
+            }
+        );
+
+        let result = db
+            .execute("SELECT * FROM personas CROSS JOIN testing")
+            .unwrap();
+        match result {
+            QueryResult::Rows { rows, .. } => assert_eq!(rows.len(), 6),
+            QueryResult::Affected(_) => panic!("expected rows"),
+        }
+
+        fs::remove_dir_all(&folder).unwrap();
+    }
+
+    #[test]
+    fn select_group_by_having() {
+        let db = Database::open("tablas").unwrap();
+        let result = db
+            .execute(
+                "SELECT producto, SUM(cantidad) FROM ordenes GROUP BY producto HAVING SUM(cantidad) > 1 ORDER BY producto",
+            )
+            .unwrap();
+
+        assert_eq!(
+            result,
+            QueryResult::Rows {
+                columns: vec![String::from("producto"), String::from("SUM(cantidad)")],
+                rows: vec![
+                    vec![Value::Text(String::from("Laptop")), Value::Integer(2)],
+                    vec![Value::Text(String::from("Mouse")), Value::Integer(2)],
+                    vec![Value::Text(String::from("Teléfono")), Value::Integer(4)],
+                ],
+            }
+        );
+
+        let result = db
+            .execute(
+                "SELECT c.nombre, COUNT(*) FROM clientes c JOIN ordenes o ON c.id = o.id_cliente GROUP BY c.nombre HAVING COUNT(*) > 1 ORDER BY c.nombre",
+            )
+            .unwrap();
+        match result {
+            QueryResult::Rows { rows, .. } => {
+                let names: Vec<String> = rows.iter().map(|r| r[0].to_string()).collect();
+                assert_eq!(names, ["José", "Juan", "Laura", "María"]);
+                assert!(rows.iter().all(|r| r[1] == Value::Integer(2)));
+            }
+            QueryResult::Affected(_) => panic!("expected rows"),
+        }
+    }
+
+    #[test]
+    fn select_with_aliases() {
+        let db = Database::open("tablas").unwrap();
+        let result = db
+            .execute("SELECT nombre AS name, email AS mail FROM clientes WHERE id <= 3 ORDER BY name DESC")
+            .unwrap();
+
+        assert_eq!(
+            result.to_csv(),
+            vec![
+                "name,mail",
+                "Juan,juan.perez@email.com",
+                "Carlos,carlos.gomez@email.com",
+                "Ana,ana.lopez@email.com",
+            ]
+        );
+
+        let result = db
+            .execute("SELECT c.apellido AS familia, COUNT(*) AS ordenes FROM clientes c JOIN ordenes o ON c.id = o.id_cliente GROUP BY c.apellido ORDER BY ordenes DESC")
+            .unwrap();
+
+        assert_eq!(
+            result.to_csv(),
+            vec![
+                "familia,ordenes",
+                "López,3",
+                "Pérez,2",
+                "Rodríguez,2",
+                "Fernández,2",
+                "Gómez,1",
+            ]
+        );
+    }
+
+    #[test]
+    fn errors_with_context() {
+        let db = Database::open("tablas").unwrap();
+
+        assert_eq!(
+            db.execute("SELECT nombre FROM testing LIMIT dos"),
+            Err(SqlError::UnexpectedToken {
+                token: String::from("dos"),
+                offset: Some(33)
+            })
+        );
+        assert_eq!(
+            db.execute("SELEC nombre FROM testing")
+                .unwrap_err()
+                .offset(),
+            Some(0)
+        );
+        assert_eq!(
+            db.execute("SELECT nombre FROM testing WHERE id = 1 ORDER BY edad"),
+            Err(SqlError::ColumnNotFound(String::from("id")))
+        );
+        assert_eq!(
+            db.execute("SELECT * FROM no_existe"),
+            Err(SqlError::TableNotFound(String::from("no_existe")))
+        );
+        // Tables without a schema also reject the columns they don't have.
+        assert_eq!(
+            db.execute("INSERT INTO testing (nombre, color) VALUES ('Ana', 'rojo')"),
+            Err(SqlError::ColumnNotFound(String::from("color")))
+        );
+        assert_eq!(
+            db.execute("UPDATE testing SET color = 'rojo'"),
+            Err(SqlError::ColumnNotFound(String::from("color")))
+        );
+    }
+
+    #[test]
+    fn queries_with_comments_and_negative_numbers() {
+        let db = Database::open("tablas").unwrap();
+
+        let result = db
+            .execute(
+                "SELECT nombre, edad * -1 AS negativa -- sin los menores\n\
+                 FROM testing /* ; */ WHERE edad * -1 < -20 AND apellido <> 'AND';",
+            )
+            .unwrap();
+        assert_eq!(
+            result.to_csv(),
+            vec!["nombre,negativa", "Juan,-30", "Carlos,-40"]
+        );
+        assert_eq!(
+            db.execute("SELECT nombre FROM testing WHERE apellido = 'Pérez"),
+            Err(SqlError::UnexpectedToken {
+                token: String::from("'"),
+                offset: Some(44)
+            })
+        );
+    }
+
+    #[test]
+    fn keywords_and_identifiers_in_any_case() {
+        let db = Database::open("tablas").unwrap();
+
+        let result = db
+            .execute("select upper(nombre) from testing where edad > 20 order by edad Desc limit 1")
+            .unwrap();
+        assert_eq!(result.to_csv(), vec!["UPPER(nombre)", "CARLOS"]);
+        assert_eq!(
+            db.execute("SELECT Nombre FROM testing"),
+            Err(SqlError::ColumnNotFound(String::from("Nombre")))
+        );
+
+        let db = db.with_identifier_case(IdentifierCase::Lower);
+        let result = db
+            .execute("SELECT Nombre FROM Testing WHERE Edad = 18")
+            .unwrap();
+        assert_eq!(result.to_csv(), vec!["nombre", "Ana"]);
+        assert_eq!(
+            db.execute("SELECT \"Nombre\" FROM Testing"),
+            Err(SqlError::ColumnNotFound(String::from("Nombre")))
+        );
+
+        // Folding can change the length of an identifier, but the errors still point to the query as it was written.
+        let query = "SELECT İİİİ FROM clientes LIMIT dos";
+        let error = db.execute(query).unwrap_err();
+        assert_eq!(error.offset(), Some(36));
+        assert_eq!(error.render(query)[2], format!("{}^^^", " ".repeat(32)));
+        let db = db.with_identifier_case(IdentifierCase::Upper);
+        let error = db
+            .execute("SELECT ßßßß FROM clientes LIMIT dos")
+            .unwrap_err();
+        assert_eq!(error.offset(), Some(36));
+    }
+
+    #[test]
+    fn select_with_order_keys() {
+        let db = Database::open("tablas").unwrap();
+        let result = db
+            .execute("SELECT apellido, nombre FROM clientes ORDER BY apellido DESC, 2")
+            .unwrap();
+        assert_eq!(
+            result.to_csv(),
+            vec![
+                "apellido,nombre",
+                "Rodríguez,María",
+                "Pérez,Juan",
+                "López,Ana",
+                "López,José",
+                "Gómez,Carlos",
+                "Fernández,Laura",
+            ]
+        );
+
+        let result = db
+            .execute("SELECT id FROM ordenes WHERE id_cliente < 4 ORDER BY cantidad * -1, id DESC")
+            .unwrap();
+        assert_eq!(result.to_csv(), vec!["id", "102", "104", "103", "101"]);
+
+        assert_eq!(
+            db.execute("SELECT id FROM ordenes ORDER BY 2"),
+            Err(SqlError::InvalidColumn)
+        );
+    }
+
+    #[test]
+    fn select_aggregates_without_group_by() {
+        let db = Database::open("tablas").unwrap();
+        let result = db
+            .execute("SELECT COUNT(*), MAX(cantidad), AVG(cantidad) FROM ordenes")
+            .unwrap();
+
+        assert_eq!(
+            result,
+            QueryResult::Rows {
+                columns: vec![
+                    String::from("COUNT(*)"),
+                    String::from("MAX(cantidad)"),
+                    String::from("AVG(cantidad)"),
+                ],
+                rows: vec![vec![
+                    Value::Integer(10),
+                    Value::Integer(2),
+                    Value::Float(1.3)
+                ]],
+            }
+        );
+
+        assert_eq!(
+            db.execute("SELECT nombre, COUNT(*) FROM testing"),
+            Err(SqlError::InvalidColumn)
+        );
+    }
+
+    #[test]
+    fn select_distinct_with_limit() {
+        let db = Database::open("tablas").unwrap();
+        let result = db
+            .execute("SELECT DISTINCT apellido FROM clientes ORDER BY apellido LIMIT 2 OFFSET 1")
+            .unwrap();
+
+        assert_eq!(
+            result,
+            QueryResult::Rows {
+                columns: vec![String::from("apellido")],
+                rows: vec![
+                    vec![Value::Text(String::from("Gómez"))],
+                    vec![Value::Text(String::from("López"))],
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn select_with_comparison_operators() {
+        let db = Database::open("tablas").unwrap();
+        let result = db
+            .execute(
+                "SELECT id FROM ordenes WHERE producto IN ('Laptop', 'Mouse') AND id BETWEEN 101 AND 105 OR producto LIKE 'Tel%' AND cantidad >= 2",
+            )
+            .unwrap();
+
+        assert_eq!(
+            result,
+            QueryResult::Rows {
+                columns: vec![String::from("id")],
+                rows: vec![
+                    vec![Value::Integer(101)],
+                    vec![Value::Integer(102)],
+                    vec![Value::Integer(105)],
+                    vec![Value::Integer(110)],
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn select_with_expressions() {
+        let db = Database::open("tablas").unwrap();
+        let result = db
+            .execute("SELECT id, cantidad * 2 AS doble, producto || '!' FROM ordenes WHERE (id_cliente + 1) * 2 = cantidad * 4 AND id < 105")
+            .unwrap();
+
+        assert_eq!(
+            result,
+            QueryResult::Rows {
+                columns: vec![
+                    String::from("id"),
+                    String::from("doble"),
+                    String::from("producto || '!'")
+                ],
+                rows: vec![
+                    vec![
+                        Value::Integer(101),
+                        Value::Integer(2),
+                        Value::Text(String::from("Laptop!"))
+                    ],
+                    vec![
+                        Value::Integer(103),
+                        Value::Integer(2),
+                        Value::Text(String::from("Monitor!"))
+                    ],
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn select_with_functions() {
+        let db = Database::open("tablas").unwrap();
+        let result = db
+            .execute("SELECT UPPER(nombre) AS nombre, SUBSTR(email, 1, LENGTH(nombre)), CAST(id AS TEXT) || '!' FROM clientes WHERE LOWER(apellido) = 'lópez'")
+            .unwrap();
+
+        assert_eq!(
+            result.to_csv(),
+            vec![
+                "nombre,\"SUBSTR(email, 1, LENGTH(nombre))\",CAST(id AS TEXT) || '!'",
+                "ANA,ana,2!",
+                "JOSÉ,jose,5!",
+            ]
+        );
+    }
+
+    #[test]
+    fn select_with_registered_function() {
+        register_function("INICIAL", |arguments| match arguments {
+            [Value::Text(text)] => Ok(Value::Text(text.chars().take(1).collect())),
+            _ => Err(SqlError::InvalidType),
+        });
+        let db = Database::open("tablas").unwrap();
+
+        assert_eq!(
+            db.execute("SELECT INICIAL(nombre) FROM clientes WHERE id <= 2")
+                .unwrap(),
+            QueryResult::Rows {
+                columns: vec![String::from("INICIAL(nombre)")],
+                rows: vec![
+                    vec![Value::Text(String::from("J"))],
+                    vec![Value::Text(String::from("A"))],
+                ],
+            }
+        );
+        assert_eq!(
+            db.execute("SELECT INICIAL(id) FROM clientes"),
+            Err(SqlError::InvalidType)
+        );
+    }
+
+    #[test]
+    fn update_with_expression() {
+        let folder = temp_folder("update_expression", &["testing"]);
+        fs::copy("tablas/ordenes.csv", format!("{}/ordenes.csv", folder)).unwrap();
+        let db = Database::open(&folder).unwrap();
+
+        db.execute("UPDATE ordenes SET cantidad = cantidad + 1 WHERE id_cliente = 1")
+            .unwrap();
+        let result = db
+            .execute("SELECT id, cantidad FROM ordenes WHERE id_cliente <= 2")
+            .unwrap();
+
+        assert_eq!(
+            result,
+            QueryResult::Rows {
+                columns: vec![String::from("id"), String::from("cantidad")],
+                rows: vec![
+                    vec![Value::Integer(101), Value::Integer(2)],
+                    vec![Value::Integer(103), Value::Integer(2)],
+                    vec![Value::Integer(102), Value::Integer(2)],
+                ],
+            }
+        );
+        assert_eq!(
+            db.execute("UPDATE ordenes SET cantidad = cantidad / 0"),
+            Err(SqlError::DivisionByZero)
+        );
+        fs::remove_dir_all(&folder).unwrap();
+    }
+
+    #[test]
+    fn quoted_csv_fields() {
+        let folder = temp_folder("csv_fields", &["testing"]);
+        let path = format!("{}/testing.csv", folder);
+        let mut content = fs::read_to_string(&path).unwrap();
+        content.push_str("\"Ana\nMaría\",\"Díaz\",22\r\n");
+        fs::write(&path, content).unwrap();
+        let db = Database::open(&folder).unwrap();
+
+        db.execute("INSERT INTO testing (nombre, apellido, edad) VALUES ('Pérez, Juan', 'O''Brien \"el grande\"', 20)")
+            .unwrap();
+        db.execute("UPDATE testing SET apellido = 'Díaz, hija' WHERE edad = 22")
+            .unwrap();
+        db.execute("DELETE FROM testing WHERE edad < 20").unwrap();
+
+        assert_eq!(
+            db.execute("SELECT nombre, apellido FROM testing WHERE edad < 30")
+                .unwrap(),
+            QueryResult::Rows {
+                columns: vec![String::from("nombre"), String::from("apellido")],
+                rows: vec![
+                    vec![
+                        Value::Text(String::from("Ana\nMaría")),
+                        Value::Text(String::from("Díaz, hija"))
+                    ],
+                    vec![
+                        Value::Text(String::from("Pérez, Juan")),
+                        Value::Text(String::from("O'Brien \"el grande\""))
+                    ],
+                ],
+            }
+        );
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "nombre,apellido,edad\nJuan,Pérez,30\nCarlos,Gómez,40\n\"Ana\nMaría\",\"Díaz, hija\",22\n\"Pérez, Juan\",\"O'Brien \"\"el grande\"\"\",20\n"
+        );
+        fs::remove_dir_all(&folder).unwrap();
+    }
+
+    #[test]
+    fn null_values() {
+        let folder = temp_folder("null_values", &["testing"]);
+        let path = format!("{}/testing.csv", folder);
+        let db = Database::open(&folder).unwrap();
+
+        db.execute("INSERT INTO testing (nombre, edad) VALUES ('Beto', 25)")
+            .unwrap();
+        db.execute("INSERT INTO testing (nombre, apellido) VALUES ('Lía', '')")
+            .unwrap();
+        db.execute("UPDATE testing SET edad = NULL WHERE nombre = 'Ana'")
+            .unwrap();
+        assert!(fs::read_to_string(&path)
+            .unwrap()
+            .ends_with("Ana,López,\nCarlos,Gómez,40\nBeto,,25\nLía,\"\",\n"));
+
+        let names = |query: &str| match db.execute(query).unwrap() {
+            QueryResult::Rows { rows, .. } => rows
+                .into_iter()
+                .map(|row| row[0].to_string())
+                .collect::<Vec<_>>(),
+            other => panic!("{:?}", other),
+        };
+        assert_eq!(
+            names("SELECT nombre FROM testing WHERE apellido IS NULL"),
+            ["Beto"]
+        );
+        assert_eq!(
+            names("SELECT nombre FROM testing WHERE apellido = ''"),
+            ["Lía"]
+        );
+        assert_eq!(
+            names("SELECT nombre FROM testing WHERE NOT edad > 30"),
+            ["Juan", "Beto"]
+        );
+        assert_eq!(
+            names("SELECT nombre FROM testing WHERE edad > 30 OR apellido = 'Ruiz'"),
+            ["Carlos"]
+        );
+        assert_eq!(
+            names("SELECT nombre FROM testing ORDER BY edad DESC NULLS FIRST, nombre"),
+            ["Ana", "Lía", "Carlos", "Juan", "Beto"]
+        );
+        assert_eq!(
+            db.execute("SELECT COUNT(*), COUNT(edad), AVG(edad), MIN(apellido) FROM testing")
+                .unwrap(),
+            QueryResult::Rows {
+                columns: vec![
+                    String::from("COUNT(*)"),
+                    String::from("COUNT(edad)"),
+                    String::from("AVG(edad)"),
+                    String::from("MIN(apellido)"),
+                ],
+                rows: vec![vec![
+                    Value::Integer(5),
+                    Value::Integer(3),
+                    Value::Float(95.0 / 3.0),
+                    Value::Text(String::new()),
+                ]],
+            }
+        );
+        fs::remove_dir_all(&folder).unwrap();
+    }
+
+    #[test]
+    fn null_values_in_single_column_tables() {
+        let folder = temp_folder("single_column_nulls", &[]);
+        let db = Database::open(&folder).unwrap();
+        let count = |table: &str| {
+            db.execute(&format!("SELECT COUNT(*) FROM {}", table))
+                .unwrap()
+                .to_csv()
+        };
+
+        db.execute("CREATE TABLE s (a INT)").unwrap();
+        assert_eq!(
+            db.execute("INSERT INTO s VALUES (NULL), (1), (NULL)"),
+            Ok(QueryResult::Affected(3))
+        );
+        assert_eq!(count("s"), vec!["COUNT(*)", "3"]);
+        assert_eq!(
+            db.execute("SELECT COUNT(a) FROM s").unwrap().to_csv(),
+            vec!["COUNT(a)", "1"]
+        );
+
+        db.execute("CREATE TABLE t (a INT, b INT)").unwrap();
+        db.execute("INSERT INTO t VALUES (1, NULL), (2, NULL)")
+            .unwrap();
+        db.execute("ALTER TABLE t DROP COLUMN a").unwrap();
+        assert_eq!(count("t"), vec!["COUNT(*)", "2"]);
+
+        fs::remove_dir_all(&folder).unwrap();
+    }
+
+    #[test]
+    fn tables_and_columns() {
+        let db = Database::open("tablas").unwrap();
+        let tables = db.tables().unwrap();
+
+        assert!(tables.contains(&String::from("clientes")));
+        assert!(tables.contains(&String::from("ordenes")));
+        assert_eq!(
+            db.columns("ordenes").unwrap(),
+            vec!["id", "id_cliente", "producto", "cantidad"]
+        );
+        assert_eq!(
+            db.columns("no_existe"),
+            Err(SqlError::TableNotFound(String::from("no_existe")))
+        );
+    }
+
+    #[test]
+    fn script_stops_on_error() {
+        let folder = temp_folder("script_stop", &["testing"]);
+        let db = Database::open(&folder).unwrap();
+        let script = "DELETE FROM testing WHERE nombre = 'Ana';\n\
+                      SELECT nombre FROM no_existe;\n\
+                      DELETE FROM testing;";
+
+        let results = db.execute_script(script, false);
+
+        assert_eq!(
+            results,
+            vec![
+                Ok(QueryResult::Affected(1)),
+                Err(SqlError::TableNotFound(String::from("no_existe")))
+            ]
+        );
+        assert_eq!(
+            db.execute("SELECT nombre FROM testing").unwrap().to_csv(),
+            vec!["nombre", "Juan", "Carlos"]
+        );
+
+        fs::remove_dir_all(&folder).unwrap();
+    }
+
+    #[test]
+    fn script_continues_on_error() {
+        let folder = temp_folder("script_continue", &["testing"]);
+        let db = Database::open(&folder).unwrap();
+        let script = "SELECT nombre FROM no_existe; DELETE FROM testing";
+
+        let results = db.execute_script(script, true);
+
+        assert_eq!(
+            results,
+            vec![
+                Err(SqlError::TableNotFound(String::from("no_existe"))),
+                Ok(QueryResult::Affected(3))
+            ]
+        );
+
+        fs::remove_dir_all(&folder).unwrap();
+    }
+
+    #[test]
+    fn create_and_validate_types() {
+        let folder = temp_folder("create", &["testing"]);
+        let db = Database::open(&folder).unwrap();
+
+        db.execute("CREATE TABLE productos (id INT NOT NULL, nombre TEXT DEFAULT 'Sin nombre', precio FLOAT)")
+            .unwrap();
+        assert_eq!(
+            db.columns("productos").unwrap(),
+            vec!["id", "nombre", "precio"]
+        );
+
+        db.execute("INSERT INTO productos (precio, id) VALUES (10, 1)")
+            .unwrap();
+        assert_eq!(
+            db.execute("INSERT INTO productos (id, precio) VALUES (2, caro)"),
+            Err(SqlError::InvalidType)
+        );
+        assert_eq!(
+            db.execute("INSERT INTO productos (nombre) VALUES (Mouse)"),
+            Err(SqlError::InvalidValue)
+        );
+        assert_eq!(
+            db.execute("INSERT INTO productos (id, color) VALUES (2, rojo)"),
+            Err(SqlError::ColumnNotFound(String::from("color")))
+        );
+        assert_eq!(
+            db.execute("UPDATE productos SET id = 'uno'"),
+            Err(SqlError::InvalidType)
+        );
+        db.execute("UPDATE productos SET precio = 12 WHERE id = 1")
+            .unwrap();
+
+        assert_eq!(
+            db.execute("SELECT * FROM productos").unwrap().to_csv(),
+            vec!["id,nombre,precio", "1,Sin nombre,12.0"]
+        );
+
+        fs::remove_dir_all(&folder).unwrap();
+    }
+
+    #[test]
+    fn insert_many_rows_and_select() {
+        let folder = temp_folder("insert_select", &["testing"]);
+        let db = Database::open(&folder).unwrap();
+
+        db.execute(
+            "CREATE TABLE adultos (nombre TEXT NOT NULL, edad INT, activo BOOLEAN DEFAULT TRUE)",
+        )
+        .unwrap();
+        assert_eq!(
+            db.execute("INSERT INTO testing VALUES ('Beto', 'Ruiz', 25), ('Lía', 'Sosa', 17), ('Eva', NULL, 52)"),
+            Ok(QueryResult::Affected(3))
+        );
+        assert_eq!(
+            db.execute("INSERT INTO adultos (edad, nombre) SELECT edad, nombre FROM testing WHERE edad >= 18 ORDER BY edad"),
+            Ok(QueryResult::Affected(5))
+        );
+        assert_eq!(
+            db.execute("SELECT * FROM adultos").unwrap().to_csv(),
+            vec![
+                "nombre,edad,activo",
+                "Ana,18,TRUE",
+                "Beto,25,TRUE",
+                "Juan,30,TRUE",
+                "Carlos,40,TRUE",
+                "Eva,52,TRUE"
+            ]
+        );
+
+        // Every row is validated before any of them is written.
+        assert_eq!(
+            db.execute("INSERT INTO adultos (nombre, edad) VALUES ('Gus', 20), (NULL, 30)"),
+            Err(SqlError::InvalidValue)
+        );
+        assert_eq!(
+            db.execute("INSERT INTO adultos (nombre) VALUES ('Gus'), ('Hugo', 20)"),
+            Err(SqlError::InvalidSyntax)
+        );
+        assert_eq!(
+            db.execute("INSERT INTO adultos (nombre) SELECT nombre, edad FROM testing"),
+            Err(SqlError::InvalidSyntax)
+        );
+        assert_eq!(
+            db.execute("INSERT INTO adultos SELECT * FROM adultos WHERE edad > 40"),
+            Ok(QueryResult::Affected(1))
+        );
+        assert_eq!(
+            db.execute("SELECT COUNT(*) FROM adultos").unwrap().to_csv(),
+            vec!["COUNT(*)", "6"]
+        );
+
+        fs::remove_dir_all(&folder).unwrap();
+    }
+
+    #[test]
+    fn alter_truncate_and_drop() {
+        let folder = temp_folder("ddl", &["testing"]);
+        let db = Database::open(&folder).unwrap();
+
+        db.execute("ALTER TABLE testing RENAME TO personas")
+            .unwrap();
+        db.execute("ALTER TABLE personas DROP COLUMN apellido")
+            .unwrap();
+        assert_eq!(db.columns("personas").unwrap(), vec!["nombre", "edad"]);
+
+        assert_eq!(
+            db.execute("TRUNCATE TABLE personas"),
+            Ok(QueryResult::Affected(3))
+        );
+        assert_eq!(
+            db.execute("SELECT * FROM personas").unwrap().to_csv(),
+            vec!["nombre,edad"]
+        );
+
+        db.execute("DROP TABLE personas").unwrap();
+        assert_eq!(
+            db.execute("DROP TABLE personas"),
+            Err(SqlError::TableNotFound(String::from("personas")))
+        );
+        assert_eq!(
+            db.execute("DROP TABLE IF EXISTS personas"),
+            Ok(QueryResult::Affected(0))
+        );
+        assert!(db.tables().unwrap().is_empty());
+
+        fs::remove_dir_all(&folder).unwrap();
+    }
+
+    #[test]
+    fn unknown_clause() {
+        let db = Database::open("tablas").unwrap();
+        assert_eq!(
+            db.execute("DROP testing"),
+            Err(SqlError::UnexpectedToken {
+                token: String::from("testing"),
+                offset: Some(5)
+            })
+        );
+        assert_eq!(
+            db.execute("MERGE INTO testing"),
+            Err(SqlError::UnexpectedToken {
+                token: String::from("MERGE"),
+                offset: Some(0)
+            })
+        );
+    }
+
+    #[test]
+    fn insert_update_delete_affected() {
+        let folder = temp_folder("affected", &["testing"]);
+        let db = Database::open(&folder).unwrap();
+
+        let inserted = db
+            .execute("INSERT INTO testing (nombre, apellido, edad) VALUES ('Alen', 'Davies', 25)")
+            .unwrap();
+        assert_eq!(inserted, QueryResult::Affected(1));
+
+        let updated = db
+            .execute("UPDATE testing SET apellido = 'D' WHERE nombre = 'Alen' OR nombre = 'Ana'")
+            .unwrap();
+        assert_eq!(updated, QueryResult::Affected(2));
+
+        let deleted = db
+            .execute("DELETE FROM testing WHERE apellido = 'D'")
+            .unwrap();
+        assert_eq!(deleted, QueryResult::Affected(2));
+
+        let inserted = db
+            .execute("INSERT INTO testing VALUES ('Beto', 'Ruiz', 20 + 5)")
+            .unwrap();
+        assert_eq!(inserted, QueryResult::Affected(1));
+        assert_eq!(
+            db.execute("INSERT INTO testing VALUES ('Beto', 'Ruiz')"),
+            Err(SqlError::InvalidSyntax)
+        );
+
+        let deleted = db.execute("DELETE FROM testing WHERE edad = 25").unwrap();
+        assert_eq!(deleted, QueryResult::Affected(1));
+
+        let deleted = db.execute("DELETE FROM testing").unwrap();
+        assert_eq!(deleted, QueryResult::Affected(2));
+
+        fs::remove_dir_all(&folder).unwrap();
+    }
+}
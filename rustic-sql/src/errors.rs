@@ -1,4 +1,5 @@
-use std::fmt::Display;
+use crate::lexer::tokenize;
+use std::{fmt::Display, io};
 
 /// Enum representing the possible errors that can occur when processing SQL queries.
 ///
@@ -7,14 +8,210 @@ use std::fmt::Display;
 /// - `InvalidTable`: related to problems with the processing of tables.
 /// - `InvalidColumn`: related to problems with the processing of columns.
 /// - `InvalidSyntax`: related to problems with the processing of queries.
+/// - `InvalidType`: a value doesn't match the declared type of its column.
+/// - `InvalidValue`: a value breaks a constraint of its column, such as `NOT NULL`.
 /// - `Error`: generic type for other possible errors detected.
+/// - `UnexpectedToken`: a token of the query can't be processed. The offset is the byte where the token starts in the query, once it is known. An empty token means that the query ended too soon.
+/// - `TableNotFound`: the table with the given name doesn't exist.
+/// - `ColumnNotFound`: the column with the given name doesn't exist.
+/// - `MalformedRecord`: the record of a table file that starts in the given line, counting from 1, can't be read.
+/// - `Io`: an error reading or writing the files of the database.
+/// - `TableLocked`: the table with the given name is locked by another process, which didn't release it before the wait timeout.
+/// - `InvalidTransaction`: a statement can't run in the current state of the transaction, such as `COMMIT` without a `BEGIN`.
+/// - `FolderNotFound`: the folder of the database with the given path doesn't exist.
+/// - `DivisionByZero`: a number is divided by zero, or its remainder by zero is taken.
+/// - `Overflow`: the result of an operation on integers doesn't fit in an integer.
+/// - `TypeMismatch`: an operation can't be applied to values of the given types, such as adding a text to a number.
 ///
-#[derive(Debug, PartialEq)]
+#[derive(Debug)]
 pub enum SqlError {
     InvalidTable,
     InvalidColumn,
     InvalidSyntax,
+    InvalidType,
+    InvalidValue,
     Error,
+    UnexpectedToken {
+        token: String,
+        offset: Option<usize>,
+    },
+    TableNotFound(String),
+    ColumnNotFound(String),
+    MalformedRecord {
+        line: usize,
+    },
+    Io(io::Error),
+    TableLocked(String),
+    InvalidTransaction(String),
+    FolderNotFound(String),
+    DivisionByZero,
+    Overflow,
+    TypeMismatch {
+        left: String,
+        right: String,
+    },
+}
+
+impl SqlError {
+    /// Creates an `UnexpectedToken` error for a token whose position in the query is not known yet.
+    pub fn unexpected(token: &str) -> Self {
+        SqlError::UnexpectedToken {
+            token: token.to_string(),
+            offset: None,
+        }
+    }
+
+    /// Returns the byte offset in the query of the problem, if the error has one.
+    pub fn offset(&self) -> Option<usize> {
+        match self {
+            SqlError::UnexpectedToken { offset, .. } => *offset,
+            _ => None,
+        }
+    }
+
+    /// Returns the same error, with the offset of its token searched in the query if it didn't have one.
+    ///
+    /// The token is searched among the tokens of the query, so `id` is not found inside `apellido` or inside a string literal.
+    /// A token that the lexer doesn't produce, like the text between parentheses, is searched as a whole word.
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - The query where the error happened.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use rustic_sql::SqlError;
+    /// let error = SqlError::unexpected("FORM").locate("SELECT * FORM personas");
+    /// assert_eq!(error.offset(), Some(9));
+    /// ```
+    ///
+    pub fn locate(self, query: &str) -> Self {
+        match self {
+            SqlError::UnexpectedToken {
+                token,
+                offset: None,
+            } => {
+                let offset = tokenize(query)
+                    .ok()
+                    .and_then(|tokens| {
+                        let found = tokens
+                            .into_iter()
+                            .find(|t| t.source(query).eq_ignore_ascii_case(&token));
+                        found.map(|t| t.span.start)
+                    })
+                    .or_else(|| find_word(query, &token));
+                SqlError::UnexpectedToken { token, offset }
+            }
+            error => error,
+        }
+    }
+
+    /// Returns the lines that describe the error: the message and, if the error has an offset, the query with a caret under the problem.
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - The query where the error happened.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use rustic_sql::SqlError;
+    /// let query = "SELECT * FORM personas";
+    /// let error = SqlError::unexpected("FORM").locate(query);
+    /// assert_eq!(error.render(query), vec![
+    ///     "[InvalidSyntax]: [Unexpected token 'FORM' at byte 9]",
+    ///     "SELECT * FORM personas",
+    ///     "         ^^^^",
+    /// ]);
+    /// ```
+    ///
+    pub fn render(&self, query: &str) -> Vec<String> {
+        let mut lines = vec![self.to_string()];
+
+        if let (Some(offset), SqlError::UnexpectedToken { token, .. }) = (self.offset(), self) {
+            // An offset that doesn't fall between two characters of the query, like one of another query, can't be shown.
+            if !query.is_char_boundary(offset) {
+                return lines;
+            }
+            // Only the line of the query that has the problem is shown.
+            let start = query[..offset].rfind('\n').map_or(0, |idx| idx + 1);
+            let end = query[offset..]
+                .find('\n')
+                .map_or(query.len(), |idx| offset + idx);
+            let width = query[start..offset].chars().count();
+            let length = token.chars().count().max(1);
+
+            lines.push(query[start..end].trim_end().to_string());
+            lines.push(format!("{}{}", " ".repeat(width), "^".repeat(length)));
+        }
+        lines
+    }
+}
+
+/// Returns the byte offset of the first appearance of the word in the text.
+fn find_word(text: &str, word: &str) -> Option<usize> {
+    if word.is_empty() {
+        return None;
+    }
+    let is_word = |char: char| char.is_alphanumeric() || char == '_';
+
+    text.match_indices(word).map(|(idx, _)| idx).find(|idx| {
+        let before = text[..*idx].chars().next_back();
+        let after = text[idx + word.len()..].chars().next();
+        let starts = word.starts_with(is_word);
+        let ends = word.ends_with(is_word);
+        let joined_before = starts && before.is_some_and(is_word);
+        let joined_after = ends && after.is_some_and(is_word);
+        !joined_before && !joined_after
+    })
+}
+
+impl PartialEq for SqlError {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (
+                SqlError::UnexpectedToken { token, offset },
+                SqlError::UnexpectedToken {
+                    token: other_token,
+                    offset: other_offset,
+                },
+            ) => token == other_token && offset == other_offset,
+            (SqlError::TableNotFound(a), SqlError::TableNotFound(b)) => a == b,
+            (SqlError::ColumnNotFound(a), SqlError::ColumnNotFound(b)) => a == b,
+            (SqlError::TableLocked(a), SqlError::TableLocked(b)) => a == b,
+            (SqlError::InvalidTransaction(a), SqlError::InvalidTransaction(b)) => a == b,
+            (SqlError::FolderNotFound(a), SqlError::FolderNotFound(b)) => a == b,
+            (
+                SqlError::TypeMismatch { left, right },
+                SqlError::TypeMismatch {
+                    left: other_left,
+                    right: other_right,
+                },
+            ) => left == other_left && right == other_right,
+            (SqlError::MalformedRecord { line }, SqlError::MalformedRecord { line: other }) => {
+                line == other
+            }
+            // The io errors can't be compared, so only their kinds are.
+            (SqlError::Io(a), SqlError::Io(b)) => a.kind() == b.kind(),
+            _ => std::mem::discriminant(self) == std::mem::discriminant(other),
+        }
+    }
+}
+
+impl From<io::Error> for SqlError {
+    fn from(error: io::Error) -> Self {
+        SqlError::Io(error)
+    }
+}
+
+impl std::error::Error for SqlError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SqlError::Io(error) => Some(error),
+            _ => None,
+        }
+    }
 }
 
 impl Display for SqlError {
@@ -23,7 +220,130 @@ impl Display for SqlError {
             SqlError::InvalidTable => write!(f, "[InvalidTable]: [Error to process table]"),
             SqlError::InvalidColumn => write!(f, "[InvalidColumn]: [Error to process column]"),
             SqlError::InvalidSyntax => write!(f, "[InvalidSyntax]: [Error to process query]"),
+            SqlError::InvalidType => {
+                write!(f, "[InvalidType]: [Value does not match the column type]")
+            }
+            SqlError::InvalidValue => {
+                write!(f, "[InvalidValue]: [Value breaks a column constraint]")
+            }
             SqlError::Error => write!(f, "[Error]: [An error occurred]"),
+            SqlError::UnexpectedToken { token, offset } if token.is_empty() => match offset {
+                Some(offset) => write!(
+                    f,
+                    "[InvalidSyntax]: [Unexpected end of query at byte {}]",
+                    offset
+                ),
+                None => write!(f, "[InvalidSyntax]: [Unexpected end of query]"),
+            },
+            SqlError::UnexpectedToken {
+                token,
+                offset: Some(offset),
+            } => write!(
+                f,
+                "[InvalidSyntax]: [Unexpected token '{}' at byte {}]",
+                token, offset
+            ),
+            SqlError::UnexpectedToken {
+                token,
+                offset: None,
+            } => {
+                write!(f, "[InvalidSyntax]: [Unexpected token '{}']", token)
+            }
+            SqlError::TableNotFound(name) => {
+                write!(f, "[InvalidTable]: [Table '{}' does not exist]", name)
+            }
+            SqlError::ColumnNotFound(name) => {
+                write!(f, "[InvalidColumn]: [Column '{}' does not exist]", name)
+            }
+            SqlError::MalformedRecord { line } => {
+                write!(f, "[InvalidTable]: [Malformed record at line {}]", line)
+            }
+            SqlError::Io(error) => write!(f, "[Error]: [{}]", error),
+            SqlError::TableLocked(name) => {
+                write!(f, "[TableLocked]: [Table '{}' is locked]", name)
+            }
+            SqlError::InvalidTransaction(message) => {
+                write!(f, "[InvalidTransaction]: [{}]", message)
+            }
+            SqlError::FolderNotFound(path) => {
+                write!(f, "[InvalidTable]: [Folder '{}' does not exist]", path)
+            }
+            SqlError::DivisionByZero => write!(f, "[DivisionByZero]: [Division by zero]"),
+            SqlError::Overflow => write!(f, "[Overflow]: [Integer out of range]"),
+            SqlError::TypeMismatch { left, right } => write!(
+                f,
+                "[TypeMismatch]: [Can't operate on {} and {}]",
+                left, right
+            ),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::SqlError;
+    use std::io;
+
+    #[test]
+    fn locate_and_render() {
+        let query = "SELECT id, apellido\nFROM personas\nWHERE id ?? 3";
+        let error = SqlError::unexpected("??").locate(query);
+
+        assert_eq!(error.offset(), Some(43));
+        assert_eq!(
+            error.render(query),
+            vec![
+                "[InvalidSyntax]: [Unexpected token '??' at byte 43]",
+                "WHERE id ?? 3",
+                "         ^^",
+            ]
+        );
+
+        let error = SqlError::unexpected("id").locate("SELECT apellido, id");
+        assert_eq!(error.offset(), Some(17));
+        assert_eq!(SqlError::unexpected("x").locate("SELECT 1").offset(), None);
+    }
+
+    #[test]
+    fn messages_with_context() {
+        assert_eq!(
+            SqlError::TableNotFound(String::from("clientes")).to_string(),
+            "[InvalidTable]: [Table 'clientes' does not exist]"
+        );
+        assert_eq!(
+            SqlError::TableLocked(String::from("clientes")).to_string(),
+            "[TableLocked]: [Table 'clientes' is locked]"
+        );
+        assert_eq!(
+            SqlError::InvalidTransaction(String::from("No transaction is active")).to_string(),
+            "[InvalidTransaction]: [No transaction is active]"
+        );
+        assert_eq!(
+            SqlError::FolderNotFound(String::from("datos")).to_string(),
+            "[InvalidTable]: [Folder 'datos' does not exist]"
+        );
+        assert_eq!(
+            SqlError::TypeMismatch {
+                left: String::from("TEXT"),
+                right: String::from("INTEGER")
+            }
+            .to_string(),
+            "[TypeMismatch]: [Can't operate on TEXT and INTEGER]"
+        );
+        let error = SqlError::UnexpectedToken {
+            token: String::from("dos"),
+            offset: Some(8),
+        };
+        assert_eq!(error.render("SELECT İİ"), vec![error.to_string()]);
+        assert_eq!(error.render("SELECT"), vec![error.to_string()]);
+        assert_eq!(
+            SqlError::MalformedRecord { line: 3 }.render("SELECT * FROM personas"),
+            vec!["[InvalidTable]: [Malformed record at line 3]"]
+        );
+
+        let error = SqlError::from(io::Error::new(io::ErrorKind::NotFound, "no such file"));
+        assert_eq!(error.to_string(), "[Error]: [no such file]");
+        assert_eq!(error, SqlError::Io(io::ErrorKind::NotFound.into()));
+        assert_ne!(error, SqlError::Error);
+    }
+}
@@ -0,0 +1,355 @@
+use crate::{errors::SqlError, schema::ColumnType, value::Value};
+use std::{
+    cmp::Ordering,
+    collections::HashMap,
+    sync::{Arc, OnceLock, RwLock},
+};
+
+/// A scalar function that can be called in queries, like `UPPER(nombre)`.
+///
+/// It receives the values of its arguments and returns a single value.
+///
+pub type ScalarFunction = Arc<dyn Fn(&[Value]) -> Result<Value, SqlError> + Send + Sync>;
+
+type Builtin = fn(&[Value]) -> Result<Value, SqlError>;
+
+static REGISTRY: OnceLock<RwLock<HashMap<String, ScalarFunction>>> = OnceLock::new();
+
+fn registry() -> &'static RwLock<HashMap<String, ScalarFunction>> {
+    REGISTRY.get_or_init(|| {
+        let builtins: [(&str, Builtin); 10] = [
+            ("UPPER", upper),
+            ("LOWER", lower),
+            ("LENGTH", length),
+            ("SUBSTR", substr),
+            ("TRIM", trim),
+            ("REPLACE", replace),
+            ("ABS", abs),
+            ("ROUND", round),
+            ("COALESCE", coalesce),
+            ("NULLIF", nullif),
+        ];
+        let functions = builtins
+            .into_iter()
+            .map(|(name, function)| (name.to_string(), Arc::new(function) as ScalarFunction))
+            .collect();
+        RwLock::new(functions)
+    })
+}
+
+/// Registers a scalar function, so it can be called in any query by its name in uppercase.
+///
+/// The functions are shared by every `Database` of the program. Registering a function with the name of an existing one replaces it.
+///
+/// # Arguments
+///
+/// * `name` - The name of the function.
+/// * `function` - The closure that computes the result from the values of the arguments.
+///
+/// # Examples
+///
+/// ```
+/// # use rustic_sql::Database;
+/// # use rustic_sql::SqlError;
+/// # use rustic_sql::Value;
+/// # use rustic_sql::functions::register_function;
+/// register_function("DOUBLE", |arguments| match arguments {
+///     [Value::Integer(integer)] => Ok(Value::Integer(integer * 2)),
+///     _ => Err(SqlError::InvalidType),
+/// });
+///
+/// let db = Database::open("tablas").unwrap();
+/// db.execute("SELECT DOUBLE(cantidad) FROM ordenes").unwrap();
+/// ```
+///
+pub fn register_function<F>(name: &str, function: F)
+where
+    F: Fn(&[Value]) -> Result<Value, SqlError> + Send + Sync + 'static,
+{
+    let mut functions = registry().write().unwrap_or_else(|e| e.into_inner());
+    functions.insert(name.to_uppercase(), Arc::new(function));
+}
+
+/// Returns the function registered with the given name, or `None` if there isn't one.
+pub fn get_function(name: &str) -> Option<ScalarFunction> {
+    let functions = registry().read().unwrap_or_else(|e| e.into_inner());
+    functions.get(name).cloned()
+}
+
+/// Returns true if there is a function registered with the given name.
+pub fn is_function(name: &str) -> bool {
+    get_function(name).is_some()
+}
+
+/// Converts a value to a type, as done by `CAST(value AS type)`.
+///
+/// Unlike `ColumnType::coerce`, texts are parsed and numbers are converted between them, truncating floats.
+///
+/// Returns `SqlError::InvalidType` if the value can't be converted.
+///
+/// # Examples
+///
+/// ```
+/// # use rustic_sql::Value;
+/// # use rustic_sql::functions::cast;
+/// # use rustic_sql::schema::ColumnType;
+/// assert_eq!(cast(Value::Text("12".to_string()), &ColumnType::Integer), Ok(Value::Integer(12)));
+/// assert_eq!(cast(Value::Float(2.7), &ColumnType::Integer), Ok(Value::Integer(2)));
+/// ```
+///
+pub fn cast(value: Value, column_type: &ColumnType) -> Result<Value, SqlError> {
+    match (column_type, value) {
+        (_, Value::Null) => Ok(Value::Null),
+        (ColumnType::Integer, Value::Float(float)) => Ok(Value::Integer(float.trunc() as i64)),
+        (ColumnType::Integer, Value::Boolean(boolean)) => Ok(Value::Integer(boolean as i64)),
+        (ColumnType::Float, Value::Boolean(boolean)) => Ok(Value::Float(boolean as i64 as f64)),
+        (ColumnType::Boolean, Value::Integer(integer)) => Ok(Value::Boolean(integer != 0)),
+        (ColumnType::Integer | ColumnType::Float | ColumnType::Boolean, Value::Text(text)) => {
+            match Value::from_literal(text.trim()) {
+                Value::Text(_) | Value::Null => Err(SqlError::InvalidType),
+                value => cast(value, column_type),
+            }
+        }
+        (column_type, value) => column_type.coerce(value),
+    }
+}
+
+fn expect_arguments(arguments: &[Value], min: usize, max: usize) -> Result<(), SqlError> {
+    if arguments.len() < min || arguments.len() > max {
+        return Err(SqlError::InvalidSyntax);
+    }
+    Ok(())
+}
+
+fn integer_argument(value: &Value) -> Result<i64, SqlError> {
+    match value {
+        Value::Integer(integer) => Ok(*integer),
+        _ => Err(SqlError::InvalidType),
+    }
+}
+
+/// Applies a function to the text of the first argument, propagating `Null`.
+fn map_text(arguments: &[Value], function: impl Fn(&str) -> Value) -> Result<Value, SqlError> {
+    expect_arguments(arguments, 1, 1)?;
+    match &arguments[0] {
+        Value::Null => Ok(Value::Null),
+        value => Ok(function(&value.to_string())),
+    }
+}
+
+fn upper(arguments: &[Value]) -> Result<Value, SqlError> {
+    map_text(arguments, |text| Value::Text(text.to_uppercase()))
+}
+
+fn lower(arguments: &[Value]) -> Result<Value, SqlError> {
+    map_text(arguments, |text| Value::Text(text.to_lowercase()))
+}
+
+fn length(arguments: &[Value]) -> Result<Value, SqlError> {
+    map_text(
+        arguments,
+        |text| Value::Integer(text.chars().count() as i64),
+    )
+}
+
+fn trim(arguments: &[Value]) -> Result<Value, SqlError> {
+    map_text(arguments, |text| Value::Text(text.trim().to_string()))
+}
+
+/// `SUBSTR(text, start[, length])`, where `start` counts the characters from 1.
+fn substr(arguments: &[Value]) -> Result<Value, SqlError> {
+    expect_arguments(arguments, 2, 3)?;
+    if arguments.iter().any(|a| a.is_null()) {
+        return Ok(Value::Null);
+    }
+    let start = integer_argument(&arguments[1])?.max(1) as usize - 1;
+    let chars = arguments[0]
+        .to_string()
+        .chars()
+        .skip(start)
+        .collect::<Vec<_>>();
+    let length = match arguments.get(2) {
+        Some(length) => integer_argument(length)?.max(0) as usize,
+        None => chars.len(),
+    };
+
+    Ok(Value::Text(chars.into_iter().take(length).collect()))
+}
+
+/// `REPLACE(text, from, to)`, which replaces every occurrence of `from`.
+fn replace(arguments: &[Value]) -> Result<Value, SqlError> {
+    expect_arguments(arguments, 3, 3)?;
+    if arguments.iter().any(|a| a.is_null()) {
+        return Ok(Value::Null);
+    }
+    let from = arguments[1].to_string();
+    if from.is_empty() {
+        return Ok(Value::Text(arguments[0].to_string()));
+    }
+
+    Ok(Value::Text(
+        arguments[0]
+            .to_string()
+            .replace(&from, &arguments[2].to_string()),
+    ))
+}
+
+fn abs(arguments: &[Value]) -> Result<Value, SqlError> {
+    expect_arguments(arguments, 1, 1)?;
+    match &arguments[0] {
+        Value::Null => Ok(Value::Null),
+        Value::Integer(integer) => integer
+            .checked_abs()
+            .map(Value::Integer)
+            .ok_or(SqlError::Overflow),
+        Value::Float(float) => Ok(Value::Float(float.abs())),
+        _ => Err(SqlError::InvalidType),
+    }
+}
+
+/// `ROUND(number[, digits])`, which rounds to `digits` decimals, or to a whole number by default.
+fn round(arguments: &[Value]) -> Result<Value, SqlError> {
+    expect_arguments(arguments, 1, 2)?;
+    let digits = match arguments.get(1) {
+        Some(Value::Null) => return Ok(Value::Null),
+        Some(digits) => integer_argument(digits)?,
+        None => 0,
+    };
+    match &arguments[0] {
+        Value::Null => Ok(Value::Null),
+        Value::Integer(integer) => Ok(Value::Integer(*integer)),
+        Value::Float(float) => {
+            let factor = 10f64.powi(digits.clamp(-15, 15) as i32);
+            Ok(Value::Float((float * factor).round() / factor))
+        }
+        _ => Err(SqlError::InvalidType),
+    }
+}
+
+/// `COALESCE(a, b, ...)`, which returns the first argument that is not `Null`.
+fn coalesce(arguments: &[Value]) -> Result<Value, SqlError> {
+    expect_arguments(arguments, 1, usize::MAX)?;
+    Ok(arguments
+        .iter()
+        .find(|a| !a.is_null())
+        .cloned()
+        .unwrap_or(Value::Null))
+}
+
+/// `NULLIF(a, b)`, which returns `Null` if both arguments are equal, or `a` otherwise.
+fn nullif(arguments: &[Value]) -> Result<Value, SqlError> {
+    expect_arguments(arguments, 2, 2)?;
+    if arguments[0].compare(&arguments[1]) == Some(Ordering::Equal) {
+        Ok(Value::Null)
+    } else {
+        Ok(arguments[0].clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{cast, get_function, register_function};
+    use crate::{errors::SqlError, schema::ColumnType, value::Value};
+
+    fn call(name: &str, arguments: &[Value]) -> Result<Value, SqlError> {
+        get_function(name).unwrap()(arguments)
+    }
+
+    fn text(text: &str) -> Value {
+        Value::Text(text.to_string())
+    }
+
+    #[test]
+    fn text_functions() {
+        assert_eq!(call("UPPER", &[text("Ana")]), Ok(text("ANA")));
+        assert_eq!(call("LOWER", &[text("Ana")]), Ok(text("ana")));
+        assert_eq!(call("LENGTH", &[text("María")]), Ok(Value::Integer(5)));
+        assert_eq!(call("TRIM", &[text("  a b ")]), Ok(text("a b")));
+        assert_eq!(
+            call(
+                "SUBSTR",
+                &[text("Teléfono"), Value::Integer(2), Value::Integer(3)]
+            ),
+            Ok(text("elé"))
+        );
+        assert_eq!(
+            call("SUBSTR", &[text("Teléfono"), Value::Integer(5)]),
+            Ok(text("fono"))
+        );
+        assert_eq!(
+            call("REPLACE", &[text("a.b.c"), text("."), text("-")]),
+            Ok(text("a-b-c"))
+        );
+        assert_eq!(call("UPPER", &[Value::Null]), Ok(Value::Null));
+        assert_eq!(call("UPPER", &[]), Err(SqlError::InvalidSyntax));
+    }
+
+    #[test]
+    fn numeric_functions() {
+        assert_eq!(call("ABS", &[Value::Integer(-3)]), Ok(Value::Integer(3)));
+        assert_eq!(call("ABS", &[Value::Float(-1.5)]), Ok(Value::Float(1.5)));
+        assert_eq!(call("ABS", &[text("a")]), Err(SqlError::InvalidType));
+        assert_eq!(call("ROUND", &[Value::Float(2.5)]), Ok(Value::Float(3.0)));
+        assert_eq!(
+            call("ROUND", &[Value::Float(12.3456), Value::Integer(2)]),
+            Ok(Value::Float(12.35))
+        );
+    }
+
+    #[test]
+    fn null_functions() {
+        assert_eq!(
+            call(
+                "COALESCE",
+                &[Value::Null, Value::Integer(2), Value::Integer(3)]
+            ),
+            Ok(Value::Integer(2))
+        );
+        assert_eq!(call("COALESCE", &[Value::Null]), Ok(Value::Null));
+        assert_eq!(
+            call("NULLIF", &[Value::Integer(2), Value::Float(2.0)]),
+            Ok(Value::Null)
+        );
+        assert_eq!(
+            call("NULLIF", &[Value::Integer(2), Value::Integer(3)]),
+            Ok(Value::Integer(2))
+        );
+    }
+
+    #[test]
+    fn cast_values() {
+        assert_eq!(
+            cast(text(" 12 "), &ColumnType::Integer),
+            Ok(Value::Integer(12))
+        );
+        assert_eq!(
+            cast(text("2.5"), &ColumnType::Integer),
+            Ok(Value::Integer(2))
+        );
+        assert_eq!(
+            cast(Value::Integer(2), &ColumnType::Float),
+            Ok(Value::Float(2.0))
+        );
+        assert_eq!(cast(Value::Float(2.5), &ColumnType::Text), Ok(text("2.5")));
+        assert_eq!(
+            cast(text("true"), &ColumnType::Boolean),
+            Ok(Value::Boolean(true))
+        );
+        assert_eq!(
+            cast(text("a"), &ColumnType::Integer),
+            Err(SqlError::InvalidType)
+        );
+        assert_eq!(cast(Value::Null, &ColumnType::Integer), Ok(Value::Null));
+    }
+
+    #[test]
+    fn register_custom_function() {
+        register_function("triple", |arguments| match arguments {
+            [Value::Integer(integer)] => Ok(Value::Integer(integer * 3)),
+            _ => Err(SqlError::InvalidType),
+        });
+
+        assert_eq!(call("TRIPLE", &[Value::Integer(2)]), Ok(Value::Integer(6)));
+        assert!(get_function("triple").is_none());
+    }
+}
@@ -0,0 +1,581 @@
+use crate::errors::SqlError;
+use std::{fmt::Display, iter::Peekable, str::CharIndices};
+
+/// The words that the lexer reads as keywords instead of identifiers.
+const KEYWORDS: &[&str] = &[
+    "ADD",
+    "ALTER",
+    "AND",
+    "AS",
+    "ASC",
+    "BEGIN",
+    "BETWEEN",
+    "BY",
+    "COLUMN",
+    "COMMIT",
+    "CREATE",
+    "CROSS",
+    "DEFAULT",
+    "DELETE",
+    "DESC",
+    "DISTINCT",
+    "DROP",
+    "EXISTS",
+    "FALSE",
+    "FIRST",
+    "FROM",
+    "GROUP",
+    "HAVING",
+    "IF",
+    "IN",
+    "INNER",
+    "INSERT",
+    "INTO",
+    "IS",
+    "JOIN",
+    "LAST",
+    "LEFT",
+    "LIKE",
+    "LIMIT",
+    "NOT",
+    "NULL",
+    "NULLS",
+    "OFFSET",
+    "ON",
+    "OR",
+    "ORDER",
+    "OUTER",
+    "RENAME",
+    "ROLLBACK",
+    "SELECT",
+    "SET",
+    "TABLE",
+    "TO",
+    "TRANSACTION",
+    "TRUE",
+    "TRUNCATE",
+    "UPDATE",
+    "VALUES",
+    "WHERE",
+];
+
+/// Enum for what is done with the case of the identifiers that are not written between double quotes.
+///
+/// - `Preserve`: they are kept as written.
+/// - `Lower`: they are converted to lowercase, so `Clientes` and `clientes` are the same table.
+/// - `Upper`: they are converted to uppercase.
+///
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum IdentifierCase {
+    #[default]
+    Preserve,
+    Lower,
+    Upper,
+}
+
+impl IdentifierCase {
+    /// Creates an `IdentifierCase` from its name, like `lower`, ignoring case.
+    pub fn new_from_token(token: &str) -> Option<Self> {
+        match token.to_lowercase().as_str() {
+            "preserve" => Some(IdentifierCase::Preserve),
+            "lower" => Some(IdentifierCase::Lower),
+            "upper" => Some(IdentifierCase::Upper),
+            _ => None,
+        }
+    }
+
+    fn fold(&self, identifier: &str) -> String {
+        match self {
+            IdentifierCase::Preserve => identifier.to_string(),
+            IdentifierCase::Lower => identifier.to_lowercase(),
+            IdentifierCase::Upper => identifier.to_uppercase(),
+        }
+    }
+}
+
+/// The operators made of two characters, which are read before the ones made of one.
+const LONG_OPERATORS: &[&str] = &["<=", ">=", "<>", "!=", "||"];
+
+/// Enum for the kinds of tokens of a query.
+///
+/// - `Keyword`: a reserved word, like `SELECT` or `WHERE`, written in any case.
+/// - `Identifier`: the name of a table, a column or a function. It can be qualified, like `c.id`, or written between double quotes, like `"fecha de alta"`.
+/// - `StringLiteral`: a text between single quotes.
+/// - `NumberLiteral`: an integer or decimal number, which can be negative.
+/// - `Operator`: an arithmetic or comparison operator, like `+`, `<=` or `||`.
+/// - `Punctuation`: a parenthesis, a comma, a semicolon or a dot.
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TokenKind {
+    Keyword,
+    Identifier,
+    StringLiteral,
+    NumberLiteral,
+    Operator,
+    Punctuation,
+}
+
+/// The position of a token in the query, as the byte offsets where it starts and ends.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Struct that represents a token of a query.
+///
+/// # Fields
+///
+/// * `kind` - The kind of the token.
+/// * `text` - The value of the token: the unescaped text for string literals and quoted identifiers, and the text as written for the rest.
+/// * `span` - The position of the token in the query, including its quotes.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub text: String,
+    pub span: Span,
+}
+
+impl Token {
+    /// Returns true if the token is the given keyword, ignoring case.
+    pub fn is_keyword(&self, keyword: &str) -> bool {
+        self.kind == TokenKind::Keyword && self.text.eq_ignore_ascii_case(keyword)
+    }
+
+    /// Returns true if the token is the given punctuation character.
+    pub fn is_punctuation(&self, char: char) -> bool {
+        self.kind == TokenKind::Punctuation && self.text.starts_with(char)
+    }
+
+    /// Returns the token as it is written in the query.
+    pub fn source<'a>(&self, query: &'a str) -> &'a str {
+        &query[self.span.start..self.span.end]
+    }
+}
+
+impl Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.kind {
+            TokenKind::StringLiteral => write!(f, "'{}'", self.text.replace('\'', "''")),
+            TokenKind::Identifier => write!(f, "{}", format_identifier(&self.text)),
+            _ => write!(f, "{}", self.text),
+        }
+    }
+}
+
+/// Returns the `SqlError::UnexpectedToken` for the token at `pos`, with its offset, or for the end of the tokens if there are no more.
+///
+/// The token is written back as in the query, like its `Display` does.
+///
+/// # Arguments
+///
+/// * `tokens` - The tokens read by `tokenize`.
+/// * `pos` - The position of the unexpected token.
+///
+/// # Examples
+///
+/// ```
+/// # use rustic_sql::SqlError;
+/// # use rustic_sql::lexer;
+/// let tokens = lexer::tokenize("WHERE id = 'x' 3").unwrap();
+///
+/// assert_eq!(lexer::unexpected(&tokens, 4), SqlError::UnexpectedToken { token: String::from("3"), offset: Some(15) });
+/// assert_eq!(lexer::unexpected(&tokens, 5), SqlError::UnexpectedToken { token: String::new(), offset: Some(16) });
+/// ```
+///
+pub fn unexpected(tokens: &[Token], pos: usize) -> SqlError {
+    match tokens.get(pos) {
+        Some(token) => SqlError::UnexpectedToken {
+            token: token.to_string(),
+            offset: Some(token.span.start),
+        },
+        None => SqlError::UnexpectedToken {
+            token: String::new(),
+            offset: Some(tokens.last().map_or(0, |token| token.span.end)),
+        },
+    }
+}
+
+/// Moves `pos` past the given punctuation character, or returns the error for the token at `pos` if it is not that character.
+pub fn expect_punctuation(tokens: &[Token], pos: &mut usize, char: char) -> Result<(), SqlError> {
+    match tokens.get(*pos) {
+        Some(token) if token.is_punctuation(char) => {
+            *pos += 1;
+            Ok(())
+        }
+        _ => Err(unexpected(tokens, *pos)),
+    }
+}
+
+/// Splits a query into its tokens, skipping whitespace and comments.
+///
+/// The comments can be `-- until the end of the line` or `/* between delimiters */`.
+/// A quote is escaped by writing it twice, both in string literals and in quoted identifiers.
+/// A `-` followed by a digit is read as part of a negative number when it can't be a subtraction, that is, when it doesn't come after a value.
+///
+/// Returns `SqlError::UnexpectedToken`, with its offset, for characters that can't start a token and for unclosed quotes or comments.
+///
+/// # Examples
+///
+/// ```
+/// # use rustic_sql::lexer;
+/// # use rustic_sql::lexer::Span;
+/// # use rustic_sql::lexer::TokenKind;
+/// let tokens = lexer::tokenize("SELECT nombre FROM clientes WHERE id = -1; -- el primero").unwrap();
+/// let kinds: Vec<TokenKind> = tokens.iter().map(|t| t.kind).collect();
+///
+/// assert_eq!(kinds, vec![
+///     TokenKind::Keyword, TokenKind::Identifier, TokenKind::Keyword, TokenKind::Identifier,
+///     TokenKind::Keyword, TokenKind::Identifier, TokenKind::Operator, TokenKind::NumberLiteral,
+///     TokenKind::Punctuation,
+/// ]);
+/// assert_eq!(tokens[7].span, Span { start: 39, end: 41 });
+/// ```
+///
+pub fn tokenize(query: &str) -> Result<Vec<Token>, SqlError> {
+    let mut lexer = Lexer {
+        query,
+        chars: query.char_indices().peekable(),
+        tokens: Vec::new(),
+    };
+
+    while let Some(&(start, char)) = lexer.chars.peek() {
+        if char.is_whitespace() {
+            lexer.chars.next();
+        } else if query[start..].starts_with("--") {
+            lexer.skip_while(|c| c != '\n');
+        } else if query[start..].starts_with("/*") {
+            lexer.skip_block_comment(start)?;
+        } else if char.is_alphabetic() || char == '_' {
+            lexer.read_identifier(start);
+        } else if char == '"' {
+            lexer.read_quoted(start, TokenKind::Identifier)?;
+        } else if char == '\'' {
+            lexer.read_quoted(start, TokenKind::StringLiteral)?;
+        } else if char.is_ascii_digit() || (char == '.' && lexer.next_is_digit(start + 1)) {
+            lexer.read_number(start);
+        } else if char == '-' && lexer.next_is_digit(start + 1) && !lexer.after_value() {
+            lexer.chars.next();
+            lexer.read_number(start);
+        } else if matches!(char, '(' | ')' | ',' | ';' | '.') {
+            lexer.chars.next();
+            lexer.push(TokenKind::Punctuation, start, start + 1);
+        } else {
+            lexer.read_operator(start, char)?;
+        }
+    }
+
+    Ok(lexer.tokens)
+}
+
+/// Converts the text of the identifiers that are not between double quotes to the given case.
+///
+/// The spans of the tokens are kept, so they still point to the identifiers as they are written in the query, even if the case conversion changes their length.
+///
+/// # Arguments
+///
+/// * `query` - The query the tokens were read from.
+/// * `tokens` - The tokens read by `tokenize`.
+/// * `case` - What to do with the case of the identifiers.
+///
+/// # Examples
+///
+/// ```
+/// # use rustic_sql::lexer;
+/// # use rustic_sql::lexer::IdentifierCase;
+/// let query = "SELECT Nombre, \"Apellido\" FROM Clientes";
+/// let mut tokens = lexer::tokenize(query).unwrap();
+/// lexer::fold_identifiers(query, &mut tokens, IdentifierCase::Lower);
+///
+/// assert_eq!(tokens[1].text, "nombre");
+/// assert_eq!(tokens[3].text, "Apellido");
+/// assert_eq!(tokens[5].source(query), "Clientes");
+/// ```
+///
+pub fn fold_identifiers(query: &str, tokens: &mut [Token], case: IdentifierCase) {
+    if case == IdentifierCase::Preserve {
+        return;
+    }
+
+    for token in tokens {
+        if token.kind == TokenKind::Identifier && !token.source(query).starts_with('"') {
+            token.text = case.fold(&token.text);
+        }
+    }
+}
+
+/// Returns the identifier as it must be written in a query: between double quotes if it is a keyword or has characters that can't be read as part of an identifier.
+///
+/// # Examples
+///
+/// ```
+/// # use rustic_sql::lexer;
+/// assert_eq!(lexer::format_identifier("c.id"), "c.id");
+/// assert_eq!(lexer::format_identifier("fecha de alta"), "\"fecha de alta\"");
+/// ```
+///
+pub fn format_identifier(identifier: &str) -> String {
+    let is_keyword = KEYWORDS.iter().any(|k| k.eq_ignore_ascii_case(identifier));
+    if is_plain_identifier(identifier) && !is_keyword {
+        identifier.to_string()
+    } else {
+        format!("\"{}\"", identifier.replace('"', "\"\""))
+    }
+}
+
+/// Returns true if the identifier can be written without double quotes.
+fn is_plain_identifier(text: &str) -> bool {
+    text.starts_with(|c: char| c.is_alphabetic() || c == '_')
+        && text
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '_' || c == '.')
+}
+
+struct Lexer<'a> {
+    query: &'a str,
+    chars: Peekable<CharIndices<'a>>,
+    tokens: Vec<Token>,
+}
+
+impl Lexer<'_> {
+    fn push(&mut self, kind: TokenKind, start: usize, end: usize) {
+        self.push_text(kind, self.query[start..end].to_string(), start, end);
+    }
+
+    fn push_text(&mut self, kind: TokenKind, text: String, start: usize, end: usize) {
+        self.tokens.push(Token {
+            kind,
+            text,
+            span: Span { start, end },
+        });
+    }
+
+    /// Returns the offset of the next character, or the length of the query if there are no more.
+    fn offset(&mut self) -> usize {
+        self.chars.peek().map_or(self.query.len(), |(idx, _)| *idx)
+    }
+
+    fn skip_while(&mut self, condition: impl Fn(char) -> bool) {
+        while self.chars.next_if(|(_, c)| condition(*c)).is_some() {}
+    }
+
+    fn next_is_digit(&self, offset: usize) -> bool {
+        self.query[offset..].starts_with(|c: char| c.is_ascii_digit())
+    }
+
+    /// Returns true if the last token is a value, so a `-` after it is a subtraction.
+    fn after_value(&self) -> bool {
+        self.tokens.last().is_some_and(|token| match token.kind {
+            TokenKind::Identifier | TokenKind::StringLiteral | TokenKind::NumberLiteral => true,
+            TokenKind::Keyword => ["NULL", "TRUE", "FALSE"]
+                .iter()
+                .any(|k| token.is_keyword(k)),
+            TokenKind::Punctuation => token.text == ")",
+            TokenKind::Operator => false,
+        })
+    }
+
+    fn skip_block_comment(&mut self, start: usize) -> Result<(), SqlError> {
+        match self.query[start + 2..].find("*/") {
+            Some(idx) => {
+                let end = start + 2 + idx + 2;
+                while self.chars.next_if(|(i, _)| *i < end).is_some() {}
+                Ok(())
+            }
+            None => Err(SqlError::UnexpectedToken {
+                token: String::from("/*"),
+                offset: Some(start),
+            }),
+        }
+    }
+
+    fn read_identifier(&mut self, start: usize) {
+        self.skip_while(|c| c.is_alphanumeric() || c == '_' || c == '.');
+        let end = self.offset();
+        let word = &self.query[start..end];
+        let kind = if KEYWORDS.iter().any(|k| k.eq_ignore_ascii_case(word)) {
+            TokenKind::Keyword
+        } else {
+            TokenKind::Identifier
+        };
+        self.push(kind, start, end);
+    }
+
+    fn read_quoted(&mut self, start: usize, kind: TokenKind) -> Result<(), SqlError> {
+        let (_, quote) = self.chars.next().unwrap_or((start, '\''));
+        let mut text = String::new();
+
+        loop {
+            match self.chars.next() {
+                // Two quotes in a row are an escaped quote.
+                Some((_, c))
+                    if c == quote && self.chars.next_if(|(_, c)| *c == quote).is_some() =>
+                {
+                    text.push(quote);
+                }
+                Some((idx, c)) if c == quote => {
+                    self.push_text(kind, text, start, idx + 1);
+                    return Ok(());
+                }
+                Some((_, c)) => text.push(c),
+                None => {
+                    return Err(SqlError::UnexpectedToken {
+                        token: quote.to_string(),
+                        offset: Some(start),
+                    })
+                }
+            }
+        }
+    }
+
+    fn read_number(&mut self, start: usize) {
+        self.skip_while(|c| c.is_ascii_digit());
+        let offset = self.offset();
+        if self.query[offset..].starts_with('.') && self.next_is_digit(offset + 1) {
+            self.chars.next();
+            self.skip_while(|c| c.is_ascii_digit());
+        }
+        let end = self.offset();
+        self.push(TokenKind::NumberLiteral, start, end);
+    }
+
+    fn read_operator(&mut self, start: usize, char: char) -> Result<(), SqlError> {
+        if let Some(operator) = LONG_OPERATORS
+            .iter()
+            .find(|op| self.query[start..].starts_with(**op))
+        {
+            self.chars.next();
+            self.chars.next();
+            self.push(TokenKind::Operator, start, start + operator.len());
+            return Ok(());
+        }
+        if matches!(char, '=' | '<' | '>' | '+' | '-' | '*' | '/' | '%') {
+            self.chars.next();
+            self.push(TokenKind::Operator, start, start + 1);
+            return Ok(());
+        }
+        Err(SqlError::UnexpectedToken {
+            token: char.to_string(),
+            offset: Some(start),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{fold_identifiers, tokenize, IdentifierCase, Span, Token, TokenKind};
+    use crate::errors::SqlError;
+
+    fn kinds_and_texts(query: &str) -> Vec<(TokenKind, String)> {
+        tokenize(query)
+            .unwrap()
+            .into_iter()
+            .map(|t| (t.kind, t.text))
+            .collect()
+    }
+
+    #[test]
+    fn typed_tokens_with_spans() {
+        let query = "SELECT c.id, \"Fecha de alta\" FROM clientes c WHERE nombre = 'AND';";
+        let tokens = tokenize(query).unwrap();
+
+        assert_eq!(
+            tokens[1],
+            Token {
+                kind: TokenKind::Identifier,
+                text: String::from("c.id"),
+                span: Span { start: 7, end: 11 },
+            }
+        );
+        assert_eq!(tokens[2].kind, TokenKind::Punctuation);
+        assert_eq!(tokens[3].text, "Fecha de alta");
+        assert_eq!(tokens[3].source(query), "\"Fecha de alta\"");
+        assert_eq!(tokens[10].kind, TokenKind::StringLiteral);
+        assert_eq!(tokens[10].text, "AND");
+        assert_eq!(tokens[10].to_string(), "'AND'");
+        assert!(tokens[11].is_punctuation(';'));
+    }
+
+    #[test]
+    fn numbers_and_operators() {
+        assert_eq!(
+            kinds_and_texts("x-1 >= -2.5*.5 || (3) - -4"),
+            vec![
+                (TokenKind::Identifier, String::from("x")),
+                (TokenKind::Operator, String::from("-")),
+                (TokenKind::NumberLiteral, String::from("1")),
+                (TokenKind::Operator, String::from(">=")),
+                (TokenKind::NumberLiteral, String::from("-2.5")),
+                (TokenKind::Operator, String::from("*")),
+                (TokenKind::NumberLiteral, String::from(".5")),
+                (TokenKind::Operator, String::from("||")),
+                (TokenKind::Punctuation, String::from("(")),
+                (TokenKind::NumberLiteral, String::from("3")),
+                (TokenKind::Punctuation, String::from(")")),
+                (TokenKind::Operator, String::from("-")),
+                (TokenKind::NumberLiteral, String::from("-4")),
+            ]
+        );
+    }
+
+    #[test]
+    fn escapes_and_comments() {
+        assert_eq!(
+            kinds_and_texts("'it''s' /* a\n comment */ \"a \"\"b\"\"\" -- rest ; 'x\nNULL"),
+            vec![
+                (TokenKind::StringLiteral, String::from("it's")),
+                (TokenKind::Identifier, String::from("a \"b\"")),
+                (TokenKind::Keyword, String::from("NULL")),
+            ]
+        );
+    }
+
+    #[test]
+    fn keywords_and_identifier_case() {
+        let query = "select Nombre from \"Clientes\" c where c.Id Is not null";
+        let tokens = tokenize(query).unwrap();
+
+        assert!(tokens[0].is_keyword("SELECT"));
+        assert!(tokens[5].is_keyword("WHERE"));
+        assert_eq!(tokens[6].kind, TokenKind::Identifier);
+        let folded = |case: IdentifierCase| {
+            let mut folded = tokens.clone();
+            fold_identifiers(query, &mut folded, case);
+            assert!(folded.iter().zip(&tokens).all(|(a, b)| a.span == b.span));
+            let texts: Vec<String> = folded.into_iter().map(|t| t.text).collect();
+            texts.join(" ")
+        };
+        assert_eq!(
+            folded(IdentifierCase::Lower),
+            "select nombre from Clientes c where c.id Is not null"
+        );
+        assert_eq!(
+            folded(IdentifierCase::Upper),
+            "select NOMBRE from Clientes C where C.ID Is not null"
+        );
+        assert_eq!(
+            folded(IdentifierCase::Preserve),
+            "select Nombre from Clientes c where c.Id Is not null"
+        );
+    }
+
+    #[test]
+    fn invalid_input() {
+        assert_eq!(
+            tokenize("SELECT 'abc"),
+            Err(SqlError::UnexpectedToken {
+                token: String::from("'"),
+                offset: Some(7),
+            })
+        );
+        assert_eq!(tokenize("a /* b").unwrap_err().offset(), Some(2));
+        assert_eq!(
+            tokenize("edad ? 3"),
+            Err(SqlError::UnexpectedToken {
+                token: String::from("?"),
+                offset: Some(5),
+            })
+        );
+    }
+}
@@ -0,0 +1,30 @@
+//! RusticSQL: a small SQL engine over a folder of csv tables.
+//!
+//! Open a `Database` on the folder where the tables are stored and run queries with `Database::execute`.
+
+pub mod arithmetic_operator;
+pub mod clauses;
+pub mod csv;
+pub mod database;
+pub mod errors;
+pub mod functions;
+pub mod lexer;
+pub mod logical_operator;
+pub mod operator;
+pub mod parser;
+pub mod query_result;
+pub mod register;
+pub mod repl;
+pub mod schema;
+pub mod script;
+pub mod storage;
+pub mod table;
+pub mod transaction;
+pub mod utils;
+pub mod value;
+pub mod wal;
+
+pub use database::Database;
+pub use errors::SqlError;
+pub use query_result::QueryResult;
+pub use value::Value;
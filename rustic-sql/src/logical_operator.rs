@@ -1,3 +1,5 @@
+use std::fmt::Display;
+
 /// Logical operators used in the `WHERE` clause.
 /// - `And`: Logical AND operator
 /// - `Or`: Logical OR operator
@@ -9,3 +11,24 @@ pub enum LogicalOperator {
     Or,
     Not,
 }
+
+impl LogicalOperator {
+    /// Returns the precedence of the operator. Operators with higher precedence are applied first.
+    pub fn precedence(&self) -> u8 {
+        match self {
+            LogicalOperator::Or => 1,
+            LogicalOperator::And => 2,
+            LogicalOperator::Not => 3,
+        }
+    }
+}
+
+impl Display for LogicalOperator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LogicalOperator::And => write!(f, "AND"),
+            LogicalOperator::Or => write!(f, "OR"),
+            LogicalOperator::Not => write!(f, "NOT"),
+        }
+    }
+}
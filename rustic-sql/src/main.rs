@@ -1,109 +1,182 @@
-mod clauses;
-mod errors;
-mod logical_operator;
-mod operator;
-mod register;
-mod table;
-mod tokens;
-mod utils;
+use rustic_sql::{
+    database::DEFAULT_LOCK_TIMEOUT, lexer::IdentifierCase, repl::Repl, script::split_statements,
+    Database, SqlError,
+};
+use std::{
+    env, fs,
+    io::{self, IsTerminal, Read},
+    process,
+    time::Duration,
+};
 
-use clauses::{delete_sql::Delete, insert_sql::Insert, select_sql::Select, update_sql::Update};
-use errors::SqlError;
-use std::env;
-use tokens::tokens_from_query;
-use utils::table_to_csv;
+const USAGE: &str = "Usage: rustic-sql <folder> [--identifiers <preserve|lower|upper>] [--lock-timeout <milliseconds>] [query | -f <script> [--continue-on-error]]";
 
-/// Matches the first token of the query and executes the corresponding SQL clause.
-///
-/// Returns a vector of strings with the result of the query for SELECT clauses.
-///
-/// Returns an empty vector for INSERT, DELETE and UPDATE clauses because they don't show results in the console.
-///
-/// Returns an error for invalid syntax or unknown clauses.
-///
-/// # Arguments
-///
-/// * `folder_path` - A string slice that holds the path to the folder where the tables are stored.
-/// * `query` - A string slice that holds the SQL query to be executed.
-///
-/// # Examples
-///
-/// ```
-/// let folder_path = "tables";
-/// let query = "SELECT * FROM table1";
-/// let result = exec_query(folder_path, query);
-///
-/// assert_eq!(result, Ok(vec!["1,Alen,25".to_string()]));
-///
-///
-/// let folder_path = "tables";
-/// let query = "INSERT INTO table1 (id, name, age) VALUES (2, Bob, 30)";
-/// let result = exec_query(folder_path, query);
-///
-/// assert_eq!(result, Ok(vec![]));
-/// ```
-///
-fn exec_query(folder_path: &str, query: &str) -> Result<Vec<String>, SqlError> {
-    let tokens = tokens_from_query(query);
-    let mut result_csv = Vec::new();
-
-    match tokens.first().ok_or(SqlError::InvalidSyntax)?.as_str() {
-        "SELECT" => {
-            let clause = Select::new_from_tokens(tokens)?;
-            let table = clause.open_table(folder_path)?;
-
-            let result = clause.apply_to_table(table)?;
-            if clause.columns[0] == "*" {
-                result_csv = table_to_csv(&result, &result.columns)?;
-            } else {
-                result_csv = table_to_csv(&result, &clause.columns)?;
-            }
-        }
-        "INSERT" => {
-            let mut clause = Insert::new_from_tokens(tokens)?;
-            let mut file = clause.open_table(folder_path)?;
+/// What the binary was asked to do, according to its arguments.
+enum Mode {
+    Query(String),
+    Script {
+        path: String,
+        continue_on_error: bool,
+    },
+    Interactive,
+}
 
-            clause.apply_to_table(&mut file)?;
-        }
-        "DELETE" => {
-            let clause = Delete::new_from_tokens(tokens)?;
-            let table = clause.open_table(folder_path)?;
+/// Removes the `--identifiers` option from the arguments and returns its case, or `None` if it is not valid.
+fn parse_identifier_case(args: &mut Vec<String>) -> Option<IdentifierCase> {
+    let Some(idx) = args.iter().position(|arg| arg == "--identifiers") else {
+        return Some(IdentifierCase::default());
+    };
+    let case = IdentifierCase::new_from_token(args.get(idx + 1)?)?;
+    args.drain(idx..idx + 2);
+    Some(case)
+}
 
-            let result = clause.apply_to_table(table)?;
+/// Removes the `--lock-timeout` option from the arguments and returns its time, or `None` if it is not valid.
+fn parse_lock_timeout(args: &mut Vec<String>) -> Option<Duration> {
+    let Some(idx) = args.iter().position(|arg| arg == "--lock-timeout") else {
+        return Some(DEFAULT_LOCK_TIMEOUT);
+    };
+    let millis = args.get(idx + 1)?.parse().ok()?;
+    args.drain(idx..idx + 2);
+    Some(Duration::from_millis(millis))
+}
 
-            let csv = table_to_csv(&result, &result.columns)?;
+fn parse_mode(args: &[String]) -> Option<Mode> {
+    let mut script = None;
+    let mut continue_on_error = false;
+    let mut query = None;
+    let mut i = 0;
 
-            clause.write_table(csv, folder_path)?;
+    while i < args.len() {
+        match args[i].as_str() {
+            "-f" => {
+                script = Some(args.get(i + 1)?.to_string());
+                i += 1;
+            }
+            "--continue-on-error" => continue_on_error = true,
+            arg if query.is_none() => query = Some(arg.to_string()),
+            _ => return None,
         }
-        "UPDATE" => {
-            let clause = Update::new_from_tokens(tokens)?;
-            let table = clause.open_table(folder_path)?;
+        i += 1;
+    }
+
+    match (script, query) {
+        (Some(path), None) => Some(Mode::Script {
+            path,
+            continue_on_error,
+        }),
+        (None, Some(query)) if !continue_on_error => Some(Mode::Query(query)),
+        (None, None) if io::stdin().is_terminal() => Some(Mode::Interactive),
+        (None, None) => Some(Mode::Script {
+            path: String::from("-"),
+            continue_on_error,
+        }),
+        _ => None,
+    }
+}
 
-            let result = clause.apply_to_table(table)?;
+fn read_script(path: &str) -> Result<String, SqlError> {
+    if path == "-" {
+        let mut script = String::new();
+        io::stdin()
+            .read_to_string(&mut script)
+            .map_err(SqlError::from)?;
+        Ok(script)
+    } else {
+        fs::read_to_string(path).map_err(SqlError::from)
+    }
+}
 
-            let csv = table_to_csv(&result, &result.columns)?;
+fn main() -> Result<(), SqlError> {
+    let mut args: Vec<String> = env::args().collect();
+    let identifier_case = parse_identifier_case(&mut args);
+    let lock_timeout = parse_lock_timeout(&mut args);
 
-            clause.write_table(csv, folder_path)?;
+    let (folder_path, mode, identifier_case, lock_timeout) = match (
+        args.get(1),
+        parse_mode(args.get(2..).unwrap_or_default()),
+        identifier_case,
+        lock_timeout,
+    ) {
+        (Some(folder_path), Some(mode), Some(case), Some(timeout)) => {
+            (folder_path, mode, case, timeout)
         }
         _ => {
-            return Err(SqlError::InvalidSyntax);
+            println!("{}", USAGE);
+            return Ok(());
         }
-    }
-    Ok(result_csv)
-}
+    };
 
-fn main() -> Result<(), SqlError> {
-    let args: Vec<String> = env::args().collect();
+    let db = match Database::open(folder_path) {
+        Ok(db) => db
+            .with_identifier_case(identifier_case)
+            .with_lock_timeout(lock_timeout),
+        Err(e) => {
+            println!("{}", e);
+            return Ok(());
+        }
+    };
 
-    let result = exec_query(&args[1], &args[2]);
+    match mode {
+        Mode::Query(query) => match db.execute(&query) {
+            Ok(result) => {
+                for line in result.to_csv() {
+                    println!("{}", line);
+                }
+            }
+            Err(e) => {
+                for line in e.render(&query) {
+                    println!("{}", line);
+                }
+            }
+        },
+        Mode::Script {
+            path,
+            continue_on_error,
+        } => {
+            let script = match read_script(&path) {
+                Ok(script) => script,
+                Err(e) => {
+                    println!("{}", e);
+                    return Ok(());
+                }
+            };
 
-    match result {
-        Ok(csv) => {
-            for line in csv {
-                println!("{}", line);
+            let statements = split_statements(&script);
+            let mut failed = false;
+            for (idx, result) in db
+                .execute_script(&script, continue_on_error)
+                .iter()
+                .enumerate()
+            {
+                match result {
+                    Ok(result) => {
+                        for line in result.to_pretty() {
+                            println!("{}", line);
+                        }
+                    }
+                    Err(e) => {
+                        let mut lines = e.render(&statements[idx]).into_iter();
+                        println!(
+                            "Statement {}: {}",
+                            idx + 1,
+                            lines.next().unwrap_or_default()
+                        );
+                        for line in lines {
+                            println!("{}", line);
+                        }
+                        failed = true;
+                    }
+                }
+            }
+            if failed {
+                process::exit(1);
             }
         }
-        Err(e) => println!("{}", e),
+        Mode::Interactive => {
+            let stdin = io::stdin();
+            Repl::new(&db).run(stdin.lock(), &mut io::stdout())?;
+        }
     }
 
     Ok(())
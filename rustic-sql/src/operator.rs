@@ -1,11 +1,56 @@
+use std::fmt::Display;
+
 /// Enum for the operators used in the queries.
-/// - `Equal`: Equal operator
-/// - `Greater`: Greater than operator
-/// - `Lesser`: Lesser than operator
+/// - `Equal`: Equal operator (`=`)
+/// - `NotEqual`: Not equal operator (`!=` or `<>`)
+/// - `Greater`: Greater than operator (`>`)
+/// - `GreaterEqual`: Greater than or equal operator (`>=`)
+/// - `Lesser`: Lesser than operator (`<`)
+/// - `LesserEqual`: Lesser than or equal operator (`<=`)
+/// - `Like`: Pattern matching operator (`LIKE`), where `%` matches any text and `_` any single character
+/// - `NotLike`: Negated pattern matching operator (`NOT LIKE`)
 ///
 #[derive(Debug, PartialEq)]
 pub enum Operator {
     Equal,
+    NotEqual,
     Greater,
+    GreaterEqual,
     Lesser,
+    LesserEqual,
+    Like,
+    NotLike,
+}
+
+impl Operator {
+    /// Creates an `Operator` from a comparison symbol, like `>=`.
+    ///
+    /// Returns `None` if the token is not a comparison symbol.
+    ///
+    pub fn new_from_token(token: &str) -> Option<Self> {
+        match token {
+            "=" => Some(Operator::Equal),
+            "!=" | "<>" => Some(Operator::NotEqual),
+            ">" => Some(Operator::Greater),
+            ">=" => Some(Operator::GreaterEqual),
+            "<" => Some(Operator::Lesser),
+            "<=" => Some(Operator::LesserEqual),
+            _ => None,
+        }
+    }
+}
+
+impl Display for Operator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Operator::Equal => write!(f, "="),
+            Operator::NotEqual => write!(f, "<>"),
+            Operator::Greater => write!(f, ">"),
+            Operator::GreaterEqual => write!(f, ">="),
+            Operator::Lesser => write!(f, "<"),
+            Operator::LesserEqual => write!(f, "<="),
+            Operator::Like => write!(f, "LIKE"),
+            Operator::NotLike => write!(f, "NOT LIKE"),
+        }
+    }
 }
@@ -0,0 +1,1005 @@
+use crate::{
+    clauses::{
+        alter_sql::{Alter, AlterAction},
+        create_sql::Create,
+        delete_sql::Delete,
+        drop_sql::Drop,
+        expression::{parse_constant, Expression},
+        groupby_sql::GroupBy,
+        insert_sql::{Insert, InsertSource},
+        into_sql::Into,
+        join_sql::{Join, JoinKind},
+        orderby_sql::OrderBy,
+        select_sql::Select,
+        set_sql::Set,
+        truncate_sql::Truncate,
+        update_sql::Update,
+        where_sql::Where,
+    },
+    errors::SqlError,
+    lexer::{tokenize, Token, TokenKind},
+    schema::{ColumnDef, Schema},
+};
+use std::fmt::Display;
+
+/// Enum for the statements of the queries, as they are read by the parser.
+///
+/// - `Select`: a `SELECT` query.
+/// - `Insert`: an `INSERT INTO` statement.
+/// - `Update`: an `UPDATE` statement.
+/// - `Delete`: a `DELETE FROM` statement.
+/// - `Create`: a `CREATE TABLE` statement.
+/// - `Drop`: a `DROP TABLE` statement.
+/// - `Truncate`: a `TRUNCATE TABLE` statement.
+/// - `Alter`: an `ALTER TABLE` statement.
+/// - `Begin`: a `BEGIN` statement, which starts a transaction.
+/// - `Commit`: a `COMMIT` statement, which makes the changes of the transaction visible.
+/// - `Rollback`: a `ROLLBACK` statement, which discards the changes of the transaction.
+///
+/// Its `Display` writes the statement back as a query, with the keywords in uppercase.
+///
+#[derive(Debug, PartialEq)]
+pub enum Statement {
+    Select(Box<Select>),
+    Insert(Insert),
+    Update(Update),
+    Delete(Delete),
+    Create(Create),
+    Drop(Drop),
+    Truncate(Truncate),
+    Alter(Alter),
+    Begin,
+    Commit,
+    Rollback,
+}
+
+/// Parses a query into the statement it holds.
+///
+/// The query can end with a `;`. Any other token after the statement is an error.
+///
+/// Returns `SqlError::UnexpectedToken` for the first token that doesn't follow the syntax of the statement, with its offset in the query when it is known.
+/// If the query ends before the statement is complete, the token of the error is empty and its offset is the end of the query.
+///
+/// # Arguments
+///
+/// * `query` - The query to parse.
+///
+/// # Examples
+///
+/// ```
+/// # use rustic_sql::SqlError;
+/// # use rustic_sql::parser;
+/// let statement = parser::parse("delete from clientes where id = 1;").unwrap();
+/// assert_eq!(statement.to_string(), "DELETE FROM clientes WHERE id = 1");
+///
+/// assert_eq!(
+///     parser::parse("DELETE clientes"),
+///     Err(SqlError::UnexpectedToken { token: String::from("clientes"), offset: Some(7) })
+/// );
+/// ```
+///
+pub fn parse(query: &str) -> Result<Statement, SqlError> {
+    let tokens = tokenize(query)?;
+    parse_tokens(query, &tokens)
+}
+
+/// Parses the tokens read by `lexer::tokenize` into the statement they hold, like `parse` does.
+///
+/// # Arguments
+///
+/// * `query` - The query the tokens were read from.
+/// * `tokens` - The tokens of the query.
+///
+pub fn parse_tokens(query: &str, tokens: &[Token]) -> Result<Statement, SqlError> {
+    let mut parser = Parser {
+        query,
+        tokens,
+        pos: 0,
+    };
+
+    let statement = parser.statement()?;
+    parser.eat_punctuation(';');
+    match parser.peek() {
+        Some(_) => Err(parser.unexpected()),
+        None => Ok(statement),
+    }
+}
+
+/// Names of the selected columns and the expressions of the computed ones, like the fields of `Select`.
+type SelectedColumns = (Vec<String>, Vec<(String, Expression)>);
+
+/// Recursive descent parser over the tokens of a query, with one method for each part of the grammar.
+///
+/// Expressions, conditions, `ORDER BY` keys and column declarations are parsed by their clauses, over the same tokens, and the parser goes on from where they stop.
+///
+struct Parser<'a> {
+    query: &'a str,
+    tokens: &'a [Token],
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<&'a Token> {
+        self.tokens.get(self.pos)
+    }
+
+    /// Returns the error for the current token, or for the end of the query if there are no more tokens.
+    fn unexpected(&self) -> SqlError {
+        match self.peek() {
+            Some(token) => SqlError::UnexpectedToken {
+                token: token.source(self.query).to_string(),
+                offset: Some(token.span.start),
+            },
+            None => SqlError::UnexpectedToken {
+                token: String::new(),
+                offset: Some(self.tokens.last().map_or(0, |token| token.span.end)),
+            },
+        }
+    }
+
+    fn at_keyword(&self, keyword: &str) -> bool {
+        self.peek().is_some_and(|token| token.is_keyword(keyword))
+    }
+
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        let found = self.at_keyword(keyword);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn expect_keyword(&mut self, keyword: &str) -> Result<(), SqlError> {
+        match self.eat_keyword(keyword) {
+            true => Ok(()),
+            false => Err(self.unexpected()),
+        }
+    }
+
+    fn eat_punctuation(&mut self, char: char) -> bool {
+        let found = self.peek().is_some_and(|token| token.is_punctuation(char));
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn expect_punctuation(&mut self, char: char) -> Result<(), SqlError> {
+        match self.eat_punctuation(char) {
+            true => Ok(()),
+            false => Err(self.unexpected()),
+        }
+    }
+
+    fn expect_operator(&mut self, operator: &str) -> Result<(), SqlError> {
+        match self.peek() {
+            Some(token) if token.kind == TokenKind::Operator && token.text == operator => {
+                self.pos += 1;
+                Ok(())
+            }
+            _ => Err(self.unexpected()),
+        }
+    }
+
+    fn identifier(&mut self) -> Result<String, SqlError> {
+        match self.peek() {
+            Some(token) if token.kind == TokenKind::Identifier => {
+                self.pos += 1;
+                Ok(token.text.clone())
+            }
+            _ => Err(self.unexpected()),
+        }
+    }
+
+    fn identifier_list(&mut self) -> Result<Vec<String>, SqlError> {
+        let mut identifiers = vec![self.identifier()?];
+        while self.eat_punctuation(',') {
+            identifiers.push(self.identifier()?);
+        }
+        Ok(identifiers)
+    }
+
+    fn expression(&mut self) -> Result<Expression, SqlError> {
+        Expression::new_from_tokens(self.tokens, &mut self.pos)
+    }
+
+    /// Parses a `WHERE` or `HAVING` clause, starting at its keyword.
+    fn condition(&mut self) -> Result<Where, SqlError> {
+        Where::new_from_tokens(self.tokens, &mut self.pos)
+    }
+
+    fn column_def(&mut self) -> Result<ColumnDef, SqlError> {
+        ColumnDef::new_from_tokens(self.tokens, &mut self.pos)
+    }
+
+    fn statement(&mut self) -> Result<Statement, SqlError> {
+        let keyword = match self.peek() {
+            Some(token) if token.kind == TokenKind::Keyword => token.text.to_uppercase(),
+            _ => return Err(self.unexpected()),
+        };
+
+        match keyword.as_str() {
+            "SELECT" => self
+                .select()
+                .map(|select| Statement::Select(Box::new(select))),
+            "INSERT" => self.insert().map(Statement::Insert),
+            "UPDATE" => self.update().map(Statement::Update),
+            "DELETE" => self.delete().map(Statement::Delete),
+            "CREATE" => self.create().map(Statement::Create),
+            "DROP" => self.drop().map(Statement::Drop),
+            "TRUNCATE" => self.truncate().map(Statement::Truncate),
+            "ALTER" => self.alter().map(Statement::Alter),
+            "BEGIN" | "COMMIT" | "ROLLBACK" => Ok(self.transaction_control()),
+            _ => Err(self.unexpected()),
+        }
+    }
+
+    /// `SELECT [DISTINCT] columns FROM table [[AS] alias] [joins] [WHERE condition] [GROUP BY columns] [HAVING condition] [ORDER BY keys] [LIMIT count] [OFFSET count]`
+    fn select(&mut self) -> Result<Select, SqlError> {
+        self.expect_keyword("SELECT")?;
+        let distinct = self.eat_keyword("DISTINCT");
+        let (columns, expressions) = self.selected_columns()?;
+
+        self.expect_keyword("FROM")?;
+        let table_name = self.identifier()?;
+        let table_alias = self.alias()?;
+
+        let mut joins = Vec::new();
+        while let Some(kind) = self.join_kind()? {
+            joins.push(self.join(kind)?);
+        }
+
+        let mut select = Select {
+            table_name,
+            table_alias,
+            joins,
+            columns,
+            expressions,
+            distinct,
+            ..Default::default()
+        };
+
+        if self.at_keyword("WHERE") {
+            select.where_clause = Some(self.condition()?);
+        }
+        if self.eat_keyword("GROUP") {
+            self.expect_keyword("BY")?;
+            select.groupby_clause = Some(GroupBy {
+                columns: self.identifier_list()?,
+            });
+        }
+        if self.at_keyword("HAVING") {
+            select.having_clause = Some(self.condition()?);
+        }
+        if self.at_keyword("ORDER") {
+            select.orderby_clause = Some(OrderBy::new_from_tokens(self.tokens, &mut self.pos)?);
+        }
+        loop {
+            if self.eat_keyword("LIMIT") {
+                select.limit = Some(self.count()?);
+            } else if self.eat_keyword("OFFSET") {
+                select.offset = self.count()?;
+            } else {
+                break;
+            }
+        }
+
+        Ok(select)
+    }
+
+    /// `* | expression [AS alias], ...`
+    fn selected_columns(&mut self) -> Result<SelectedColumns, SqlError> {
+        let is_star = self
+            .peek()
+            .is_some_and(|token| token.kind == TokenKind::Operator && token.text == "*");
+        if is_star {
+            self.pos += 1;
+            return Ok((vec![String::from("*")], Vec::new()));
+        }
+
+        let mut columns = Vec::new();
+        let mut expressions = Vec::new();
+        loop {
+            let expression = self.expression()?;
+            let alias = match self.eat_keyword("AS") {
+                true => Some(self.identifier()?),
+                false => None,
+            };
+
+            match (expression, alias) {
+                (Expression::Column(name), None) => columns.push(name),
+                (expression, alias) => {
+                    let name = alias.unwrap_or_else(|| expression.to_string());
+                    columns.push(name.clone());
+                    expressions.push((name, expression));
+                }
+            }
+            if !self.eat_punctuation(',') {
+                return Ok((columns, expressions));
+            }
+        }
+    }
+
+    /// `[AS] alias`, which can be missing.
+    fn alias(&mut self) -> Result<Option<String>, SqlError> {
+        if self.eat_keyword("AS") {
+            return self.identifier().map(Some);
+        }
+        match self.peek() {
+            Some(token) if token.kind == TokenKind::Identifier => {
+                self.pos += 1;
+                Ok(Some(token.text.clone()))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// `[INNER | LEFT [OUTER] | CROSS] JOIN`, or `None` if there isn't another join.
+    fn join_kind(&mut self) -> Result<Option<JoinKind>, SqlError> {
+        let kind = if self.eat_keyword("JOIN") {
+            return Ok(Some(JoinKind::Inner));
+        } else if self.eat_keyword("INNER") {
+            JoinKind::Inner
+        } else if self.eat_keyword("LEFT") {
+            self.eat_keyword("OUTER");
+            JoinKind::Left
+        } else if self.eat_keyword("CROSS") {
+            JoinKind::Cross
+        } else {
+            return Ok(None);
+        };
+        self.expect_keyword("JOIN")?;
+        Ok(Some(kind))
+    }
+
+    /// `table [[AS] alias] ON column = column [AND column = column ...]`, without `ON` for `CROSS JOIN`.
+    fn join(&mut self, kind: JoinKind) -> Result<Join, SqlError> {
+        let table_name = self.identifier()?;
+        let alias = self.alias()?;
+
+        let mut on = Vec::new();
+        if kind != JoinKind::Cross {
+            self.expect_keyword("ON")?;
+            loop {
+                let left = self.identifier()?;
+                self.expect_operator("=")?;
+                on.push((left, self.identifier()?));
+                if !self.eat_keyword("AND") {
+                    break;
+                }
+            }
+        }
+
+        Ok(Join {
+            kind,
+            table_name,
+            alias,
+            on,
+        })
+    }
+
+    /// The number of registers of `LIMIT` or `OFFSET`.
+    fn count(&mut self) -> Result<usize, SqlError> {
+        let count = self
+            .peek()
+            .filter(|token| token.kind == TokenKind::NumberLiteral)
+            .and_then(|token| token.text.parse().ok())
+            .ok_or_else(|| self.unexpected())?;
+        self.pos += 1;
+        Ok(count)
+    }
+
+    /// `INSERT INTO table [(columns)] VALUES (values), ...` or `INSERT INTO table [(columns)] SELECT ...`
+    fn insert(&mut self) -> Result<Insert, SqlError> {
+        self.expect_keyword("INSERT")?;
+        self.expect_keyword("INTO")?;
+        let table_name = self.identifier()?;
+
+        let mut columns = Vec::new();
+        if self.eat_punctuation('(') {
+            columns = self.identifier_list()?;
+            self.expect_punctuation(')')?;
+        }
+
+        let source = if self.at_keyword("SELECT") {
+            InsertSource::Select(Box::new(self.select()?))
+        } else {
+            self.expect_keyword("VALUES")?;
+            let mut rows = Vec::new();
+            loop {
+                self.expect_punctuation('(')?;
+                let mut values = vec![parse_constant(self.tokens, &mut self.pos)?];
+                while self.eat_punctuation(',') {
+                    values.push(parse_constant(self.tokens, &mut self.pos)?);
+                }
+                self.expect_punctuation(')')?;
+                rows.push(values);
+                if !self.eat_punctuation(',') {
+                    break;
+                }
+            }
+            InsertSource::Values(rows)
+        };
+
+        Ok(Insert {
+            source,
+            into_clause: Into {
+                table_name,
+                columns,
+            },
+        })
+    }
+
+    /// `UPDATE table SET column = expression, ... [WHERE condition]`
+    fn update(&mut self) -> Result<Update, SqlError> {
+        self.expect_keyword("UPDATE")?;
+        let table_name = self.identifier()?;
+        self.expect_keyword("SET")?;
+
+        let mut set = Vec::new();
+        loop {
+            let column = self.identifier()?;
+            self.expect_operator("=")?;
+            set.push((column, self.expression()?));
+            if !self.eat_punctuation(',') {
+                break;
+            }
+        }
+
+        let mut where_clause = None;
+        if self.at_keyword("WHERE") {
+            where_clause = Some(self.condition()?);
+        }
+
+        Ok(Update {
+            table_name,
+            set_clause: Set(set),
+            where_clause,
+        })
+    }
+
+    /// `DELETE FROM table [WHERE condition]`
+    fn delete(&mut self) -> Result<Delete, SqlError> {
+        self.expect_keyword("DELETE")?;
+        self.expect_keyword("FROM")?;
+        let table_name = self.identifier()?;
+
+        let mut where_clause = None;
+        if self.at_keyword("WHERE") {
+            where_clause = Some(self.condition()?);
+        }
+
+        Ok(Delete {
+            table_name,
+            where_clause,
+        })
+    }
+
+    /// `CREATE TABLE table (column TYPE [NOT NULL] [DEFAULT value], ...)`
+    fn create(&mut self) -> Result<Create, SqlError> {
+        self.expect_keyword("CREATE")?;
+        self.expect_keyword("TABLE")?;
+        let table_name = self.identifier()?;
+
+        self.expect_punctuation('(')?;
+        let mut columns: Vec<ColumnDef> = Vec::new();
+        loop {
+            let column = self.column_def()?;
+            if columns.iter().any(|c| c.name == column.name) {
+                return Err(SqlError::InvalidColumn);
+            }
+            columns.push(column);
+            if !self.eat_punctuation(',') {
+                break;
+            }
+        }
+        self.expect_punctuation(')')?;
+
+        Ok(Create {
+            table_name,
+            schema: Schema { columns },
+        })
+    }
+
+    /// `DROP TABLE [IF EXISTS] table`
+    fn drop(&mut self) -> Result<Drop, SqlError> {
+        self.expect_keyword("DROP")?;
+        self.expect_keyword("TABLE")?;
+
+        let if_exists = self.eat_keyword("IF");
+        if if_exists {
+            self.expect_keyword("EXISTS")?;
+        }
+
+        Ok(Drop {
+            table_name: self.identifier()?,
+            if_exists,
+        })
+    }
+
+    /// `TRUNCATE [TABLE] table`
+    fn truncate(&mut self) -> Result<Truncate, SqlError> {
+        self.expect_keyword("TRUNCATE")?;
+        self.eat_keyword("TABLE");
+
+        Ok(Truncate {
+            table_name: self.identifier()?,
+        })
+    }
+
+    /// `BEGIN [TRANSACTION]`, `COMMIT [TRANSACTION]` or `ROLLBACK [TRANSACTION]`.
+    fn transaction_control(&mut self) -> Statement {
+        let statement = if self.eat_keyword("BEGIN") {
+            Statement::Begin
+        } else if self.eat_keyword("COMMIT") {
+            Statement::Commit
+        } else {
+            self.pos += 1;
+            Statement::Rollback
+        };
+        self.eat_keyword("TRANSACTION");
+        statement
+    }
+
+    /// `ALTER TABLE table` and one of `ADD [COLUMN] column [TYPE ...]`, `DROP [COLUMN] column`, `RENAME [COLUMN] column TO new_column` or `RENAME TO new_table`.
+    fn alter(&mut self) -> Result<Alter, SqlError> {
+        self.expect_keyword("ALTER")?;
+        self.expect_keyword("TABLE")?;
+        let table_name = self.identifier()?;
+
+        let action = if self.eat_keyword("ADD") {
+            self.eat_keyword("COLUMN");
+            let start = self.pos;
+            let name = self.identifier()?;
+            let definition = match self.peek() {
+                Some(token) if !token.is_punctuation(';') => {
+                    self.pos = start;
+                    Some(self.column_def()?)
+                }
+                _ => None,
+            };
+            AlterAction::AddColumn { name, definition }
+        } else if self.eat_keyword("DROP") {
+            self.eat_keyword("COLUMN");
+            AlterAction::DropColumn(self.identifier()?)
+        } else if self.eat_keyword("RENAME") {
+            if self.eat_keyword("TO") {
+                AlterAction::RenameTo(self.identifier()?)
+            } else {
+                self.eat_keyword("COLUMN");
+                let from = self.identifier()?;
+                self.expect_keyword("TO")?;
+                AlterAction::RenameColumn {
+                    from,
+                    to: self.identifier()?,
+                }
+            }
+        } else {
+            return Err(self.unexpected());
+        };
+
+        Ok(Alter { table_name, action })
+    }
+}
+
+impl Display for Statement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Statement::Select(select) => write!(f, "{}", select),
+            Statement::Insert(insert) => write!(f, "{}", insert),
+            Statement::Update(update) => write!(f, "{}", update),
+            Statement::Delete(delete) => write!(f, "{}", delete),
+            Statement::Create(create) => write!(f, "{}", create),
+            Statement::Drop(drop) => write!(f, "{}", drop),
+            Statement::Truncate(truncate) => write!(f, "{}", truncate),
+            Statement::Alter(alter) => write!(f, "{}", alter),
+            Statement::Begin => write!(f, "BEGIN"),
+            Statement::Commit => write!(f, "COMMIT"),
+            Statement::Rollback => write!(f, "ROLLBACK"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse, Statement};
+    use crate::{
+        clauses::{
+            aggregate::{Aggregate, AggregateFunction},
+            alter_sql::AlterAction,
+            condition::Condition,
+            expression::Expression,
+            insert_sql::InsertSource,
+            join_sql::{Join, JoinKind},
+            orderby_sql::{OrderBy, OrderKey},
+            select_sql::Select,
+        },
+        errors::SqlError,
+        operator::Operator,
+        schema::{ColumnDef, ColumnType},
+        value::Value,
+    };
+
+    fn parse_select(query: &str) -> Select {
+        match parse(query) {
+            Ok(Statement::Select(select)) => *select,
+            other => panic!("{:?}", other),
+        }
+    }
+
+    fn unexpected(token: &str, offset: usize) -> Result<Statement, SqlError> {
+        Err(SqlError::UnexpectedToken {
+            token: token.to_string(),
+            offset: Some(offset),
+        })
+    }
+
+    #[test]
+    fn parse_select_clauses() {
+        let select = parse_select("SELECT col FROM tabla WHERE cantidad > 1 ORDER BY email DESC");
+        assert_eq!(select.columns, ["col"]);
+        assert_eq!(select.table_name, "tabla");
+        assert_eq!(
+            select.where_clause.unwrap().condition,
+            Condition::Simple {
+                field: String::from("cantidad"),
+                operator: Operator::Greater,
+                value: Value::Integer(1),
+            }
+        );
+        assert_eq!(
+            select.orderby_clause,
+            Some(OrderBy {
+                keys: vec![OrderKey::new_from_column("email").desc()]
+            })
+        );
+
+        let select = parse_select("SELECT DISTINCT col FROM tabla ORDER BY col LIMIT 2 OFFSET 1;");
+        assert!(select.distinct);
+        assert_eq!(select.limit, Some(2));
+        assert_eq!(select.offset, 1);
+        assert_eq!(select.where_clause, None);
+
+        let select = parse_select("SELECT producto, SUM(cantidad) FROM ordenes GROUP BY producto, cliente HAVING SUM(cantidad) > 2");
+        let sum = Expression::Aggregate(Aggregate {
+            function: AggregateFunction::Sum,
+            column: Some(String::from("cantidad")),
+        });
+        assert_eq!(select.columns, ["producto", "SUM(cantidad)"]);
+        assert_eq!(
+            select.expressions,
+            [(String::from("SUM(cantidad)"), sum.clone())]
+        );
+        assert_eq!(
+            select.groupby_clause.unwrap().columns,
+            ["producto", "cliente"]
+        );
+        assert_eq!(
+            select.having_clause.unwrap().condition,
+            Condition::Comparison {
+                left: sum,
+                operator: Operator::Greater,
+                right: Expression::Literal(Value::Integer(2)),
+            }
+        );
+    }
+
+    #[test]
+    fn parse_select_aliases() {
+        let select = parse_select("SELECT id, nombre AS name, edad * 2 FROM clientes AS c");
+        assert_eq!(select.columns, ["id", "name", "edad * 2"]);
+        assert_eq!(
+            select.expressions[0],
+            (
+                String::from("name"),
+                Expression::Column(String::from("nombre"))
+            )
+        );
+        assert_eq!(select.table_alias, Some(String::from("c")));
+
+        assert_eq!(
+            parse("SELECT nombre AS 'name' FROM clientes"),
+            unexpected("'name'", 17)
+        );
+        assert_eq!(
+            parse("SELECT nombre AS FROM clientes"),
+            unexpected("FROM", 17)
+        );
+        assert_eq!(parse("SELECT FROM clientes"), unexpected("FROM", 7));
+    }
+
+    #[test]
+    fn parse_joins() {
+        let select = parse_select(
+            "SELECT * FROM clientes c INNER JOIN ordenes AS o ON c.id = o.id_cliente \
+             LEFT OUTER JOIN pagos p ON o.id = p.id_orden AND c.id = p.id_cliente CROSS JOIN meses",
+        );
+        assert_eq!(
+            select.joins[0],
+            Join {
+                kind: JoinKind::Inner,
+                table_name: String::from("ordenes"),
+                alias: Some(String::from("o")),
+                on: vec![(String::from("c.id"), String::from("o.id_cliente"))],
+            }
+        );
+        assert_eq!(select.joins[1].kind, JoinKind::Left);
+        assert_eq!(select.joins[1].on.len(), 2);
+        assert_eq!(select.joins[2].kind, JoinKind::Cross);
+        assert!(select.joins[2].on.is_empty());
+
+        assert_eq!(
+            parse("SELECT * FROM a JOIN b ON a.x > b.x"),
+            unexpected(">", 30)
+        );
+        assert_eq!(parse("SELECT * FROM a JOIN b"), unexpected("", 22));
+    }
+
+    #[test]
+    fn parse_insert_update_delete() {
+        match parse("INSERT INTO personas (nombre, edad) VALUES (Alen, -25)") {
+            Ok(Statement::Insert(insert)) => {
+                assert_eq!(insert.into_clause.table_name, "personas");
+                assert_eq!(insert.into_clause.columns, ["nombre", "edad"]);
+                assert_eq!(
+                    insert.source,
+                    InsertSource::Values(vec![vec![
+                        Value::Text(String::from("Alen")),
+                        Value::Integer(-25)
+                    ]])
+                );
+            }
+            other => panic!("{:?}", other),
+        }
+        match parse("INSERT INTO personas VALUES ('Alen', 20 + 5, NULL), ('Beto', 30, '')") {
+            Ok(Statement::Insert(insert)) => {
+                assert!(insert.into_clause.columns.is_empty());
+                assert_eq!(
+                    insert.source,
+                    InsertSource::Values(vec![
+                        vec![
+                            Value::Text(String::from("Alen")),
+                            Value::Integer(25),
+                            Value::Null
+                        ],
+                        vec![
+                            Value::Text(String::from("Beto")),
+                            Value::Integer(30),
+                            Value::Text(String::new())
+                        ],
+                    ])
+                );
+            }
+            other => panic!("{:?}", other),
+        }
+        match parse("INSERT INTO adultos (nombre) SELECT nombre FROM personas WHERE edad >= 18") {
+            Ok(Statement::Insert(insert)) => match insert.source {
+                InsertSource::Select(select) => {
+                    assert_eq!(select.table_name, "personas");
+                    assert!(select.where_clause.is_some());
+                }
+                other => panic!("{:?}", other),
+            },
+            other => panic!("{:?}", other),
+        }
+
+        match parse("UPDATE personas SET nombre = 'Alen', edad = edad + 1 WHERE edad < 30") {
+            Ok(Statement::Update(update)) => {
+                assert_eq!(update.table_name, "personas");
+                assert_eq!(update.set_clause.0.len(), 2);
+                assert_eq!(
+                    update.set_clause.0[0],
+                    (
+                        String::from("nombre"),
+                        Expression::Literal(Value::Text(String::from("Alen")))
+                    )
+                );
+                assert!(update.where_clause.is_some());
+            }
+            other => panic!("{:?}", other),
+        }
+
+        match parse("DELETE FROM personas") {
+            Ok(Statement::Delete(delete)) => {
+                assert_eq!(delete.table_name, "personas");
+                assert_eq!(delete.where_clause, None);
+            }
+            other => panic!("{:?}", other),
+        }
+
+        assert_eq!(parse("INSERT INTO personas"), unexpected("", 20));
+        assert_eq!(parse("INSERT INTO personas VALUES ()"), unexpected(")", 29));
+        assert_eq!(
+            parse("INSERT INTO personas VALUES (1),"),
+            unexpected("", 32)
+        );
+        assert_eq!(
+            parse("INSERT INTO personas VALUES (1) (2)"),
+            unexpected("(", 32)
+        );
+        assert_eq!(parse("UPDATE personas SET"), unexpected("", 19));
+        assert_eq!(parse("UPDATE personas SET edad 1"), unexpected("1", 25));
+        assert_eq!(parse("DELETE personas"), unexpected("personas", 7));
+        assert_eq!(parse("DELETE FROM personas WHERE"), unexpected("", 26));
+    }
+
+    #[test]
+    fn parse_table_statements() {
+        match parse("CREATE TABLE personas (id INT NOT NULL, nombre TEXT DEFAULT 'Sin nombre')") {
+            Ok(Statement::Create(create)) => {
+                assert_eq!(create.table_name, "personas");
+                assert_eq!(
+                    create.schema.columns[1],
+                    ColumnDef {
+                        name: String::from("nombre"),
+                        column_type: ColumnType::Text,
+                        not_null: false,
+                        default: Some(Value::Text(String::from("Sin nombre"))),
+                    }
+                );
+            }
+            other => panic!("{:?}", other),
+        }
+        assert_eq!(
+            parse("CREATE TABLE personas (id INT, id TEXT)"),
+            Err(SqlError::InvalidColumn)
+        );
+        assert_eq!(parse("CREATE personas (id INT)"), unexpected("personas", 7));
+
+        match parse("DROP TABLE IF EXISTS personas") {
+            Ok(Statement::Drop(drop)) => assert!(drop.if_exists),
+            other => panic!("{:?}", other),
+        }
+        assert_eq!(parse("DROP TABLE IF personas"), unexpected("personas", 14));
+
+        match parse("TRUNCATE personas") {
+            Ok(Statement::Truncate(truncate)) => assert_eq!(truncate.table_name, "personas"),
+            other => panic!("{:?}", other),
+        }
+        assert_eq!(parse("TRUNCATE TABLE"), unexpected("", 14));
+    }
+
+    #[test]
+    fn parse_alter_actions() {
+        let action = |query: &str| match parse(query) {
+            Ok(Statement::Alter(alter)) => alter.action,
+            other => panic!("{:?}", other),
+        };
+
+        assert_eq!(
+            action("ALTER TABLE t ADD COLUMN edad INT"),
+            AlterAction::AddColumn {
+                name: String::from("edad"),
+                definition: Some(ColumnDef {
+                    name: String::from("edad"),
+                    column_type: ColumnType::Integer,
+                    not_null: false,
+                    default: None,
+                }),
+            }
+        );
+        assert_eq!(
+            action("ALTER TABLE t ADD edad"),
+            AlterAction::AddColumn {
+                name: String::from("edad"),
+                definition: None,
+            }
+        );
+        assert_eq!(
+            action("ALTER TABLE t DROP edad"),
+            AlterAction::DropColumn(String::from("edad"))
+        );
+        assert_eq!(
+            action("ALTER TABLE t RENAME COLUMN a TO b"),
+            AlterAction::RenameColumn {
+                from: String::from("a"),
+                to: String::from("b"),
+            }
+        );
+        assert_eq!(
+            action("ALTER TABLE t RENAME TO u"),
+            AlterAction::RenameTo(String::from("u"))
+        );
+
+        assert_eq!(parse("ALTER TABLE t"), unexpected("", 13));
+        assert_eq!(
+            parse("ALTER TABLE t RENAME COLUMN TO b"),
+            unexpected("TO", 28)
+        );
+        assert_eq!(parse("ALTER TABLE t MODIFY a"), unexpected("MODIFY", 14));
+    }
+
+    #[test]
+    fn transaction_statements() {
+        assert_eq!(parse("BEGIN"), Ok(Statement::Begin));
+        assert_eq!(parse("begin transaction;"), Ok(Statement::Begin));
+        assert_eq!(parse("COMMIT TRANSACTION"), Ok(Statement::Commit));
+        assert_eq!(parse("rollback"), Ok(Statement::Rollback));
+        assert_eq!(parse("BEGIN WORK"), unexpected("WORK", 6));
+        assert_eq!(parse("COMMIT personas"), unexpected("personas", 7));
+    }
+
+    #[test]
+    fn unexpected_tokens() {
+        assert_eq!(parse(""), unexpected("", 0));
+        assert_eq!(parse("SELEC * FROM t"), unexpected("SELEC", 0));
+        assert_eq!(parse("SELECT * FROM t a b"), unexpected("b", 18));
+        assert_eq!(parse("SELECT * FROM t; SELECT"), unexpected("SELECT", 17));
+        assert_eq!(
+            parse("SELECT * FROM t WHERE a = 1, b = 2"),
+            unexpected(",", 27)
+        );
+        assert_eq!(parse("SELECT * FROM t LIMIT dos"), unexpected("dos", 22));
+        assert_eq!(parse("SELECT nombre edad FROM t"), unexpected("edad", 14));
+        assert_eq!(parse("SELECT * FROM t WHERE a = 1 b"), unexpected("b", 28));
+        assert_eq!(
+            parse("SELECT * FROM t WHERE (a = 1 OR b) ORDER BY a"),
+            unexpected(")", 33)
+        );
+        assert_eq!(
+            parse("SELECT a FROM t ORDER BY a NULLS LIMIT 1"),
+            unexpected("LIMIT", 33)
+        );
+        assert_eq!(parse("SELECT UPPER(a, FROM t"), unexpected("FROM", 16));
+    }
+
+    #[test]
+    fn display_round_trip() {
+        let queries = [
+            (
+                "select distinct nombre, edad * 2 as doble, COUNT(*) from clientes c where not (edad > 18 or nombre like 'A%') and id in (1, 2) group by nombre having COUNT(*) >= 1 order by 2 desc, nombre nulls last limit 5 offset 1;",
+                "SELECT DISTINCT nombre, edad * 2 AS doble, COUNT(*) FROM clientes c WHERE NOT (edad > 18 OR nombre LIKE 'A%') AND id IN (1, 2) GROUP BY nombre HAVING COUNT(*) >= 1 ORDER BY 2 DESC, nombre NULLS LAST LIMIT 5 OFFSET 1",
+            ),
+            (
+                "SELECT * FROM clientes AS c LEFT JOIN ordenes o ON c.id = o.id_cliente WHERE o.id IS NULL OR (c.id BETWEEN 1 AND 3 AND c.id <> 2)",
+                "SELECT * FROM clientes c LEFT JOIN ordenes o ON c.id = o.id_cliente WHERE o.id IS NULL OR c.id BETWEEN 1 AND 3 AND c.id <> 2",
+            ),
+            (
+                "SELECT UPPER(nombre) FROM \"mis clientes\" WHERE a = 1 AND (b = 2 AND c = 3)",
+                "SELECT UPPER(nombre) FROM \"mis clientes\" WHERE a = 1 AND (b = 2 AND c = 3)",
+            ),
+            (
+                "insert into personas (nombre, edad) values ('O''Neil', -2.5)",
+                "INSERT INTO personas (nombre, edad) VALUES ('O''Neil', -2.5)",
+            ),
+            (
+                "insert into personas values (1, 'a'), (2, NULL)",
+                "INSERT INTO personas VALUES (1, 'a'), (2, NULL)",
+            ),
+            (
+                "insert into adultos (nombre) select nombre from personas where edad >= 18;",
+                "INSERT INTO adultos (nombre) SELECT nombre FROM personas WHERE edad >= 18",
+            ),
+            (
+                "update personas set edad = (edad + 1) * 2 where nombre != 'Ana'",
+                "UPDATE personas SET edad = (edad + 1) * 2 WHERE nombre <> 'Ana'",
+            ),
+            (
+                "delete from personas where edad not between 18 and 30",
+                "DELETE FROM personas WHERE edad NOT BETWEEN 18 AND 30",
+            ),
+            (
+                "create table personas (id int not null, activo boolean default true)",
+                "CREATE TABLE personas (id INTEGER NOT NULL, activo BOOLEAN DEFAULT TRUE)",
+            ),
+            (
+                "drop table if exists personas",
+                "DROP TABLE IF EXISTS personas",
+            ),
+            ("truncate personas", "TRUNCATE TABLE personas"),
+            (
+                "alter table personas rename edad to anios",
+                "ALTER TABLE personas RENAME COLUMN edad TO anios",
+            ),
+            ("begin transaction", "BEGIN"),
+            ("commit", "COMMIT"),
+            ("rollback transaction", "ROLLBACK"),
+        ];
+
+        for (query, expected) in queries {
+            let statement = parse(query).unwrap();
+            assert_eq!(statement.to_string(), expected);
+            assert_eq!(parse(expected), Ok(statement));
+        }
+    }
+}
@@ -0,0 +1,225 @@
+use crate::{csv::Csv, errors::SqlError, table::Table, value::Value};
+
+/// Result of executing a query through a `Database`.
+///
+/// - `Rows`: result of a `SELECT` query, with the selected columns and one row of typed values for each selected register, in the same order as the columns.
+/// - `Affected`: result of an `INSERT`, `UPDATE` or `DELETE` query, with the number of registers affected.
+///
+/// # Examples
+///
+/// ```
+/// # use rustic_sql::QueryResult;
+/// # use rustic_sql::Value;
+/// let result = QueryResult::Rows {
+///     columns: vec!["id".to_string(), "name".to_string()],
+///     rows: vec![vec![Value::Integer(1), Value::Text("Alen".to_string())]],
+/// };
+///
+/// assert_eq!(result.to_csv(), vec!["id,name", "1,Alen"]);
+/// ```
+///
+#[derive(Debug, PartialEq)]
+pub enum QueryResult {
+    Rows {
+        columns: Vec<String>,
+        rows: Vec<Vec<Value>>,
+    },
+    Affected(usize),
+}
+
+impl QueryResult {
+    /// Transforms the result into a csv format where the first line is the column names and the following lines are the rows.
+    ///
+    /// Returns an empty vector for `Affected` results, because they don't show results in the console.
+    ///
+    pub fn to_csv(&self) -> Vec<String> {
+        match self {
+            QueryResult::Rows { columns, rows } => {
+                let csv = Csv::default();
+                let mut result = vec![csv.format_record(columns)];
+                for row in rows {
+                    result.push(csv.format_values(row));
+                }
+                result
+            }
+            QueryResult::Affected(_) => Vec::new(),
+        }
+    }
+
+    /// Formats the result to be shown in a console.
+    ///
+    /// `Rows` results are drawn as a table with a header and aligned columns, followed by the number of rows.
+    /// `Affected` results show the number of affected registers.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use rustic_sql::QueryResult;
+    /// # use rustic_sql::Value;
+    /// let result = QueryResult::Rows {
+    ///     columns: vec!["id".to_string(), "name".to_string()],
+    ///     rows: vec![vec![Value::Integer(1), Value::Text("Alen".to_string())]],
+    /// };
+    ///
+    /// assert_eq!(
+    ///     result.to_pretty(),
+    ///     vec![
+    ///         "+----+------+",
+    ///         "| id | name |",
+    ///         "+----+------+",
+    ///         "| 1  | Alen |",
+    ///         "+----+------+",
+    ///         "(1 row)",
+    ///     ]
+    /// );
+    /// ```
+    ///
+    pub fn to_pretty(&self) -> Vec<String> {
+        match self {
+            QueryResult::Rows { columns, rows } => {
+                let rows: Vec<Vec<String>> = rows
+                    .iter()
+                    .map(|row| row.iter().map(|v| v.to_string()).collect())
+                    .collect();
+
+                let mut widths: Vec<usize> = columns.iter().map(|c| c.chars().count()).collect();
+                for row in &rows {
+                    for (idx, value) in row.iter().enumerate() {
+                        if let Some(width) = widths.get_mut(idx) {
+                            *width = (*width).max(value.chars().count());
+                        }
+                    }
+                }
+
+                let separator = widths
+                    .iter()
+                    .map(|w| "-".repeat(w + 2))
+                    .collect::<Vec<String>>()
+                    .join("+");
+                let separator = format!("+{}+", separator);
+
+                let mut result = vec![separator.clone(), pretty_line(columns, &widths)];
+                result.push(separator.clone());
+                for row in &rows {
+                    result.push(pretty_line(row, &widths));
+                }
+                if !rows.is_empty() {
+                    result.push(separator);
+                }
+
+                match rows.len() {
+                    1 => result.push(String::from("(1 row)")),
+                    n => result.push(format!("({} rows)", n)),
+                }
+                result
+            }
+            QueryResult::Affected(1) => vec![String::from("1 row affected")],
+            QueryResult::Affected(n) => vec![format!("{} rows affected", n)],
+        }
+    }
+
+    /// Creates a `Rows` result from the registers of a table, taking the values in the given column order.
+    ///
+    /// If a column is not found in a register, returns an error.
+    ///
+    pub(crate) fn from_table(table: &Table, columns: &[String]) -> Result<Self, SqlError> {
+        let mut rows = Vec::new();
+
+        for register in &table.registers {
+            let mut row = Vec::new();
+            for col in columns {
+                let value = register.0.get(col).ok_or(SqlError::InvalidColumn)?;
+                row.push(value.clone());
+            }
+            rows.push(row);
+        }
+
+        Ok(QueryResult::Rows {
+            columns: columns.to_vec(),
+            rows,
+        })
+    }
+}
+
+fn pretty_line(values: &[String], widths: &[usize]) -> String {
+    let cells: Vec<String> = values
+        .iter()
+        .zip(widths)
+        .map(|(value, width)| {
+            let padding = width - value.chars().count();
+            format!(" {}{} ", value, " ".repeat(padding))
+        })
+        .collect();
+
+    format!("|{}|", cells.join("|"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::QueryResult;
+    use crate::value::Value;
+
+    #[test]
+    fn rows_to_csv() {
+        let result = QueryResult::Rows {
+            columns: vec![String::from("id"), String::from("nombre")],
+            rows: vec![
+                vec![Value::Integer(1), Value::Text(String::from("Juan"))],
+                vec![Value::Integer(2), Value::Text(String::from("Ana"))],
+            ],
+        };
+
+        assert_eq!(result.to_csv(), vec!["id,nombre", "1,Juan", "2,Ana"]);
+    }
+
+    #[test]
+    fn affected_to_csv() {
+        assert!(QueryResult::Affected(3).to_csv().is_empty());
+    }
+
+    #[test]
+    fn rows_to_pretty() {
+        let result = QueryResult::Rows {
+            columns: vec![String::from("id"), String::from("apellido")],
+            rows: vec![
+                vec![Value::Integer(1), Value::Text(String::from("Pérez"))],
+                vec![Value::Integer(20), Value::Text(String::from("López"))],
+            ],
+        };
+
+        assert_eq!(
+            result.to_pretty(),
+            vec![
+                "+----+----------+",
+                "| id | apellido |",
+                "+----+----------+",
+                "| 1  | Pérez    |",
+                "| 20 | López    |",
+                "+----+----------+",
+                "(2 rows)",
+            ]
+        );
+    }
+
+    #[test]
+    fn empty_rows_to_pretty() {
+        let result = QueryResult::Rows {
+            columns: vec![String::from("id")],
+            rows: vec![],
+        };
+
+        assert_eq!(
+            result.to_pretty(),
+            vec!["+----+", "| id |", "+----+", "(0 rows)"]
+        );
+    }
+
+    #[test]
+    fn affected_to_pretty() {
+        assert_eq!(QueryResult::Affected(1).to_pretty(), vec!["1 row affected"]);
+        assert_eq!(
+            QueryResult::Affected(0).to_pretty(),
+            vec!["0 rows affected"]
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use crate::errors::SqlError;
+use crate::{csv::Csv, errors::SqlError, value::Value};
 use std::collections::HashMap;
 
 /// Register struct
@@ -7,58 +7,80 @@ use std::collections::HashMap;
 ///
 /// # Fields
 ///
-/// * A `HashMap` containing the column name and the column value.
+/// * A `HashMap` containing the column name and the typed column value.
 ///
 /// # Examples
 ///
 /// ```
+/// # use rustic_sql::register::Register;
+/// # use std::collections::HashMap;
 /// let register = Register(HashMap::new());
 ///
 /// ```
 ///
 #[derive(Clone, Debug, PartialEq)]
-pub struct Register(pub HashMap<String, String>);
+pub struct Register(pub HashMap<String, Value>);
 
 impl Register {
+    /// Creates a register from the fields of a record of a table file.
+    ///
+    /// The fields are typed with `Value::from_field`, and the columns without a field are `Null`.
+    ///
+    /// # Arguments
+    ///
+    /// * `columns` - The columns of the table.
+    /// * `fields` - The fields of the record, in the order of the columns.
+    ///
+    pub fn from_fields(columns: &[String], fields: &[Option<String>]) -> Self {
+        let values = columns
+            .iter()
+            .enumerate()
+            .map(|(idx, col)| (col.to_string(), Value::from_field(fields.get(idx))));
+        Register(values.collect())
+    }
+
     /// Converts a register to a csv format.
     /// The column order is given by the columns parameter.
     ///
-    /// Returns a string with the values of the register separated by commas.
+    /// Returns a string with the values of the register separated by the delimiter of the codec, quoted when needed.
     ///
     /// If a column is not found in the register, returns an error.
     ///
     /// # Examples
     ///
     /// ```
+    /// # use rustic_sql::SqlError;
+    /// # use rustic_sql::Value;
+    /// # use rustic_sql::csv::Csv;
+    /// # use rustic_sql::register::Register;
+    /// # use std::collections::HashMap;
     /// let register = Register(HashMap::new());
     /// let columns = vec!["id".to_string(), "name".to_string(), "age".to_string()];
-    /// let result = register.to_csv(&columns);
+    /// let result = register.to_csv(&columns, &Csv::default());
     ///
     /// assert_eq!(result, Err(SqlError::Error));
     ///
     ///
     /// let mut table = HashMap::new();
-    /// table.insert("id".to_string(), "1".to_string());
-    /// table.insert("name".to_string(), "Alen".to_string());
-    /// table.insert("age".to_string(), "25".to_string());
+    /// table.insert("id".to_string(), Value::Integer(1));
+    /// table.insert("name".to_string(), Value::Text("Alen".to_string()));
+    /// table.insert("age".to_string(), Value::Integer(25));
     ///
     /// let register = Register(table);
     /// let columns = vec!["id".to_string(), "name".to_string(), "age".to_string()];
-    /// let result = register.to_csv(&columns);
+    /// let result = register.to_csv(&columns, &Csv::default());
     ///
     /// assert_eq!(result, Ok("1,Alen,25".to_string()));
     /// ```
     ///
-    pub fn to_csv(&self, columns: &Vec<String>) -> Result<String, SqlError> {
+    pub fn to_csv(&self, columns: &Vec<String>, csv: &Csv) -> Result<String, SqlError> {
         let mut values = Vec::new();
 
         for col in columns {
             let value = self.0.get(col).ok_or(SqlError::Error)?;
-            values.push(value.to_string());
+            values.push(value.clone());
         }
 
-        let csv = values.join(",");
-
-        Ok(csv)
+        Ok(csv.format_values(&values))
     }
 }
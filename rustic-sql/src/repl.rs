@@ -0,0 +1,233 @@
+use crate::{database::Database, errors::SqlError, script::split_complete};
+use std::io::{BufRead, Write};
+
+const PROMPT: &str = "rustic-sql> ";
+const CONTINUATION_PROMPT: &str = "       ...> ";
+
+/// Interactive shell over a `Database`.
+///
+/// Reads statements line by line from the input, executing them when they are terminated by `;`, so a statement can span several lines.
+/// A `;` inside a quoted string does not terminate the statement.
+/// Lines starting with `.` (when no statement is pending) are meta-commands:
+///
+/// - `.tables`: lists the tables of the database.
+/// - `.schema <table>`: shows the columns of a table, with their declared types if the table has a schema.
+/// - `.help`: lists the meta-commands.
+/// - `.quit` / `.exit`: ends the session.
+///
+/// # Examples
+///
+/// ```
+/// # use rustic_sql::Database;
+/// # use rustic_sql::repl::Repl;
+/// let db = Database::open("tablas").unwrap();
+/// let input = "SELECT nombre\nFROM clientes;\n.quit\n".as_bytes();
+/// let mut output = Vec::new();
+///
+/// Repl::new(&db).run(input, &mut output).unwrap();
+/// ```
+///
+pub struct Repl<'a> {
+    db: &'a Database,
+}
+
+/// Outcome of a meta-command, telling the shell whether to keep reading input.
+#[derive(Debug, PartialEq)]
+enum MetaCommand {
+    Continue,
+    Quit,
+}
+
+impl<'a> Repl<'a> {
+    /// Creates a new shell over the given database.
+    pub fn new(db: &'a Database) -> Self {
+        Self { db }
+    }
+
+    /// Runs the shell until the input ends or a `.quit` meta-command is read.
+    ///
+    /// Results and errors of each statement are written to the output, so an invalid statement does not end the session.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The reader where the statements and meta-commands are read from.
+    /// * `output` - The writer where the prompts and results are written to.
+    ///
+    pub fn run<R: BufRead, W: Write>(&self, input: R, output: &mut W) -> Result<(), SqlError> {
+        let mut statement = String::new();
+        let mut lines = input.lines();
+
+        loop {
+            let prompt = if statement.is_empty() {
+                PROMPT
+            } else {
+                CONTINUATION_PROMPT
+            };
+            write!(output, "{}", prompt).map_err(SqlError::from)?;
+            output.flush().map_err(SqlError::from)?;
+
+            let line = match lines.next() {
+                Some(line) => line.map_err(SqlError::from)?,
+                None => break,
+            };
+            let trimmed = line.trim();
+
+            if statement.is_empty() && trimmed.starts_with('.') {
+                if self.meta_command(trimmed, output)? == MetaCommand::Quit {
+                    return Ok(());
+                }
+                continue;
+            }
+
+            if trimmed.is_empty() && statement.is_empty() {
+                continue;
+            }
+
+            statement.push_str(&line);
+            statement.push('\n');
+
+            let (complete, rest) = split_complete(&statement);
+            for query in complete {
+                self.execute(&query, output)?;
+            }
+            statement = if rest.trim().is_empty() {
+                String::new()
+            } else {
+                rest
+            };
+        }
+
+        writeln!(output).map_err(SqlError::from)?;
+        if !statement.trim().is_empty() {
+            self.execute(&statement, output)?;
+        }
+
+        Ok(())
+    }
+
+    fn execute<W: Write>(&self, statement: &str, output: &mut W) -> Result<(), SqlError> {
+        let lines = match self.db.execute(statement) {
+            Ok(result) => result.to_pretty(),
+            Err(e) => e.render(statement),
+        };
+
+        for line in lines {
+            writeln!(output, "{}", line).map_err(SqlError::from)?;
+        }
+        Ok(())
+    }
+
+    fn meta_command<W: Write>(&self, line: &str, output: &mut W) -> Result<MetaCommand, SqlError> {
+        let mut parts = line.split_whitespace();
+        let command = parts.next().unwrap_or_default();
+        let argument = parts.next();
+
+        let lines = match (command, argument) {
+            (".quit" | ".exit", _) => return Ok(MetaCommand::Quit),
+            (".tables", _) => match self.db.tables() {
+                Ok(tables) => tables,
+                Err(e) => vec![e.to_string()],
+            },
+            (".schema", Some(table)) => match self.db.schema(table) {
+                Ok(Some(schema)) => schema.columns.iter().map(|c| c.to_string()).collect(),
+                Ok(None) => match self.db.columns(table) {
+                    Ok(columns) => columns,
+                    Err(e) => vec![e.to_string()],
+                },
+                Err(e) => vec![e.to_string()],
+            },
+            (".schema", None) => vec![String::from("Usage: .schema <table>")],
+            (".help", _) => vec![
+                String::from(".tables          List the tables"),
+                String::from(".schema <table>  Show the columns of a table"),
+                String::from(".quit            Exit the shell"),
+            ],
+            _ => vec![format!(
+                "Unknown command: {}. Enter .help for the list of commands",
+                command
+            )],
+        };
+
+        for line in lines {
+            writeln!(output, "{}", line).map_err(SqlError::from)?;
+        }
+        Ok(MetaCommand::Continue)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Repl;
+    use crate::database::Database;
+
+    fn run(input: &str) -> String {
+        let db = Database::open("tablas").unwrap();
+        let mut output = Vec::new();
+        Repl::new(&db).run(input.as_bytes(), &mut output).unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn multi_line_statement() {
+        let output = run("SELECT nombre\nFROM testing\nWHERE edad > 35;\n");
+
+        assert_eq!(
+            output,
+            "rustic-sql>        ...>        ...> +--------+\n\
+             | nombre |\n\
+             +--------+\n\
+             | Carlos |\n\
+             +--------+\n\
+             (1 row)\n\
+             rustic-sql> \n"
+        );
+    }
+
+    #[test]
+    fn terminator_inside_quotes() {
+        let output = run("SELECT edad FROM testing WHERE nombre = ';\n';\nSELECT edad FROM testing WHERE nombre = 'Ana';\n");
+
+        assert!(output.contains("rustic-sql>        ...> +------+\n| edad |\n+------+\n(0 rows)\n"));
+        assert!(output.contains("| 18   |"));
+    }
+
+    #[test]
+    fn quit_stops_reading() {
+        let output = run(".quit\nSELECT * FROM testing;\n");
+        assert_eq!(output, "rustic-sql> ");
+    }
+
+    #[test]
+    fn tables_and_schema() {
+        let output = run(".tables\n.schema testing\n.schema\n.exit\n");
+
+        assert!(output.contains("clientes\n"));
+        assert!(output.contains("ordenes\n"));
+        assert!(output.contains("nombre\napellido\nedad\n"));
+        assert!(output.contains("Usage: .schema <table>\n"));
+    }
+
+    #[test]
+    fn error_does_not_end_session() {
+        let output =
+            run("SELECT * FROM no_existe;\nSELECT edad FROM testing WHERE nombre = 'Ana';\n");
+
+        assert!(output.contains("[InvalidTable]: [Table 'no_existe' does not exist]"));
+        assert!(output.contains("| 18   |"));
+    }
+
+    #[test]
+    fn error_with_caret() {
+        let output = run("SELECT edad FROM testing LIMIT dos;
+");
+
+        assert!(output.contains("SELECT edad FROM testing LIMIT dos\n"));
+        assert!(output.contains("\n                               ^^^\n"));
+    }
+
+    #[test]
+    fn unknown_meta_command() {
+        let output = run(".foo\n");
+        assert!(output.contains("Unknown command: .foo"));
+    }
+}
@@ -0,0 +1,364 @@
+use crate::{
+    clauses::expression::parse_constant,
+    errors::SqlError,
+    lexer::{tokenize, unexpected, Token, TokenKind},
+    value::Value,
+    wal::Operation,
+};
+use std::{fmt::Display, fs, path::Path};
+
+/// Enum for the types that can be declared for a column in `CREATE TABLE`.
+///
+/// - `Integer`: `INT` or `INTEGER`.
+/// - `Float`: `FLOAT`, `REAL` or `DOUBLE`.
+/// - `Text`: `TEXT`, `VARCHAR` or `CHAR`.
+/// - `Boolean`: `BOOL` or `BOOLEAN`.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub enum ColumnType {
+    Integer,
+    Float,
+    Text,
+    Boolean,
+}
+
+impl ColumnType {
+    /// Creates a `ColumnType` from the name of the type.
+    pub fn new_from_token(token: &str) -> Result<Self, SqlError> {
+        match token.to_uppercase().as_str() {
+            "INT" | "INTEGER" => Ok(ColumnType::Integer),
+            "FLOAT" | "REAL" | "DOUBLE" => Ok(ColumnType::Float),
+            "TEXT" | "VARCHAR" | "CHAR" => Ok(ColumnType::Text),
+            "BOOL" | "BOOLEAN" => Ok(ColumnType::Boolean),
+            _ => Err(SqlError::InvalidSyntax),
+        }
+    }
+
+    /// Converts a value to this type.
+    ///
+    /// An `Integer` can be stored in a `Float` column and any value can be stored in a `Text` column, but otherwise the value must already be of this type.
+    /// `Null` is accepted by every type.
+    ///
+    /// Returns `SqlError::InvalidType` if the value can't be stored in a column of this type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use rustic_sql::SqlError;
+    /// # use rustic_sql::Value;
+    /// # use rustic_sql::schema::ColumnType;
+    /// assert_eq!(ColumnType::Float.coerce(Value::Integer(3)), Ok(Value::Float(3.0)));
+    /// assert_eq!(ColumnType::Text.coerce(Value::Integer(3)), Ok(Value::Text("3".to_string())));
+    /// assert_eq!(ColumnType::Integer.coerce(Value::Text("a".to_string())), Err(SqlError::InvalidType));
+    /// ```
+    ///
+    pub fn coerce(&self, value: Value) -> Result<Value, SqlError> {
+        match (self, value) {
+            (_, Value::Null) => Ok(Value::Null),
+            (ColumnType::Integer, Value::Integer(integer)) => Ok(Value::Integer(integer)),
+            (ColumnType::Float, Value::Integer(integer)) => Ok(Value::Float(integer as f64)),
+            (ColumnType::Float, Value::Float(float)) => Ok(Value::Float(float)),
+            (ColumnType::Boolean, Value::Boolean(boolean)) => Ok(Value::Boolean(boolean)),
+            (ColumnType::Text, Value::Text(text)) => Ok(Value::Text(text)),
+            (ColumnType::Text, value) => Ok(Value::Text(value.to_string())),
+            _ => Err(SqlError::InvalidType),
+        }
+    }
+}
+
+impl Display for ColumnType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ColumnType::Integer => write!(f, "INTEGER"),
+            ColumnType::Float => write!(f, "FLOAT"),
+            ColumnType::Text => write!(f, "TEXT"),
+            ColumnType::Boolean => write!(f, "BOOLEAN"),
+        }
+    }
+}
+
+/// Struct that represents the declaration of a column in `CREATE TABLE`.
+///
+/// # Fields
+///
+/// * `name` - The name of the column.
+/// * `column_type` - The declared type of the column.
+/// * `not_null` - Whether the column rejects `Null` values.
+/// * `default` - The value used when an `INSERT` doesn't give a value for the column.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColumnDef {
+    pub name: String,
+    pub column_type: ColumnType,
+    pub not_null: bool,
+    pub default: Option<Value>,
+}
+
+impl ColumnDef {
+    /// Creates and returns a new `ColumnDef` from the tokens read by the lexer, starting at the position `pos`.
+    ///
+    /// The tokens should be in the following order: `name`, `type`, and optionally `NOT`, `NULL` and `DEFAULT`, `value`.
+    /// Stops at the first token that can't continue the declaration, leaving `pos` there.
+    ///
+    /// Returns `SqlError::UnexpectedToken`, with its offset, for a token that doesn't follow this syntax, and `SqlError::InvalidType` if the default value doesn't match the type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use rustic_sql::Value;
+    /// # use rustic_sql::lexer;
+    /// # use rustic_sql::schema::ColumnDef;
+    /// # use rustic_sql::schema::ColumnType;
+    /// let tokens = lexer::tokenize("edad INT NOT NULL DEFAULT 18").unwrap();
+    /// let mut pos = 0;
+    /// let column = ColumnDef::new_from_tokens(&tokens, &mut pos).unwrap();
+    ///
+    /// assert_eq!(column, ColumnDef {
+    ///     name: "edad".to_string(),
+    ///     column_type: ColumnType::Integer,
+    ///     not_null: true,
+    ///     default: Some(Value::Integer(18)),
+    /// });
+    /// ```
+    ///
+    pub fn new_from_tokens(tokens: &[Token], pos: &mut usize) -> Result<Self, SqlError> {
+        let name = tokens
+            .get(*pos)
+            .filter(|t| t.kind == TokenKind::Identifier)
+            .map(|t| t.text.clone())
+            .ok_or_else(|| unexpected(tokens, *pos))?;
+        *pos += 1;
+        let column_type = tokens
+            .get(*pos)
+            .filter(|t| t.kind == TokenKind::Identifier)
+            .and_then(|t| ColumnType::new_from_token(&t.text).ok())
+            .ok_or_else(|| unexpected(tokens, *pos))?;
+        *pos += 1;
+
+        let is_keyword =
+            |pos: usize, keyword: &str| tokens.get(pos).is_some_and(|t| t.is_keyword(keyword));
+        let mut not_null = false;
+        let mut default = None;
+        loop {
+            if is_keyword(*pos, "NOT") {
+                if !is_keyword(*pos + 1, "NULL") {
+                    return Err(unexpected(tokens, *pos + 1));
+                }
+                not_null = true;
+                *pos += 2;
+            } else if is_keyword(*pos, "DEFAULT") {
+                *pos += 1;
+                default = Some(column_type.coerce(parse_constant(tokens, pos)?)?);
+            } else {
+                break;
+            }
+        }
+
+        Ok(Self {
+            name,
+            column_type,
+            not_null,
+            default,
+        })
+    }
+
+    /// Converts a value to the type of the column, checking the `NOT NULL` constraint.
+    ///
+    /// Returns `SqlError::InvalidType` if the value doesn't match the type and `SqlError::InvalidValue` if it is `Null` in a `NOT NULL` column.
+    ///
+    pub fn validate(&self, value: Value) -> Result<Value, SqlError> {
+        let value = self.column_type.coerce(value)?;
+        if self.not_null && value.is_null() {
+            return Err(SqlError::InvalidValue);
+        }
+        Ok(value)
+    }
+}
+
+impl Display for ColumnDef {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.name, self.column_type)?;
+        if self.not_null {
+            write!(f, " NOT NULL")?;
+        }
+        match &self.default {
+            Some(Value::Text(text)) => write!(f, " DEFAULT '{}'", text.replace('\'', "''")),
+            Some(value) => write!(f, " DEFAULT {}", value),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Struct that represents the declared columns of a table.
+///
+/// The schema of a table is stored next to its csv file, in a `.schema` file with one column declaration per line.
+/// Tables created without `CREATE TABLE` don't have a schema, and their values are not validated.
+///
+/// # Fields
+///
+/// * `columns` - The declarations of the columns, in the same order as in the csv header.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct Schema {
+    pub columns: Vec<ColumnDef>,
+}
+
+impl Schema {
+    /// Returns the name of the schema file of a table.
+    pub fn file_name(table_name: &str) -> String {
+        table_name.to_string() + ".schema"
+    }
+
+    /// Returns the path of the schema file of a table.
+    pub fn path(folder_path: &str, table_name: &str) -> String {
+        folder_path.to_string() + "/" + &Schema::file_name(table_name)
+    }
+
+    /// Loads the schema of a table.
+    ///
+    /// Returns `None` if the table doesn't have a schema file.
+    ///
+    /// # Arguments
+    ///
+    /// * `folder_path` - The path to the folder where the table is located.
+    /// * `table_name` - The name of the table.
+    ///
+    pub fn load(folder_path: &str, table_name: &str) -> Result<Option<Self>, SqlError> {
+        let path = Schema::path(folder_path, table_name);
+        if !Path::new(&path).is_file() {
+            return Ok(None);
+        }
+        let content = fs::read_to_string(&path).map_err(SqlError::from)?;
+
+        let mut columns = Vec::new();
+        for line in content.lines().filter(|l| !l.trim().is_empty()) {
+            let tokens = tokenize(line)?;
+            let mut pos = 0;
+            columns.push(ColumnDef::new_from_tokens(&tokens, &mut pos)?);
+            if pos != tokens.len() {
+                return Err(unexpected(&tokens, pos));
+            }
+        }
+
+        Ok(Some(Self { columns }))
+    }
+
+    /// Records the schema file of a table in a write-ahead log operation, which writes it with the other changes of the operation when it is committed.
+    pub fn save(&self, operation: &mut Operation, table_name: &str) -> Result<(), SqlError> {
+        let lines: Vec<String> = self.columns.iter().map(|c| c.to_string()).collect();
+        operation.replace_file(&Schema::file_name(table_name), &lines)
+    }
+
+    /// Returns the declaration of the given column.
+    pub fn column(&self, name: &str) -> Result<&ColumnDef, SqlError> {
+        self.columns
+            .iter()
+            .find(|c| c.name == name)
+            .ok_or_else(|| SqlError::ColumnNotFound(name.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ColumnDef, ColumnType, Schema};
+    use crate::{
+        errors::SqlError, lexer::tokenize, utils::temp_folder, value::Value, wal::Operation,
+    };
+    use std::fs;
+
+    #[test]
+    fn column_types() {
+        assert_eq!(ColumnType::new_from_token("int"), Ok(ColumnType::Integer));
+        assert_eq!(ColumnType::new_from_token("VARCHAR"), Ok(ColumnType::Text));
+        assert_eq!(
+            ColumnType::new_from_token("DATE"),
+            Err(SqlError::InvalidSyntax)
+        );
+    }
+
+    #[test]
+    fn coerce_values() {
+        assert_eq!(
+            ColumnType::Float.coerce(Value::Integer(2)),
+            Ok(Value::Float(2.0))
+        );
+        assert_eq!(
+            ColumnType::Integer.coerce(Value::Float(2.5)),
+            Err(SqlError::InvalidType)
+        );
+        assert_eq!(
+            ColumnType::Boolean.coerce(Value::Integer(1)),
+            Err(SqlError::InvalidType)
+        );
+        assert_eq!(
+            ColumnType::Text.coerce(Value::Float(2.5)),
+            Ok(Value::Text(String::from("2.5")))
+        );
+        assert_eq!(ColumnType::Integer.coerce(Value::Null), Ok(Value::Null));
+    }
+
+    fn column(query: &str) -> Result<ColumnDef, SqlError> {
+        let tokens = tokenize(query).unwrap();
+        let mut pos = 0;
+        ColumnDef::new_from_tokens(&tokens, &mut pos)
+    }
+
+    #[test]
+    fn column_from_tokens() {
+        let column = column("precio REAL DEFAULT 1").unwrap();
+
+        assert_eq!(
+            column,
+            ColumnDef {
+                name: String::from("precio"),
+                column_type: ColumnType::Float,
+                not_null: false,
+                default: Some(Value::Float(1.0)),
+            }
+        );
+        assert_eq!(column.to_string(), "precio FLOAT DEFAULT 1.0");
+    }
+
+    #[test]
+    fn column_from_invalid_tokens() {
+        let unexpected = |token: &str, offset: usize| {
+            Err(SqlError::UnexpectedToken {
+                token: token.to_string(),
+                offset: Some(offset),
+            })
+        };
+        assert_eq!(column("id"), unexpected("", 2));
+        assert_eq!(column("id NUMBER"), unexpected("NUMBER", 3));
+        assert_eq!(column("id INT NOT"), unexpected("", 10));
+        assert_eq!(column("id INT DEFAULT a"), Err(SqlError::InvalidType));
+    }
+
+    #[test]
+    fn validate_not_null() {
+        let column = column("id INT NOT NULL").unwrap();
+
+        assert_eq!(column.validate(Value::Integer(1)), Ok(Value::Integer(1)));
+        assert_eq!(column.validate(Value::Null), Err(SqlError::InvalidValue));
+    }
+
+    #[test]
+    fn save_and_load() {
+        let folder = temp_folder("schema", &[]);
+
+        let schema = Schema {
+            columns: vec![
+                column("id INT NOT NULL").unwrap(),
+                column("nombre TEXT DEFAULT 'sin nombre'").unwrap(),
+            ],
+        };
+        let mut operation = Operation::start(&folder).unwrap();
+        schema.save(&mut operation, "personas").unwrap();
+        assert_eq!(Schema::load(&folder, "personas"), Ok(None));
+        operation.commit().unwrap();
+
+        assert_eq!(Schema::load(&folder, "personas"), Ok(Some(schema)));
+        assert_eq!(Schema::load(&folder, "otra"), Ok(None));
+
+        fs::remove_dir_all(&folder).unwrap();
+    }
+}
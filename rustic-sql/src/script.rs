@@ -0,0 +1,169 @@
+/// Splits a script into its statements, using `;` as the terminator.
+///
+/// A `;` inside a quoted string (`'...'` or `"..."`) does not end a statement, and `--` and `/* ... */` comments are removed.
+/// The text after the last `;` is also returned as a statement, unless it is empty.
+///
+/// The returned statements are trimmed and don't include the `;`.
+///
+/// # Examples
+///
+/// ```
+/// # use rustic_sql::script;
+/// let script = "INSERT INTO t (a) VALUES ('x;y');\nSELECT * FROM t";
+/// let statements = script::split_statements(script);
+///
+/// assert_eq!(statements, vec!["INSERT INTO t (a) VALUES ('x;y')", "SELECT * FROM t"]);
+/// ```
+///
+pub fn split_statements(script: &str) -> Vec<String> {
+    let (mut statements, rest) = split_complete(script);
+
+    if !rest.trim().is_empty() {
+        statements.push(rest.trim().to_string());
+    }
+    statements
+}
+
+/// Splits the statements of a script that are terminated by `;`.
+///
+/// Returns the complete statements and the remaining text after the last `;`, which may be a statement still being written.
+/// A `/* ... */` comment that is not closed yet is kept in the remaining text, so it is still a comment when the text is split again with more lines.
+///
+pub fn split_complete(script: &str) -> (Vec<String>, String) {
+    let mut statements = Vec::new();
+    let mut current = String::new();
+    let mut quote: Option<char> = None;
+    let mut in_comment = false;
+    let mut block_comment: Option<String> = None;
+    let mut chars = script.chars().peekable();
+
+    while let Some(char) = chars.next() {
+        if in_comment {
+            if char == '\n' {
+                in_comment = false;
+                current.push(char);
+            }
+            continue;
+        }
+        if let Some(comment) = block_comment.as_mut() {
+            if char == '*' && chars.peek() == Some(&'/') {
+                chars.next();
+                block_comment = None;
+                // The comment separates the tokens around it, like a space.
+                current.push(' ');
+            } else {
+                comment.push(char);
+            }
+            continue;
+        }
+
+        match quote {
+            Some(q) => {
+                if char == q {
+                    quote = None;
+                }
+                current.push(char);
+            }
+            None => match char {
+                '\'' | '"' => {
+                    quote = Some(char);
+                    current.push(char);
+                }
+                '-' if chars.peek() == Some(&'-') => in_comment = true,
+                '/' if chars.peek() == Some(&'*') => {
+                    chars.next();
+                    block_comment = Some(String::new());
+                }
+                ';' => {
+                    if !current.trim().is_empty() {
+                        statements.push(current.trim().to_string());
+                    }
+                    current.clear();
+                }
+                _ => current.push(char),
+            },
+        }
+    }
+    if let Some(comment) = block_comment {
+        current.push_str("/*");
+        current.push_str(&comment);
+    }
+
+    (statements, current)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{split_complete, split_statements};
+
+    #[test]
+    fn split_several_statements() {
+        let script = "SELECT * FROM a;\nDELETE FROM b WHERE id = 1;\n\nUPDATE c SET x = 1;";
+
+        assert_eq!(
+            split_statements(script),
+            vec![
+                "SELECT * FROM a",
+                "DELETE FROM b WHERE id = 1",
+                "UPDATE c SET x = 1"
+            ]
+        );
+    }
+
+    #[test]
+    fn last_statement_without_terminator() {
+        assert_eq!(
+            split_statements("SELECT * FROM a; SELECT * FROM b"),
+            vec!["SELECT * FROM a", "SELECT * FROM b"]
+        );
+    }
+
+    #[test]
+    fn terminator_inside_quotes() {
+        let script = "INSERT INTO t (a, b) VALUES ('x;y', 'it''s;'); SELECT \"a;b\" FROM t;";
+
+        assert_eq!(
+            split_statements(script),
+            vec![
+                "INSERT INTO t (a, b) VALUES ('x;y', 'it''s;')",
+                "SELECT \"a;b\" FROM t"
+            ]
+        );
+    }
+
+    #[test]
+    fn terminator_inside_comment() {
+        let script = "-- borra; todo\nDELETE FROM t;";
+        assert_eq!(split_statements(script), vec!["DELETE FROM t"]);
+    }
+
+    #[test]
+    fn terminator_inside_block_comment() {
+        let script = "/* borra; todo */DELETE FROM t;SELECT/* a;\nb */1 FROM t; SELECT '/*;*/'";
+        assert_eq!(
+            split_statements(script),
+            vec!["DELETE FROM t", "SELECT 1 FROM t", "SELECT '/*;*/'"]
+        );
+
+        // A comment that is not closed yet is kept, so the next lines are still part of it.
+        let (statements, rest) = split_complete("SELECT 1; /* sin; cerrar\n");
+        assert_eq!(statements, vec!["SELECT 1"]);
+        assert_eq!(rest, " /* sin; cerrar\n");
+        let (statements, rest) = split_complete(&(rest + "hasta acá; */ SELECT 2;"));
+        assert_eq!(statements, vec!["SELECT 2"]);
+        assert_eq!(rest, "");
+    }
+
+    #[test]
+    fn empty_statements_are_skipped() {
+        assert!(split_statements(" ;; \n ").is_empty());
+    }
+
+    #[test]
+    fn incomplete_statement() {
+        let (statements, rest) = split_complete("SELECT * FROM a; SELECT 'x;\n");
+
+        assert_eq!(statements, vec!["SELECT * FROM a"]);
+        assert_eq!(rest, " SELECT 'x;\n");
+    }
+}
@@ -0,0 +1,212 @@
+use crate::errors::SqlError;
+use std::{
+    collections::BTreeMap,
+    fs::{File, OpenOptions, TryLockError},
+    io::Write,
+    path::{Path, PathBuf},
+    thread,
+    time::{Duration, Instant},
+};
+
+/// Creates a file with the given lines and flushes it to disk.
+///
+/// Returns an error if the file already exists.
+///
+/// # Arguments
+///
+/// * `path` - The path of the file to create.
+/// * `lines` - The lines of the file, without their line breaks.
+///
+pub fn write_new_file<S: AsRef<str>>(path: &Path, lines: &[S]) -> Result<(), SqlError> {
+    let mut content = String::new();
+    for line in lines {
+        content.push_str(line.as_ref());
+        content.push('\n');
+    }
+
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(path)
+        .map_err(SqlError::from)?;
+    file.write_all(content.as_bytes()).map_err(SqlError::from)?;
+    file.sync_all().map_err(SqlError::from)
+}
+
+/// Flushes the entries of a folder to disk, so that the files created, renamed or removed in it survive a crash.
+///
+/// Folders can only be flushed on Unix, so on other systems it does nothing.
+///
+pub fn sync_folder(folder: &Path) -> Result<(), SqlError> {
+    if cfg!(unix) {
+        File::open(folder)
+            .and_then(|folder| folder.sync_all())
+            .map_err(SqlError::from)?;
+    }
+    Ok(())
+}
+
+/// Enum for the ways a table can be locked.
+///
+/// - `Shared`: For statements that only read the table. Many of them can hold the lock at the same time.
+/// - `Exclusive`: For statements that write the table. Only one of them can hold the lock, and no statement can hold a shared lock meanwhile.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LockMode {
+    Shared,
+    Exclusive,
+}
+
+/// Advisory lock on a table, shared by every process that uses the folder of the tables. The lock is released when it is dropped.
+///
+/// The lock is taken on a `.{table}.lock` file next to the table file instead of on the table file, because rewrites replace the table file with a new one.
+/// The lock files are kept after their tables are dropped, so that a process waiting for one never ends up with a lock that nobody else can see.
+///
+#[derive(Debug)]
+pub struct TableLock {
+    file: File,
+}
+
+impl Drop for TableLock {
+    fn drop(&mut self) {
+        let _ = self.file.unlock();
+    }
+}
+
+/// Returns the path of the file that holds the lock of a table.
+pub(crate) fn lock_path(folder_path: &str, table_name: &str) -> PathBuf {
+    Path::new(folder_path).join(format!(".{}.lock", table_name))
+}
+
+/// Locks a table, waiting for the other processes that hold a conflicting lock to release it.
+///
+/// Returns `SqlError::TableLocked` if the lock can't be taken before the timeout.
+///
+/// # Arguments
+///
+/// * `folder_path` - The folder of the tables.
+/// * `table_name` - The name of the table to lock.
+/// * `mode` - Whether the lock is shared or exclusive.
+/// * `timeout` - How long to wait for the lock. With `Duration::ZERO`, the lock is only tried once.
+///
+/// # Examples
+///
+/// ```
+/// # use rustic_sql::storage;
+/// # use rustic_sql::storage::LockMode;
+/// # use std::time::Duration;
+/// let lock = storage::lock_table("tablas", "clientes", LockMode::Shared, Duration::from_secs(1)).unwrap();
+/// assert!(storage::lock_table("tablas", "clientes", LockMode::Exclusive, Duration::ZERO).is_err());
+/// drop(lock);
+/// ```
+///
+pub fn lock_table(
+    folder_path: &str,
+    table_name: &str,
+    mode: LockMode,
+    timeout: Duration,
+) -> Result<TableLock, SqlError> {
+    let file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(lock_path(folder_path, table_name))
+        .map_err(SqlError::from)?;
+    let start = Instant::now();
+
+    loop {
+        let result = match mode {
+            LockMode::Shared => file.try_lock_shared(),
+            LockMode::Exclusive => file.try_lock(),
+        };
+        match result {
+            Ok(()) => return Ok(TableLock { file }),
+            Err(TryLockError::Error(e)) => return Err(SqlError::from(e)),
+            Err(TryLockError::WouldBlock) => {
+                let waited = start.elapsed();
+                if waited >= timeout {
+                    return Err(SqlError::TableLocked(table_name.to_string()));
+                }
+                thread::sleep((timeout - waited).min(Duration::from_millis(10)));
+            }
+        }
+    }
+}
+
+/// Locks several tables with `lock_table`, in the order of their names so that two statements that lock the same tables can't wait for each other forever.
+///
+/// A table that appears more than once is locked once, with the strongest of its modes.
+///
+/// # Arguments
+///
+/// * `folder_path` - The folder of the tables.
+/// * `tables` - The names of the tables to lock, with the mode of each one.
+/// * `timeout` - How long to wait for each lock.
+///
+pub fn lock_tables(
+    folder_path: &str,
+    tables: &[(String, LockMode)],
+    timeout: Duration,
+) -> Result<Vec<TableLock>, SqlError> {
+    let mut modes: BTreeMap<&str, LockMode> = BTreeMap::new();
+    for (table_name, mode) in tables {
+        let current = modes.entry(table_name).or_insert(*mode);
+        *current = (*current).max(*mode);
+    }
+
+    modes
+        .into_iter()
+        .map(|(table_name, mode)| lock_table(folder_path, table_name, mode, timeout))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{lock_table, lock_tables, LockMode};
+    use crate::{errors::SqlError, utils::temp_folder};
+    use std::{fs, thread, time::Duration};
+
+    #[test]
+    fn shared_and_exclusive_locks() {
+        let folder = temp_folder("locks", &[]);
+        let timeout = Duration::from_millis(50);
+
+        let reader = lock_table(&folder, "personas", LockMode::Shared, timeout).unwrap();
+        let other_reader = lock_table(&folder, "personas", LockMode::Shared, timeout).unwrap();
+        assert_eq!(
+            lock_table(&folder, "personas", LockMode::Exclusive, timeout).unwrap_err(),
+            SqlError::TableLocked(String::from("personas"))
+        );
+        assert!(lock_table(&folder, "ordenes", LockMode::Exclusive, timeout).is_ok());
+
+        drop(reader);
+        drop(other_reader);
+        let tables = [
+            (String::from("personas"), LockMode::Shared),
+            (String::from("ordenes"), LockMode::Shared),
+            (String::from("personas"), LockMode::Exclusive),
+        ];
+        let locks = lock_tables(&folder, &tables, timeout).unwrap();
+        assert_eq!(locks.len(), 2);
+        assert!(lock_table(&folder, "personas", LockMode::Shared, Duration::ZERO).is_err());
+        assert!(lock_table(&folder, "ordenes", LockMode::Shared, Duration::ZERO).is_ok());
+
+        // The lock is released while another thread waits for it.
+        thread::scope(|scope| {
+            let waiter = scope.spawn(|| {
+                lock_table(
+                    &folder,
+                    "personas",
+                    LockMode::Exclusive,
+                    Duration::from_secs(5),
+                )
+            });
+            thread::sleep(Duration::from_millis(20));
+            drop(locks);
+            assert!(waiter.join().unwrap().is_ok());
+        });
+
+        fs::remove_dir_all(&folder).unwrap();
+    }
+}
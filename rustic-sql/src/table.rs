@@ -9,6 +9,7 @@ use crate::register::Register;
 ///
 /// # Examples
 /// ```
+/// # use rustic_sql::table::Table;
 /// let table = Table::new();
 /// ```
 #[derive(Debug)]
@@ -26,3 +27,9 @@ impl Table {
         }
     }
 }
+
+impl Default for Table {
+    fn default() -> Self {
+        Self::new()
+    }
+}
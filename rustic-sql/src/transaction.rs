@@ -0,0 +1,269 @@
+use crate::{
+    errors::SqlError,
+    storage::{lock_path, lock_table, LockMode, TableLock},
+    wal::Operation,
+};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Duration,
+};
+
+/// Number of transactions started by this process, used to give each one a unique name.
+static TRANSACTIONS: AtomicUsize = AtomicUsize::new(0);
+
+/// Prefix of the names of the transactions, which are also the names of their staging folders.
+const TRANSACTION_PREFIX: &str = ".tx-";
+
+/// Struct that represents a transaction started with `BEGIN`.
+///
+/// The statements of the transaction run on shadow copies of their tables, kept in a staging folder inside the folder of the tables.
+/// Every table is copied, and locked with an exclusive lock, the first time a statement of the transaction uses it, so no other process can change it until the transaction ends.
+///
+/// `COMMIT` moves the copies of the written tables over the tables, in a single operation of the write-ahead log. The transaction is rolled back, discarding the copies, when it is dropped without a commit.
+///
+/// # Fields
+///
+/// * `folder_path` - The folder of the tables.
+/// * `name` - The unique name of the transaction. Its staging folder is `folder_path/name`.
+/// * `staged` - The tables that have a copy in the staging folder.
+/// * `written` - The tables that the transaction changed, which are the ones to commit.
+/// * `lock_timeout` - How long to wait for the lock of a table the first time it is used.
+/// * `locks` - The locks of the staged tables, held until the transaction ends.
+/// * `own_lock` - The lock of the transaction itself, which tells `recover_transactions` that it is still running.
+///
+#[derive(Debug)]
+pub struct Transaction {
+    folder_path: String,
+    name: String,
+    staged: Vec<String>,
+    written: Vec<String>,
+    lock_timeout: Duration,
+    locks: Vec<TableLock>,
+    own_lock: Option<TableLock>,
+}
+
+impl Transaction {
+    /// Starts a transaction on the tables of a folder, creating its staging folder.
+    ///
+    /// # Arguments
+    ///
+    /// * `folder_path` - The folder of the tables.
+    /// * `lock_timeout` - How long to wait for the lock of each table used by the transaction.
+    ///
+    pub fn begin(folder_path: &str, lock_timeout: Duration) -> Result<Self, SqlError> {
+        let name = format!(
+            "{}{}-{}",
+            TRANSACTION_PREFIX,
+            process::id(),
+            TRANSACTIONS.fetch_add(1, Ordering::Relaxed)
+        );
+        // The lock is taken before the staging folder exists, so recovery never takes a running transaction for an interrupted one.
+        let own_lock = lock_table(folder_path, &name, LockMode::Exclusive, Duration::ZERO)?;
+        let transaction = Self {
+            folder_path: folder_path.to_string(),
+            name,
+            staged: Vec::new(),
+            written: Vec::new(),
+            lock_timeout,
+            locks: Vec::new(),
+            own_lock: Some(own_lock),
+        };
+        fs::create_dir(transaction.staging_path()).map_err(SqlError::from)?;
+
+        Ok(transaction)
+    }
+
+    /// Returns the path of the staging folder, where the statements of the transaction run.
+    pub fn staging_path(&self) -> PathBuf {
+        Path::new(&self.folder_path).join(&self.name)
+    }
+
+    /// Copies the tables used by a statement to the staging folder, locking them, if they are not there yet.
+    ///
+    /// The tables that the statement locks with `LockMode::Exclusive` are the ones it writes, which will be committed.
+    /// Tables that don't exist are skipped, so the statement fails as it would outside of the transaction.
+    ///
+    /// # Arguments
+    ///
+    /// * `tables` - The tables used by the statement, with the lock that each one needs.
+    ///
+    pub fn stage(&mut self, tables: &[(String, LockMode)]) -> Result<(), SqlError> {
+        for (table_name, mode) in tables {
+            if !self.staged.contains(table_name) {
+                self.stage_table(table_name)?;
+            }
+            if *mode == LockMode::Exclusive && !self.written.contains(table_name) {
+                self.written.push(table_name.to_string());
+            }
+        }
+        Ok(())
+    }
+
+    fn stage_table(&mut self, table_name: &str) -> Result<(), SqlError> {
+        let lock = lock_table(
+            &self.folder_path,
+            table_name,
+            LockMode::Exclusive,
+            self.lock_timeout,
+        )?;
+
+        for extension in ["csv", "schema"] {
+            let file_name = format!("{}.{}", table_name, extension);
+            let source = Path::new(&self.folder_path).join(&file_name);
+            if source.is_file() {
+                fs::copy(source, self.staging_path().join(&file_name)).map_err(SqlError::from)?;
+            }
+        }
+
+        self.locks.push(lock);
+        self.staged.push(table_name.to_string());
+        Ok(())
+    }
+
+    /// Makes the changes of the transaction visible, moving the copies of the written tables over the tables.
+    ///
+    /// The copies are recorded in a `wal::Operation` before any table is replaced.
+    /// If the process stops in the middle of the commit, `wal::recover_log` finishes replacing the tables, so either all or none of the changes are kept.
+    ///
+    pub fn commit(self) -> Result<(), SqlError> {
+        let mut operation = Operation::start(&self.folder_path)?;
+        for table_name in &self.written {
+            let copy = self.staging_path().join(format!("{}.csv", table_name));
+            operation.replace_with_file(table_name, &copy)?;
+        }
+        operation.commit()
+    }
+}
+
+impl Drop for Transaction {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(self.staging_path());
+        self.locks.clear();
+        self.own_lock.take();
+        let _ = fs::remove_file(lock_path(&self.folder_path, &self.name));
+    }
+}
+
+/// Rolls back the transactions that a process left in a folder when it stopped, removing their staging folders.
+///
+/// A transaction that was committing already moved its copies to the write-ahead log, where `wal::recover_log` finds them.
+/// Transactions that are still running hold their own lock, so they are not touched.
+///
+/// Returns the number of recovered transactions.
+///
+/// # Arguments
+///
+/// * `folder_path` - The folder of the tables.
+///
+pub fn recover_transactions(folder_path: &str) -> Result<usize, SqlError> {
+    let mut recovered = 0;
+
+    for entry in fs::read_dir(folder_path).map_err(SqlError::from)? {
+        let entry = entry.map_err(SqlError::from)?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        if !name.starts_with(TRANSACTION_PREFIX) || !entry.path().is_dir() {
+            continue;
+        }
+        let lock = match lock_table(folder_path, &name, LockMode::Exclusive, Duration::ZERO) {
+            Ok(lock) => lock,
+            Err(SqlError::TableLocked(_)) => continue,
+            Err(e) => return Err(e),
+        };
+
+        fs::remove_dir_all(entry.path()).map_err(SqlError::from)?;
+        drop(lock);
+        fs::remove_file(lock_path(folder_path, &name)).map_err(SqlError::from)?;
+        recovered += 1;
+    }
+
+    Ok(recovered)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{recover_transactions, Transaction};
+    use crate::{
+        storage::{lock_table, LockMode},
+        utils::temp_folder,
+    };
+    use std::{fs, path::Path, time::Duration};
+
+    fn stage_and_write(transaction: &mut Transaction) {
+        let tables = [
+            (String::from("personas"), LockMode::Exclusive),
+            (String::from("ordenes"), LockMode::Shared),
+        ];
+        transaction.stage(&tables).unwrap();
+        fs::write(transaction.staging_path().join("personas.csv"), "id\n2\n").unwrap();
+    }
+
+    #[test]
+    fn commit_and_rollback() {
+        let folder = temp_folder("transaction", &[]);
+        let table = Path::new(&folder).join("personas.csv");
+        fs::write(&table, "id\n1\n").unwrap();
+        fs::write(Path::new(&folder).join("ordenes.csv"), "id\n").unwrap();
+
+        let mut transaction = Transaction::begin(&folder, Duration::ZERO).unwrap();
+        stage_and_write(&mut transaction);
+        assert!(lock_table(&folder, "ordenes", LockMode::Shared, Duration::ZERO).is_err());
+        drop(transaction);
+        assert_eq!(fs::read_to_string(&table).unwrap(), "id\n1\n");
+
+        let mut transaction = Transaction::begin(&folder, Duration::ZERO).unwrap();
+        stage_and_write(&mut transaction);
+        transaction.commit().unwrap();
+        assert_eq!(fs::read_to_string(&table).unwrap(), "id\n2\n");
+        assert!(lock_table(&folder, "ordenes", LockMode::Exclusive, Duration::ZERO).is_ok());
+
+        // Only the tables, the locks of the tables and the empty log folder are left.
+        let mut names: Vec<String> = fs::read_dir(&folder)
+            .unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().to_string())
+            .collect();
+        names.sort();
+        assert_eq!(
+            names,
+            [
+                ".ordenes.lock",
+                ".personas.lock",
+                ".wal",
+                "ordenes.csv",
+                "personas.csv"
+            ]
+        );
+
+        fs::remove_dir_all(&folder).unwrap();
+    }
+
+    #[test]
+    fn recover_interrupted_transactions() {
+        let folder = temp_folder("recover_transactions", &[]);
+        let table = Path::new(&folder).join("personas.csv");
+        fs::write(&table, "id\n1\n").unwrap();
+        fs::write(Path::new(&folder).join("ordenes.csv"), "id\n").unwrap();
+
+        let mut running = Transaction::begin(&folder, Duration::ZERO).unwrap();
+        stage_and_write(&mut running);
+        assert_eq!(recover_transactions(&folder), Ok(0));
+        drop(running);
+
+        // A transaction of a process that stopped, which doesn't hold its lock anymore.
+        let mut stopped = Transaction::begin(&folder, Duration::ZERO).unwrap();
+        stage_and_write(&mut stopped);
+        let staging_path = stopped.staging_path();
+        stopped.locks.clear();
+        stopped.own_lock.take();
+        std::mem::forget(stopped);
+
+        assert_eq!(recover_transactions(&folder), Ok(1));
+        assert_eq!(fs::read_to_string(&table).unwrap(), "id\n1\n");
+        assert!(!staging_path.exists());
+        assert_eq!(recover_transactions(&folder), Ok(0));
+
+        fs::remove_dir_all(&folder).unwrap();
+    }
+}
@@ -1,4 +1,4 @@
-use crate::{errors::SqlError, table::Table};
+use crate::{csv::Csv, errors::SqlError, table::Table};
 use std::{fs, path::Path};
 
 /// Searches for the file given in the folder path, returns true if the file is found.
@@ -6,8 +6,9 @@ use std::{fs, path::Path};
 /// # Examples
 ///
 /// ```
-/// let folder_path = "tables";
-/// let file_name = "clients.csv";
+/// # use rustic_sql::utils;
+/// let folder_path = "tablas";
+/// let file_name = "clientes.csv";
 /// let result = utils::find_file_in_folder(folder_path, file_name);
 /// assert_eq!(result, true);
 /// ```
@@ -33,127 +34,65 @@ pub fn find_file_in_folder(folder_path: &str, file_name: &str) -> bool {
 /// # Examples
 ///
 /// ```
+/// # use rustic_sql::csv::Csv;
+/// # use rustic_sql::register::Register;
+/// # use rustic_sql::table::Table;
+/// # use rustic_sql::utils;
+/// # use rustic_sql::Value;
+/// # use std::collections::HashMap;
+/// let register = |id: i64, name: &str, age: i64| {
+///     Register(HashMap::from([
+///         ("id".to_string(), Value::Integer(id)),
+///         ("name".to_string(), Value::Text(name.to_string())),
+///         ("age".to_string(), Value::Integer(age)),
+///     ]))
+/// };
 /// let table = Table {
-///     name: "clients".to_string(),
 ///     columns: vec!["id".to_string(), "name".to_string(), "age".to_string()],
-///     registers: vec![
-///         Register {values: vec!["1".to_string(), "Alen".to_string(), "30".to_string()]},
-///         Register {values: vec!["2".to_string(), "Emily".to_string(), "25".to_string()]},
-///     ],
+///     registers: vec![register(1, "Alen", 30), register(2, "Emily", 25)],
 /// };
 ///
 /// let column_order = vec!["id".to_string(), "name".to_string(), "age".to_string()];
-/// let result = utils::table_to_csv(&table, &column_order);
+/// let result = utils::table_to_csv(&table, &column_order, &Csv::default()).unwrap();
 /// assert_eq!(result, vec!["id,name,age", "1,Alen,30", "2,Emily,25"]);
+///
+/// let result = utils::table_to_csv(&table, &column_order, &Csv::new(';').unwrap()).unwrap();
+/// assert_eq!(result, vec!["id;name;age", "1;Alen;30", "2;Emily;25"]);
 /// ```
 ///
-pub fn table_to_csv(table: &Table, column_order: &Vec<String>) -> Result<Vec<String>, SqlError> {
+pub fn table_to_csv(
+    table: &Table,
+    column_order: &Vec<String>,
+    csv: &Csv,
+) -> Result<Vec<String>, SqlError> {
     let mut result: Vec<String> = Vec::new();
 
-    result.push(column_order.join(","));
+    result.push(csv.format_record(column_order));
 
     for register in &table.registers {
-        let register_csv = register.to_csv(column_order)?;
+        let register_csv = register.to_csv(column_order, csv)?;
         result.push(register_csv);
     }
 
     Ok(result)
 }
 
-/// Returns true if the token can be converted to an i32 value.
+/// Creates an empty folder for a test in the temporary directory and copies the given tables of the `tablas` folder into it.
+/// Returns the path to the folder.
 ///
-/// # Examples
-///
-/// ```
-/// let token = "123";
-/// let result = utils::is_number(token);
-/// assert_eq!(result, true);
+/// # Arguments
 ///
-/// let token = "hola"
-/// let result = utils::is_number(token);
-/// assert_eq!(result, false);
-/// ```
+/// * `name` - A name for the folder, unique among the tests.
+/// * `tables` - The names of the tables to copy, without the `.csv` extension.
 ///
-pub fn is_number(token: &str) -> bool {
-    token.parse::<i32>().is_ok()
-}
-
-/// Returns true if the token is equal to "AND".
-pub fn is_and(token: &str) -> bool {
-    token == "AND"
-}
-
-/// Returns true if the token is equal to "OR".
-pub fn is_or(token: &str) -> bool {
-    token == "OR"
-}
-
-/// Returns true if the token is equal to "NOT".
-pub fn is_not(token: &str) -> bool {
-    token == "NOT"
-}
-
-/// Returns true if the token is equal to "(".
-pub fn is_left_paren(token: &str) -> bool {
-    token == "("
-}
-
-/// Returns true if the token is equal to ")".
-pub fn is_right_paren(token: &str) -> bool {
-    token == ")"
-}
-
-/// Returns true if the token is equal to "WHERE".
-pub fn is_where(token: &str) -> bool {
-    token == "WHERE"
-}
-
-/// Returns true if the token is equal to "SELECT".
-pub fn is_select(token: &str) -> bool {
-    token == "SELECT"
-}
-
-/// Returns true if the token is equal to "UPDATE".
-pub fn is_update(token: &str) -> bool {
-    token == "UPDATE"
-}
-
-/// Returns true if the token is equal to "INSERT".
-pub fn is_insert(token: &str) -> bool {
-    token == "INSERT"
-}
-
-/// Returns true if the token is equal to "INTO".
-pub fn is_into(token: &str) -> bool {
-    token == "INTO"
-}
-
-/// Returns true if the token is equal to "FROM".
-pub fn is_from(token: &str) -> bool {
-    token == "FROM"
-}
-
-/// Returns true if the token is equal to "ORDER".
-pub fn is_order(token1: &str) -> bool {
-    token1 == "ORDER"
-}
-
-/// Returns true if the token is equal to "BY".
-pub fn is_by(token1: &str) -> bool {
-    token1 == "BY"
-}
-
-/// Returns true if the token is equal to "DELETE".
-pub fn is_delete(token: &str) -> bool {
-    token == "DELETE"
-}
-
-/// Returns true if the token is equal to "SET".
-pub fn is_set(token: &str) -> bool {
-    token == "SET"
-}
-
-/// Returns true if the token is equal to "VALUES".
-pub fn is_values(token: &str) -> bool {
-    token == "VALUES"
+#[cfg(test)]
+pub(crate) fn temp_folder(name: &str, tables: &[&str]) -> String {
+    let folder = std::env::temp_dir().join(format!("rustic_sql_{}_{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&folder);
+    fs::create_dir_all(&folder).unwrap();
+    for table in tables {
+        let file_name = format!("{}.csv", table);
+        fs::copy(Path::new("tablas").join(&file_name), folder.join(file_name)).unwrap();
+    }
+    folder.to_string_lossy().to_string()
 }
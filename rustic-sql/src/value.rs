@@ -0,0 +1,503 @@
+use crate::errors::SqlError;
+use std::{cmp::Ordering, fmt::Display};
+
+/// Enum for the values stored in the columns of a table.
+///
+/// - `Integer`: Whole number, such as `30` or `-4`.
+/// - `Float`: Decimal number, such as `2.5` or `-0.75`.
+/// - `Text`: String of characters.
+/// - `Boolean`: `TRUE` or `FALSE`.
+/// - `Null`: Absence of a value.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Integer(i64),
+    Float(f64),
+    Text(String),
+    Boolean(bool),
+    Null,
+}
+
+impl Value {
+    /// Types a literal written in a query.
+    ///
+    /// Numbers become `Integer` or `Float` (negatives included), `TRUE` and `FALSE` become `Boolean`, `NULL` becomes `Null` and anything else is `Text`.
+    /// A quoted string is always `Text`, without its quotes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use rustic_sql::Value;
+    /// assert_eq!(Value::from_literal("-3"), Value::Integer(-3));
+    /// assert_eq!(Value::from_literal("'3'"), Value::Text(String::from("3")));
+    /// assert_eq!(Value::from_literal("2.5"), Value::Float(2.5));
+    /// assert_eq!(Value::from_literal("TRUE"), Value::Boolean(true));
+    /// assert_eq!(Value::from_literal("Alen"), Value::Text(String::from("Alen")));
+    /// ```
+    ///
+    pub fn from_literal(token: &str) -> Self {
+        if let Some(text) = unquote(token) {
+            return Value::Text(text);
+        }
+        if let Ok(integer) = token.parse::<i64>() {
+            return Value::Integer(integer);
+        }
+        if is_float(token) {
+            if let Ok(float) = token.parse::<f64>() {
+                return Value::Float(float);
+            }
+        }
+        match token.to_uppercase().as_str() {
+            "TRUE" => Value::Boolean(true),
+            "FALSE" => Value::Boolean(false),
+            "NULL" => Value::Null,
+            _ => Value::Text(token.to_string()),
+        }
+    }
+
+    /// Types a value read from a table file.
+    ///
+    /// A field is an `Integer`, a `Float` or a `Boolean` only if it is written the same way as the value, so rewriting a table doesn't change the fields that were not modified.
+    /// A number or a boolean written differently, like `007`, `10.50`, `1e3` or `true`, is kept as `Text` and typed with `typed` when it is compared or used in an operation.
+    /// Anything else is `Text`, including `NULL` and quoted literals like `'3'`.
+    /// An empty field is an empty `Text`, since `Null` values are written as empty fields without quotes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use rustic_sql::Value;
+    /// assert_eq!(Value::from_csv("7"), Value::Integer(7));
+    /// assert_eq!(Value::from_csv("007"), Value::Text(String::from("007")));
+    /// assert_eq!(Value::from_csv("NULL"), Value::Text(String::from("NULL")));
+    /// ```
+    ///
+    pub fn from_csv(field: &str) -> Self {
+        match Value::parse_field(field) {
+            value if value.to_string() == field => value,
+            _ => Value::Text(field.to_string()),
+        }
+    }
+
+    /// Returns the number or boolean that a `Text` stands for, like `Integer(7)` for `007` or `Boolean(true)` for `true`.
+    /// Texts that are not a number or a boolean, and values of other types, are returned as they are.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use rustic_sql::Value;
+    /// assert_eq!(Value::Text(String::from("10.50")).typed(), Value::Float(10.5));
+    /// assert_eq!(Value::Text(String::from("x")).typed(), Value::Text(String::from("x")));
+    /// ```
+    ///
+    pub fn typed(&self) -> Value {
+        match self {
+            Value::Text(text) => Value::parse_field(text),
+            value => value.clone(),
+        }
+    }
+
+    /// Types a field by parsing it, as a number, a boolean in any case, or else a `Text`.
+    fn parse_field(field: &str) -> Self {
+        if let Ok(integer) = field.parse::<i64>() {
+            return Value::Integer(integer);
+        }
+        if is_float(field) {
+            if let Ok(float) = field.parse::<f64>() {
+                return Value::Float(float);
+            }
+        }
+        if field.eq_ignore_ascii_case("TRUE") {
+            Value::Boolean(true)
+        } else if field.eq_ignore_ascii_case("FALSE") {
+            Value::Boolean(false)
+        } else {
+            Value::Text(field.to_string())
+        }
+    }
+
+    /// Types a field read by `Csv::parse_fields`, where `None` is a `Null` value. A missing field is also `Null`.
+    pub fn from_field(field: Option<&Option<String>>) -> Self {
+        match field {
+            Some(Some(field)) => Value::from_csv(field),
+            _ => Value::Null,
+        }
+    }
+
+    /// Returns true if the value is `Null`.
+    pub fn is_null(&self) -> bool {
+        matches!(self, Value::Null)
+    }
+
+    /// Compares two values taking their types into account.
+    ///
+    /// Numbers are compared numerically (an `Integer` can be compared with a `Float`), texts alphabetically and booleans with `FALSE` before `TRUE`.
+    /// A text that stands for a number or a boolean is compared with `typed` when the other value is not a text, or when both texts stand for one.
+    ///
+    /// Returns `None` if one of the values is `Null` or if the types can't be compared.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use rustic_sql::Value;
+    /// # use std::cmp::Ordering;
+    /// let result = Value::Integer(30).compare(&Value::Integer(9));
+    /// assert_eq!(result, Some(Ordering::Greater));
+    /// ```
+    ///
+    pub fn compare(&self, other: &Value) -> Option<Ordering> {
+        match (self, other) {
+            (Value::Integer(a), Value::Integer(b)) => Some(a.cmp(b)),
+            (Value::Integer(a), Value::Float(b)) => (*a as f64).partial_cmp(b),
+            (Value::Float(a), Value::Integer(b)) => a.partial_cmp(&(*b as f64)),
+            (Value::Float(a), Value::Float(b)) => a.partial_cmp(b),
+            (Value::Text(a), Value::Text(b)) => match (self.typed(), other.typed()) {
+                (Value::Text(_), _) | (_, Value::Text(_)) => Some(a.cmp(b)),
+                (a, b) => a.compare(&b),
+            },
+            (Value::Boolean(a), Value::Boolean(b)) => Some(a.cmp(b)),
+            (Value::Text(_), _) | (_, Value::Text(_)) => match (self.typed(), other.typed()) {
+                (Value::Text(_), _) | (_, Value::Text(_)) => None,
+                (a, b) => a.compare(&b),
+            },
+            _ => None,
+        }
+    }
+
+    /// Total order used to sort values.
+    ///
+    /// Values that can be compared use `compare`. Otherwise, `Null` goes first, then booleans, numbers and texts.
+    ///
+    pub fn sort_cmp(&self, other: &Value) -> Ordering {
+        self.compare(other)
+            .unwrap_or_else(|| self.type_rank().cmp(&other.type_rank()))
+    }
+
+    /// Returns the name of the type of the value, as it is declared in `CREATE TABLE`, or `NULL`.
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Value::Null => "NULL",
+            Value::Boolean(_) => "BOOLEAN",
+            Value::Integer(_) => "INTEGER",
+            Value::Float(_) => "FLOAT",
+            Value::Text(_) => "TEXT",
+        }
+    }
+
+    fn type_rank(&self) -> u8 {
+        match self {
+            Value::Null => 0,
+            Value::Boolean(_) => 1,
+            Value::Integer(_) | Value::Float(_) => 2,
+            Value::Text(_) => 3,
+        }
+    }
+
+    /// Adds two numbers. Returns `Null` if one of them is `Null`.
+    pub fn add(&self, other: &Value) -> Result<Value, SqlError> {
+        self.arithmetic(other, i64::checked_add, |a, b| a + b)
+    }
+
+    /// Subtracts two numbers. Returns `Null` if one of them is `Null`.
+    pub fn sub(&self, other: &Value) -> Result<Value, SqlError> {
+        self.arithmetic(other, i64::checked_sub, |a, b| a - b)
+    }
+
+    /// Multiplies two numbers. Returns `Null` if one of them is `Null`.
+    pub fn mul(&self, other: &Value) -> Result<Value, SqlError> {
+        self.arithmetic(other, i64::checked_mul, |a, b| a * b)
+    }
+
+    /// Divides two numbers. Returns `Null` if one of them is `Null` and `SqlError::DivisionByZero` if the divisor is zero.
+    ///
+    /// The division of two integers is an integer division.
+    ///
+    pub fn div(&self, other: &Value) -> Result<Value, SqlError> {
+        if other.is_zero() {
+            return Err(SqlError::DivisionByZero);
+        }
+        self.arithmetic(other, i64::checked_div, |a, b| a / b)
+    }
+
+    /// Returns the remainder of dividing two numbers. Returns `Null` if one of them is `Null` and `SqlError::DivisionByZero` if the divisor is zero.
+    pub fn rem(&self, other: &Value) -> Result<Value, SqlError> {
+        if other.is_zero() {
+            return Err(SqlError::DivisionByZero);
+        }
+        self.arithmetic(other, i64::checked_rem, |a, b| a % b)
+    }
+
+    fn is_zero(&self) -> bool {
+        match self.typed() {
+            Value::Integer(a) => a == 0,
+            Value::Float(a) => a == 0.0,
+            _ => false,
+        }
+    }
+
+    /// Applies an operation to two numbers.
+    ///
+    /// A text that stands for a number is typed with `typed`.
+    /// Returns `SqlError::Overflow` if the result of two integers doesn't fit in an integer, and `SqlError::TypeMismatch` if one of the values is not a number.
+    ///
+    fn arithmetic(
+        &self,
+        other: &Value,
+        integer_op: fn(i64, i64) -> Option<i64>,
+        float_op: fn(f64, f64) -> f64,
+    ) -> Result<Value, SqlError> {
+        match (self, other) {
+            (Value::Null, _) | (_, Value::Null) => Ok(Value::Null),
+            (Value::Integer(a), Value::Integer(b)) => integer_op(*a, *b)
+                .map(Value::Integer)
+                .ok_or(SqlError::Overflow),
+            (Value::Integer(a), Value::Float(b)) => Ok(Value::Float(float_op(*a as f64, *b))),
+            (Value::Float(a), Value::Integer(b)) => Ok(Value::Float(float_op(*a, *b as f64))),
+            (Value::Float(a), Value::Float(b)) => Ok(Value::Float(float_op(*a, *b))),
+            (Value::Text(_), _) | (_, Value::Text(_))
+                if self.typed() != *self || other.typed() != *other =>
+            {
+                self.typed()
+                    .arithmetic(&other.typed(), integer_op, float_op)
+            }
+            _ => Err(SqlError::TypeMismatch {
+                left: self.type_name().to_string(),
+                right: other.type_name().to_string(),
+            }),
+        }
+    }
+}
+
+/// Returns the text of a quoted string, with its escaped quotes (`''`) unescaped, or `None` if the token is not quoted.
+pub fn unquote(token: &str) -> Option<String> {
+    let text = token.strip_prefix('\'')?.strip_suffix('\'')?;
+    Some(text.replace("''", "'"))
+}
+
+/// Returns true if the token is a decimal number, with a fraction like `2.5` or an exponent like `1e3`.
+fn is_float(token: &str) -> bool {
+    let is_digits = |s: &str| s.chars().all(|c| c.is_ascii_digit());
+    let unsigned = token.strip_prefix(['-', '+']).unwrap_or(token);
+    let (mantissa, exponent) = match unsigned.split_once(['e', 'E']) {
+        Some((mantissa, exponent)) => (mantissa, Some(exponent)),
+        None => (unsigned, None),
+    };
+    let (whole, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+
+    let valid_mantissa =
+        (!whole.is_empty() || !fraction.is_empty()) && is_digits(whole) && is_digits(fraction);
+    let valid_exponent = exponent.is_none_or(|exponent| {
+        let digits = exponent.strip_prefix(['-', '+']).unwrap_or(exponent);
+        !digits.is_empty() && is_digits(digits)
+    });
+    valid_mantissa && valid_exponent && (mantissa.contains('.') || exponent.is_some())
+}
+
+impl Display for Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Value::Integer(integer) => write!(f, "{}", integer),
+            Value::Float(float) if float.fract() == 0.0 && float.is_finite() => {
+                write!(f, "{:.1}", float)
+            }
+            Value::Float(float) => write!(f, "{}", float),
+            Value::Text(text) => write!(f, "{}", text),
+            Value::Boolean(true) => write!(f, "TRUE"),
+            Value::Boolean(false) => write!(f, "FALSE"),
+            Value::Null => write!(f, ""),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Value;
+    use crate::errors::SqlError;
+    use std::cmp::Ordering;
+
+    #[test]
+    fn literals() {
+        assert_eq!(Value::from_literal("30"), Value::Integer(30));
+        assert_eq!(Value::from_literal("-30"), Value::Integer(-30));
+        assert_eq!(Value::from_literal("2.5"), Value::Float(2.5));
+        assert_eq!(Value::from_literal("-.5"), Value::Float(-0.5));
+        assert_eq!(Value::from_literal("false"), Value::Boolean(false));
+        assert_eq!(Value::from_literal("NULL"), Value::Null);
+        assert_eq!(
+            Value::from_literal("1.2.3"),
+            Value::Text(String::from("1.2.3"))
+        );
+        assert_eq!(Value::from_literal("-"), Value::Text(String::from("-")));
+        assert_eq!(Value::from_literal("inf"), Value::Text(String::from("inf")));
+        assert_eq!(
+            Value::from_literal("'it''s'"),
+            Value::Text(String::from("it's"))
+        );
+        assert_eq!(
+            Value::from_literal("'NULL'"),
+            Value::Text(String::from("NULL"))
+        );
+    }
+
+    #[test]
+    fn csv_fields() {
+        assert_eq!(Value::from_csv(""), Value::Text(String::new()));
+        assert_eq!(Value::from_csv("NULL"), Value::Text(String::from("NULL")));
+        assert_eq!(Value::from_csv("Pérez"), Value::Text(String::from("Pérez")));
+        assert_eq!(Value::from_csv("7"), Value::Integer(7));
+        assert_eq!(Value::from_csv("2.5"), Value::Float(2.5));
+        assert_eq!(Value::from_csv("1000.0"), Value::Float(1000.0));
+        assert_eq!(Value::from_csv("TRUE"), Value::Boolean(true));
+        assert_eq!(Value::from_csv("1e"), Value::Text(String::from("1e")));
+        assert_eq!(Value::from_csv("inf"), Value::Text(String::from("inf")));
+        assert_eq!(Value::from_csv("1.2.3"), Value::Text(String::from("1.2.3")));
+        let typed = |field: &str| {
+            let value = Value::from_csv(field);
+            assert_eq!(value, Value::Text(field.to_string()));
+            value.typed()
+        };
+        assert_eq!(typed("007"), Value::Integer(7));
+        assert_eq!(typed("2.50"), Value::Float(2.5));
+        assert_eq!(typed("-.5"), Value::Float(-0.5));
+        assert_eq!(typed("1e3"), Value::Float(1000.0));
+        assert_eq!(typed("1E-2"), Value::Float(0.01));
+        assert_eq!(typed("false"), Value::Boolean(false));
+        assert_eq!(Value::from_field(Some(&None)), Value::Null);
+        assert_eq!(Value::from_field(None), Value::Null);
+    }
+
+    #[test]
+    fn compare_numbers() {
+        assert_eq!(
+            Value::Integer(30).compare(&Value::Integer(9)),
+            Some(Ordering::Greater)
+        );
+        assert_eq!(
+            Value::Integer(2).compare(&Value::Float(2.5)),
+            Some(Ordering::Less)
+        );
+        assert_eq!(
+            Value::Float(2.0).compare(&Value::Integer(2)),
+            Some(Ordering::Equal)
+        );
+    }
+
+    #[test]
+    fn compare_incompatible() {
+        assert_eq!(
+            Value::Integer(1).compare(&Value::Text(String::from("uno"))),
+            None
+        );
+        assert_eq!(Value::Null.compare(&Value::Null), None);
+    }
+
+    #[test]
+    fn compare_texts_that_stand_for_numbers() {
+        let text = |text: &str| Value::Text(text.to_string());
+
+        assert_eq!(
+            text("007").compare(&Value::Integer(7)),
+            Some(Ordering::Equal)
+        );
+        assert_eq!(
+            text("10.50").compare(&text("9.25")),
+            Some(Ordering::Greater)
+        );
+        assert_eq!(text("10.50").compare(&text("9.25a")), Some(Ordering::Less));
+        assert_eq!(
+            text("true").compare(&Value::Boolean(true)),
+            Some(Ordering::Equal)
+        );
+        assert_eq!(
+            text("10.50").mul(&Value::Integer(2)),
+            Ok(Value::Float(21.0))
+        );
+        assert_eq!(
+            text("x").add(&Value::Integer(1)),
+            Err(SqlError::TypeMismatch {
+                left: String::from("TEXT"),
+                right: String::from("INTEGER"),
+            })
+        );
+    }
+
+    #[test]
+    fn sort_mixed_types() {
+        let mut values = vec![
+            Value::Text(String::from("a")),
+            Value::Integer(10),
+            Value::Null,
+            Value::Float(9.5),
+        ];
+        values.sort_by(|a, b| a.sort_cmp(b));
+
+        assert_eq!(
+            values,
+            vec![
+                Value::Null,
+                Value::Float(9.5),
+                Value::Integer(10),
+                Value::Text(String::from("a")),
+            ]
+        );
+    }
+
+    #[test]
+    fn arithmetic() {
+        assert_eq!(
+            Value::Integer(1).add(&Value::Integer(2)),
+            Ok(Value::Integer(3))
+        );
+        assert_eq!(
+            Value::Integer(1).sub(&Value::Float(0.5)),
+            Ok(Value::Float(0.5))
+        );
+        assert_eq!(
+            Value::Integer(7).div(&Value::Integer(2)),
+            Ok(Value::Integer(3))
+        );
+        assert_eq!(
+            Value::Integer(7).rem(&Value::Integer(2)),
+            Ok(Value::Integer(1))
+        );
+        assert_eq!(
+            Value::Float(1.5).mul(&Value::Integer(2)),
+            Ok(Value::Float(3.0))
+        );
+        assert_eq!(Value::Null.add(&Value::Integer(2)), Ok(Value::Null));
+    }
+
+    #[test]
+    fn arithmetic_errors() {
+        assert_eq!(
+            Value::Integer(1).div(&Value::Integer(0)),
+            Err(SqlError::DivisionByZero)
+        );
+        assert_eq!(
+            Value::Float(1.5).rem(&Value::Float(0.0)),
+            Err(SqlError::DivisionByZero)
+        );
+        assert_eq!(
+            Value::Text(String::from("a")).add(&Value::Integer(1)),
+            Err(SqlError::TypeMismatch {
+                left: String::from("TEXT"),
+                right: String::from("INTEGER")
+            })
+        );
+        assert_eq!(
+            Value::Integer(i64::MAX).add(&Value::Integer(1)),
+            Err(SqlError::Overflow)
+        );
+        assert_eq!(
+            Value::Integer(i64::MIN).div(&Value::Integer(-1)),
+            Err(SqlError::Overflow)
+        );
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(Value::Float(3.0).to_string(), "3.0");
+        assert_eq!(Value::Float(2.25).to_string(), "2.25");
+        assert_eq!(Value::Boolean(true).to_string(), "TRUE");
+        assert_eq!(Value::Null.to_string(), "");
+    }
+}
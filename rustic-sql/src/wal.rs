@@ -0,0 +1,720 @@
+use crate::{
+    csv::Csv,
+    errors::SqlError,
+    storage::{lock_tables, sync_folder, write_new_file, LockMode},
+};
+use std::{
+    fs::{self, File, OpenOptions, TryLockError},
+    io::{BufReader, ErrorKind, Read, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+    process,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Duration,
+};
+
+/// Number of operations started by this process, used to give each one a unique name.
+static OPERATIONS: AtomicUsize = AtomicUsize::new(0);
+
+/// Name of the folder, inside the folder of the tables, where the write-ahead log is kept.
+pub const LOG_FOLDER: &str = ".wal";
+
+/// Extension of the log of an operation while its effects are being recorded.
+const PENDING_EXTENSION: &str = "tmp";
+
+/// Extension of the log of an operation whose effects are all recorded, which can be applied to the tables.
+const COMMITTED_EXTENSION: &str = "log";
+
+/// Enum for the changes that an operation makes to the files of a folder, like the file or the schema of a table.
+///
+/// The data of the changes that write a file is kept in a file of the log folder until the change is applied.
+///
+/// - `Append`: the data file is written at the given offset of the file, which was its length when the operation started.
+/// - `Replace`: the data file replaces the file, or becomes it if the file doesn't exist.
+/// - `Remove`: the file is removed.
+/// - `Rename`: the file is renamed.
+///
+#[derive(Debug, PartialEq)]
+enum Effect {
+    Append {
+        file_name: String,
+        offset: u64,
+        data_file: String,
+    },
+    Replace {
+        file_name: String,
+        data_file: String,
+    },
+    Remove {
+        file_name: String,
+    },
+    Rename {
+        file_name: String,
+        new_file_name: String,
+    },
+}
+
+impl Effect {
+    /// Returns the names of the files of the folder that the effect changes.
+    fn file_names(&self) -> Vec<&str> {
+        match self {
+            Effect::Append { file_name, .. }
+            | Effect::Replace { file_name, .. }
+            | Effect::Remove { file_name } => vec![file_name],
+            Effect::Rename {
+                file_name,
+                new_file_name,
+            } => vec![file_name, new_file_name],
+        }
+    }
+
+    fn data_file(&self) -> Option<&str> {
+        match self {
+            Effect::Append { data_file, .. } | Effect::Replace { data_file, .. } => Some(data_file),
+            Effect::Remove { .. } | Effect::Rename { .. } => None,
+        }
+    }
+
+    /// Returns the fields of the record that holds the effect in a log.
+    fn to_record(&self) -> Vec<String> {
+        match self {
+            Effect::Append {
+                file_name,
+                offset,
+                data_file,
+            } => vec![
+                String::from("append"),
+                file_name.to_string(),
+                offset.to_string(),
+                data_file.to_string(),
+            ],
+            Effect::Replace {
+                file_name,
+                data_file,
+            } => vec![
+                String::from("replace"),
+                file_name.to_string(),
+                data_file.to_string(),
+            ],
+            Effect::Remove { file_name } => vec![String::from("remove"), file_name.to_string()],
+            Effect::Rename {
+                file_name,
+                new_file_name,
+            } => vec![
+                String::from("rename"),
+                file_name.to_string(),
+                new_file_name.to_string(),
+            ],
+        }
+    }
+
+    /// Reads an effect from the fields of a record written by `to_record`.
+    ///
+    /// Returns `SqlError::Error` if the record is not one of an effect.
+    ///
+    fn from_record(fields: &[String]) -> Result<Self, SqlError> {
+        match fields {
+            [kind, file_name, offset, data_file] if kind == "append" => Ok(Effect::Append {
+                file_name: file_name.to_string(),
+                offset: offset.parse().map_err(|_| SqlError::Error)?,
+                data_file: data_file.to_string(),
+            }),
+            [kind, file_name, data_file] if kind == "replace" => Ok(Effect::Replace {
+                file_name: file_name.to_string(),
+                data_file: data_file.to_string(),
+            }),
+            [kind, file_name] if kind == "remove" => Ok(Effect::Remove {
+                file_name: file_name.to_string(),
+            }),
+            [kind, file_name, new_file_name] if kind == "rename" => Ok(Effect::Rename {
+                file_name: file_name.to_string(),
+                new_file_name: new_file_name.to_string(),
+            }),
+            _ => Err(SqlError::Error),
+        }
+    }
+
+    /// Applies the effect to its file.
+    ///
+    /// Applying an effect again gives the same file, so a log can be replayed after a crash in the middle of it.
+    /// The source of an effect, which is its data file or the file that it removes or renames, is gone once the effect was applied, so an effect without its source is skipped.
+    ///
+    fn apply(&self, folder_path: &str, log_folder: &Path) -> Result<(), SqlError> {
+        let folder = Path::new(folder_path);
+
+        let (result, source) = match self {
+            Effect::Append {
+                file_name,
+                offset,
+                data_file,
+            } => {
+                let data_path = log_folder.join(data_file);
+                let result = fs::read(&data_path).and_then(|data| {
+                    let mut file = OpenOptions::new()
+                        .write(true)
+                        .open(folder.join(file_name))?;
+                    file.set_len(*offset)?;
+                    file.seek(SeekFrom::Start(*offset))?;
+                    file.write_all(&data)?;
+                    file.sync_all()
+                });
+                (result, data_path)
+            }
+            Effect::Replace {
+                file_name,
+                data_file,
+            } => {
+                let data_path = log_folder.join(data_file);
+                (fs::rename(&data_path, folder.join(file_name)), data_path)
+            }
+            Effect::Remove { file_name } => {
+                let path = folder.join(file_name);
+                (fs::remove_file(&path), path)
+            }
+            Effect::Rename {
+                file_name,
+                new_file_name,
+            } => {
+                let path = folder.join(file_name);
+                (fs::rename(&path, folder.join(new_file_name)), path)
+            }
+        };
+        match result {
+            Err(e) if e.kind() == ErrorKind::NotFound && !source.exists() => Ok(()),
+            result => result.map_err(SqlError::from),
+        }
+    }
+}
+
+/// Returns the name of the table that a file of the folder belongs to, which is the file name without its extension.
+fn table_of_file(file_name: &str) -> &str {
+    file_name
+        .rsplit_once('.')
+        .map_or(file_name, |(table_name, _)| table_name)
+}
+
+fn table_file(table_name: &str) -> String {
+    format!("{}.csv", table_name)
+}
+
+/// Struct that represents a change to the tables of a folder that is recorded in the write-ahead log before any table is touched.
+///
+/// The effects of the operation, on the files of the tables and on their schemas, are recorded in a log file of the `.wal` folder, with their data in files next to it.
+/// `commit` flushes the log to disk, applies the effects and then removes the log. If the process stops before the log is removed, `recover_log` applies the effects again when the database is opened.
+/// An operation that is dropped without a commit is discarded, since none of its effects reached the tables.
+///
+/// The log file stays locked until the operation ends, which tells `recover_log` that it is still running.
+/// The tables of the operation must be locked with an exclusive lock while it runs.
+///
+/// # Fields
+///
+/// * `folder_path` - The folder of the tables.
+/// * `name` - The unique name of the operation, which starts the names of its files.
+/// * `log` - The log file, locked with an exclusive lock.
+/// * `effects` - The effects recorded so far.
+/// * `committed` - Whether the log was committed, after which its files are left for `recover_log` if the commit fails.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use rustic_sql::wal::Operation;
+/// let mut operation = Operation::start("tablas").unwrap();
+/// operation.append("clientes", &["4,María,Rodríguez,maria@email.com"]).unwrap();
+/// operation.commit().unwrap();
+/// ```
+///
+#[derive(Debug)]
+pub struct Operation {
+    folder_path: String,
+    name: String,
+    log: File,
+    effects: Vec<Effect>,
+    committed: bool,
+}
+
+impl Operation {
+    /// Starts an operation on the tables of a folder, creating its log file.
+    ///
+    /// # Arguments
+    ///
+    /// * `folder_path` - The folder of the tables.
+    ///
+    pub fn start(folder_path: &str) -> Result<Self, SqlError> {
+        let log_folder = log_folder(folder_path);
+        fs::create_dir_all(&log_folder).map_err(SqlError::from)?;
+
+        loop {
+            let name = format!(
+                "{}-{}",
+                process::id(),
+                OPERATIONS.fetch_add(1, Ordering::Relaxed)
+            );
+            let path = log_folder.join(format!("{}.{}", name, PENDING_EXTENSION));
+            let log = match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(log) => log,
+                Err(e) if e.kind() == ErrorKind::AlreadyExists => continue,
+                Err(e) => return Err(SqlError::from(e)),
+            };
+            // `recover_log` may take the lock of the new file before this process, and then it removes the file.
+            match log.try_lock() {
+                Ok(()) => {}
+                Err(TryLockError::WouldBlock) => continue,
+                Err(TryLockError::Error(e)) => return Err(SqlError::from(e)),
+            }
+            // It may also have removed the file between its creation and the lock, and the lock is then held on a file that is not in the log folder anymore.
+            if !path.exists() {
+                continue;
+            }
+
+            let operation = Self {
+                folder_path: folder_path.to_string(),
+                name,
+                log,
+                effects: Vec::new(),
+                committed: false,
+            };
+            // A process with the same id that stopped may have left a log with the same name.
+            if operation.log_path(COMMITTED_EXTENSION).exists() {
+                continue;
+            }
+            return Ok(operation);
+        }
+    }
+
+    fn log_folder(&self) -> PathBuf {
+        log_folder(&self.folder_path)
+    }
+
+    fn log_path(&self, extension: &str) -> PathBuf {
+        self.log_folder()
+            .join(format!("{}.{}", self.name, extension))
+    }
+
+    fn next_data_file(&self) -> String {
+        format!("{}.{}.csv", self.name, self.effects.len())
+    }
+
+    /// Records that the given lines are appended to the file of a table.
+    ///
+    /// If the last line of the file has no line break, one is added before the new lines so they don't join it.
+    ///
+    /// # Arguments
+    ///
+    /// * `table_name` - The name of the table.
+    /// * `lines` - The lines to append, without their line breaks.
+    ///
+    pub fn append<S: AsRef<str>>(&mut self, table_name: &str, lines: &[S]) -> Result<(), SqlError> {
+        let file_name = table_file(table_name);
+        let mut table =
+            File::open(Path::new(&self.folder_path).join(&file_name)).map_err(SqlError::from)?;
+        let offset = table.metadata().map_err(SqlError::from)?.len();
+
+        let mut data: Vec<&str> = Vec::new();
+        if offset > 0 {
+            let mut last = [0];
+            table.seek(SeekFrom::End(-1)).map_err(SqlError::from)?;
+            table.read_exact(&mut last).map_err(SqlError::from)?;
+            if last[0] != b'\n' {
+                data.push("");
+            }
+        }
+        data.extend(lines.iter().map(AsRef::as_ref));
+
+        let data_file = self.next_data_file();
+        write_new_file(&self.log_folder().join(&data_file), &data)?;
+        self.effects.push(Effect::Append {
+            file_name,
+            offset,
+            data_file,
+        });
+        Ok(())
+    }
+
+    /// Records that the file of a table is replaced with the given lines.
+    ///
+    /// # Arguments
+    ///
+    /// * `table_name` - The name of the table.
+    /// * `lines` - The new lines of the table file, without their line breaks.
+    ///
+    pub fn replace<S: AsRef<str>>(
+        &mut self,
+        table_name: &str,
+        lines: &[S],
+    ) -> Result<(), SqlError> {
+        self.replace_file(&table_file(table_name), lines)
+    }
+
+    /// Records that a file of the folder, like the schema of a table, is replaced with the given lines, or created with them if it doesn't exist.
+    ///
+    /// # Arguments
+    ///
+    /// * `file_name` - The name of the file, with its extension.
+    /// * `lines` - The new lines of the file, without their line breaks.
+    ///
+    pub fn replace_file<S: AsRef<str>>(
+        &mut self,
+        file_name: &str,
+        lines: &[S],
+    ) -> Result<(), SqlError> {
+        let data_file = self.next_data_file();
+        write_new_file(&self.log_folder().join(&data_file), lines)?;
+        self.effects.push(Effect::Replace {
+            file_name: file_name.to_string(),
+            data_file,
+        });
+        Ok(())
+    }
+
+    /// Records that the file of a table is replaced with another file, which is moved to the log folder.
+    ///
+    /// The file must be in the same file system as the log folder, and already flushed to disk.
+    ///
+    /// # Arguments
+    ///
+    /// * `table_name` - The name of the table.
+    /// * `path` - The path of the file that replaces the table file.
+    ///
+    pub fn replace_with_file(&mut self, table_name: &str, path: &Path) -> Result<(), SqlError> {
+        let data_file = self.next_data_file();
+        fs::rename(path, self.log_folder().join(&data_file)).map_err(SqlError::from)?;
+        self.effects.push(Effect::Replace {
+            file_name: table_file(table_name),
+            data_file,
+        });
+        Ok(())
+    }
+
+    /// Records that a file of the folder is removed.
+    ///
+    /// # Arguments
+    ///
+    /// * `file_name` - The name of the file, with its extension.
+    ///
+    pub fn remove_file(&mut self, file_name: &str) {
+        self.effects.push(Effect::Remove {
+            file_name: file_name.to_string(),
+        });
+    }
+
+    /// Records that a file of the folder is renamed.
+    ///
+    /// # Arguments
+    ///
+    /// * `file_name` - The name of the file, with its extension.
+    /// * `new_file_name` - The new name of the file, with its extension.
+    ///
+    pub fn rename_file(&mut self, file_name: &str, new_file_name: &str) {
+        self.effects.push(Effect::Rename {
+            file_name: file_name.to_string(),
+            new_file_name: new_file_name.to_string(),
+        });
+    }
+
+    /// Writes the recorded effects to the log, flushing it to disk, and then applies them to the tables.
+    ///
+    /// Once the log is flushed, the operation is kept even if applying its effects fails, and `recover_log` finishes it.
+    ///
+    pub fn commit(mut self) -> Result<(), SqlError> {
+        if self.effects.is_empty() {
+            return Ok(());
+        }
+        let csv = Csv::default();
+        let mut content = String::new();
+        for effect in &self.effects {
+            content.push_str(&csv.format_record(&effect.to_record()));
+            content.push('\n');
+        }
+        self.log
+            .write_all(content.as_bytes())
+            .map_err(SqlError::from)?;
+        self.log.sync_all().map_err(SqlError::from)?;
+
+        let log_path = self.log_path(COMMITTED_EXTENSION);
+        fs::rename(self.log_path(PENDING_EXTENSION), &log_path).map_err(SqlError::from)?;
+        sync_folder(&self.log_folder())?;
+        self.committed = true;
+
+        apply_log(&self.folder_path, &log_path, &self.effects)
+    }
+}
+
+impl Drop for Operation {
+    fn drop(&mut self) {
+        if !self.committed {
+            for data_file in self.effects.iter().filter_map(Effect::data_file) {
+                let _ = fs::remove_file(self.log_folder().join(data_file));
+            }
+            let _ = fs::remove_file(self.log_path(PENDING_EXTENSION));
+        }
+    }
+}
+
+fn log_folder(folder_path: &str) -> PathBuf {
+    Path::new(folder_path).join(LOG_FOLDER)
+}
+
+/// Applies the effects of a committed log and then removes the log with its data files.
+///
+/// The tables are flushed to disk before the log is removed, and the removal is flushed too, so a log is never replayed over later changes of its tables.
+///
+fn apply_log(folder_path: &str, log_path: &Path, effects: &[Effect]) -> Result<(), SqlError> {
+    let log_folder = log_path.parent().ok_or(SqlError::Error)?;
+    for effect in effects {
+        effect.apply(folder_path, log_folder)?;
+    }
+    sync_folder(Path::new(folder_path))?;
+
+    for data_file in effects.iter().filter_map(Effect::data_file) {
+        match fs::remove_file(log_folder.join(data_file)) {
+            Err(e) if e.kind() != ErrorKind::NotFound => return Err(SqlError::from(e)),
+            _ => {}
+        }
+    }
+    fs::remove_file(log_path).map_err(SqlError::from)?;
+    sync_folder(log_folder)
+}
+
+/// Finishes the operations that a process left in the write-ahead log of a folder when it stopped.
+///
+/// The effects of a committed log are applied again, since the process may have stopped before applying all of them.
+/// A log that was not committed is removed with its data files, since none of its effects reached the tables.
+/// Operations that are still running hold the lock of their log, and logs whose tables are locked by another process are left for later, so they are not touched.
+///
+/// Returns the number of recovered operations.
+///
+/// # Arguments
+///
+/// * `folder_path` - The folder of the tables.
+///
+pub fn recover_log(folder_path: &str) -> Result<usize, SqlError> {
+    let log_folder = log_folder(folder_path);
+    if !log_folder.is_dir() {
+        return Ok(0);
+    }
+    let mut recovered = 0;
+
+    for entry in fs::read_dir(&log_folder).map_err(SqlError::from)? {
+        let path = entry.map_err(SqlError::from)?.path();
+        let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+        if extension != PENDING_EXTENSION && extension != COMMITTED_EXTENSION {
+            continue;
+        }
+        let log = match File::open(&path) {
+            Ok(log) => log,
+            Err(e) if e.kind() == ErrorKind::NotFound => continue,
+            Err(e) => return Err(SqlError::from(e)),
+        };
+        match log.try_lock() {
+            Ok(()) => {}
+            Err(TryLockError::WouldBlock) => continue,
+            Err(TryLockError::Error(e)) => return Err(SqlError::from(e)),
+        }
+        // An operation removes its log before releasing the lock, so a log that is still there was left by a process that stopped.
+        if !path.exists() {
+            continue;
+        }
+
+        if extension == COMMITTED_EXTENSION {
+            let effects = read_log(log)?;
+            let tables: Vec<(String, LockMode)> = effects
+                .iter()
+                .flat_map(Effect::file_names)
+                .map(|file_name| (table_of_file(file_name).to_string(), LockMode::Exclusive))
+                .collect();
+            let _locks = match lock_tables(folder_path, &tables, Duration::ZERO) {
+                Ok(locks) => locks,
+                Err(SqlError::TableLocked(_)) => continue,
+                Err(e) => return Err(e),
+            };
+            apply_log(folder_path, &path, &effects)?;
+        } else {
+            discard_log(&path)?;
+        }
+        recovered += 1;
+    }
+
+    Ok(recovered)
+}
+
+fn read_log(log: File) -> Result<Vec<Effect>, SqlError> {
+    Csv::default()
+        .records(BufReader::new(log))
+        .map(|record| {
+            let fields: Vec<String> = record?.into_iter().map(Option::unwrap_or_default).collect();
+            Effect::from_record(&fields)
+        })
+        .collect()
+}
+
+/// Removes a log that was not committed, with the data files of its operation.
+fn discard_log(log_path: &Path) -> Result<(), SqlError> {
+    let log_folder = log_path.parent().ok_or(SqlError::Error)?;
+    let name = log_path
+        .file_stem()
+        .ok_or(SqlError::Error)?
+        .to_string_lossy()
+        .to_string();
+    let prefix = format!("{}.", name);
+
+    for entry in fs::read_dir(log_folder).map_err(SqlError::from)? {
+        let entry = entry.map_err(SqlError::from)?;
+        let file_name = entry.file_name().to_string_lossy().to_string();
+        if file_name.starts_with(&prefix) && file_name.ends_with(".csv") {
+            fs::remove_file(entry.path()).map_err(SqlError::from)?;
+        }
+    }
+    fs::remove_file(log_path).map_err(SqlError::from)?;
+    sync_folder(log_folder)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{recover_log, Operation, LOG_FOLDER};
+    use crate::{
+        storage::{lock_table, LockMode},
+        utils::temp_folder,
+    };
+    use std::{fs, path::Path, time::Duration};
+
+    fn log_files(folder: &str) -> Vec<String> {
+        let mut names: Vec<String> = fs::read_dir(Path::new(folder).join(LOG_FOLDER))
+            .unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().to_string())
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn commit_and_discard_operations() {
+        let folder = temp_folder("wal_commit", &[]);
+        let table = Path::new(&folder).join("personas.csv");
+        fs::write(&table, "id,nombre\n1,Ana\n").unwrap();
+
+        let mut operation = Operation::start(&folder).unwrap();
+        operation.append("personas", &["2,Beto"]).unwrap();
+        operation.append("personas", &["3,Carla"]).unwrap();
+        drop(operation);
+        assert_eq!(fs::read_to_string(&table).unwrap(), "id,nombre\n1,Ana\n");
+        assert!(log_files(&folder).is_empty());
+
+        let mut operation = Operation::start(&folder).unwrap();
+        operation.append("personas", &["2,Beto"]).unwrap();
+        operation.commit().unwrap();
+        assert_eq!(
+            fs::read_to_string(&table).unwrap(),
+            "id,nombre\n1,Ana\n2,Beto\n"
+        );
+
+        let mut operation = Operation::start(&folder).unwrap();
+        operation
+            .replace("personas", &["id,nombre", "\"2\",\"Beto, el primo\""])
+            .unwrap();
+        operation.commit().unwrap();
+        assert_eq!(
+            fs::read_to_string(&table).unwrap(),
+            "id,nombre\n\"2\",\"Beto, el primo\"\n"
+        );
+        assert!(log_files(&folder).is_empty());
+
+        fs::remove_dir_all(&folder).unwrap();
+    }
+
+    #[test]
+    fn append_after_a_line_without_break() {
+        let folder = temp_folder("wal_append", &[]);
+        let table = Path::new(&folder).join("personas.csv");
+        fs::write(&table, "id,nombre\n1,Ana").unwrap();
+
+        let mut operation = Operation::start(&folder).unwrap();
+        operation.append("personas", &["2,Beto"]).unwrap();
+        operation.commit().unwrap();
+        let mut operation = Operation::start(&folder).unwrap();
+        operation.append("personas", &["3,Carla"]).unwrap();
+        operation.commit().unwrap();
+        assert_eq!(
+            fs::read_to_string(&table).unwrap(),
+            "id,nombre\n1,Ana\n2,Beto\n3,Carla\n"
+        );
+
+        fs::remove_dir_all(&folder).unwrap();
+    }
+
+    #[test]
+    fn rename_and_remove_files() {
+        let folder = temp_folder("wal_files", &[]);
+        let path = |file_name: &str| Path::new(&folder).join(file_name);
+        fs::write(path("personas.csv"), "id\n").unwrap();
+        fs::write(path("personas.schema"), "id INTEGER\n").unwrap();
+
+        let mut operation = Operation::start(&folder).unwrap();
+        operation.rename_file("personas.csv", "gente.csv");
+        operation.rename_file("personas.schema", "gente.schema");
+        operation.commit().unwrap();
+        assert!(!path("personas.csv").exists() && !path("personas.schema").exists());
+        assert_eq!(
+            fs::read_to_string(path("gente.schema")).unwrap(),
+            "id INTEGER\n"
+        );
+
+        // A process that stopped after renaming the table file, but before renaming its schema.
+        let log_folder = path(LOG_FOLDER);
+        fs::write(
+            log_folder.join("7-0.log"),
+            "rename,gente.csv,amigos.csv\nrename,gente.schema,amigos.schema\n",
+        )
+        .unwrap();
+        fs::rename(path("gente.csv"), path("amigos.csv")).unwrap();
+        assert_eq!(recover_log(&folder), Ok(1));
+        assert!(path("amigos.csv").exists() && path("amigos.schema").exists());
+
+        let mut operation = Operation::start(&folder).unwrap();
+        operation.remove_file("amigos.csv");
+        operation.remove_file("amigos.schema");
+        operation.commit().unwrap();
+        assert!(!path("amigos.csv").exists() && !path("amigos.schema").exists());
+        assert!(log_files(&folder).is_empty());
+
+        fs::remove_dir_all(&folder).unwrap();
+    }
+
+    #[test]
+    fn recover_committed_and_pending_logs() {
+        let folder = temp_folder("wal_recover", &[]);
+        let table = Path::new(&folder).join("personas.csv");
+        fs::write(&table, "id,nombre\n1,Ana\n").unwrap();
+        let log_folder = Path::new(&folder).join(LOG_FOLDER);
+        fs::create_dir(&log_folder).unwrap();
+
+        // A process that stopped after committing its log, half way through appending its rows.
+        fs::write(
+            log_folder.join("7-0.log"),
+            "append,personas.csv,16,7-0.0.csv\n",
+        )
+        .unwrap();
+        fs::write(log_folder.join("7-0.0.csv"), "2,Beto\n3,Carla\n").unwrap();
+        fs::write(&table, "id,nombre\n1,Ana\n2,Be").unwrap();
+        // A process that stopped while recording its effects.
+        fs::write(log_folder.join("7-1.tmp"), "").unwrap();
+        fs::write(log_folder.join("7-1.0.csv"), "id,nombre\n").unwrap();
+
+        let mut running = Operation::start(&folder).unwrap();
+        running.replace("personas", &["id,nombre"]).unwrap();
+
+        let lock = lock_table(&folder, "personas", LockMode::Shared, Duration::ZERO).unwrap();
+        assert_eq!(recover_log(&folder), Ok(1));
+        drop(lock);
+        assert_eq!(recover_log(&folder), Ok(1));
+        assert_eq!(recover_log(&folder), Ok(0));
+        assert_eq!(
+            fs::read_to_string(&table).unwrap(),
+            "id,nombre\n1,Ana\n2,Beto\n3,Carla\n"
+        );
+        assert_eq!(log_files(&folder).len(), 2);
+
+        drop(running);
+        assert!(log_files(&folder).is_empty());
+
+        fs::remove_dir_all(&folder).unwrap();
+    }
+}